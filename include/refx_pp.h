@@ -0,0 +1,274 @@
+/*
+ * C interface of refx-pp, available with the `capi` feature.
+ *
+ * Handles are opaque pointers created by the `*_new` or `*_from_*`
+ * functions and must be released with the matching `*_free` function.
+ * Functions return `false` or a null handle if an argument is null or if the
+ * operation failed.
+ *
+ * Modes are passed as `uint8_t`: 0 for osu!standard, 1 for osu!taiko, 2 for
+ * osu!catch, and 3 for osu!mania. Mods are passed as legacy bitflags.
+ *
+ * Handles must not be used by multiple threads at the same time.
+ *
+ * This header is maintained by hand. Keep it in sync with `src/capi`; the
+ * tests in `src/capi/mod.rs` check that its functions, types, and struct
+ * layouts match the Rust definitions.
+ *
+ * Build the library with
+ *
+ *     cargo rustc --release --features capi --crate-type cdylib
+ *
+ * or `--crate-type staticlib` for a static library.
+ */
+
+#ifndef REFX_PP_H
+#define REFX_PP_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Opaque handles */
+
+typedef struct RefxBeatmap RefxBeatmap;
+typedef struct RefxDifficulty RefxDifficulty;
+typedef struct RefxPerformance RefxPerformance;
+typedef struct RefxGradualDifficulty RefxGradualDifficulty;
+typedef struct RefxGradualPerformance RefxGradualPerformance;
+
+/* Attributes */
+
+typedef struct RefxOsuDifficultyAttributes {
+    double aim;
+    double aim_difficult_slider_count;
+    double speed;
+    double flashlight;
+    double slider_factor;
+    double speed_note_count;
+    double aim_difficult_strain_count;
+    double speed_difficult_strain_count;
+    double ar;
+    double great_hit_window;
+    double ok_hit_window;
+    double meh_hit_window;
+    double hp;
+    uint32_t n_circles;
+    uint32_t n_sliders;
+    uint32_t n_large_ticks;
+    uint32_t n_spinners;
+    double stars;
+    uint32_t max_combo;
+    double aim_top_weighted_slider_factor;
+    double speed_top_weighted_slider_factor;
+    double nested_score_per_object;
+    double legacy_score_base_multiplier;
+    double maximum_legacy_combo_score;
+} RefxOsuDifficultyAttributes;
+
+typedef struct RefxTaikoDifficultyAttributes {
+    double stamina;
+    double rhythm;
+    double color;
+    double reading;
+    double great_hit_window;
+    double ok_hit_window;
+    double mono_stamina_factor;
+    double stars;
+    uint32_t max_combo;
+    bool is_convert;
+} RefxTaikoDifficultyAttributes;
+
+typedef struct RefxCatchDifficultyAttributes {
+    double stars;
+    double ar;
+    uint32_t n_fruits;
+    uint32_t n_droplets;
+    uint32_t n_tiny_droplets;
+    uint32_t max_combo;
+    bool is_convert;
+} RefxCatchDifficultyAttributes;
+
+typedef struct RefxManiaDifficultyAttributes {
+    double stars;
+    uint32_t n_objects;
+    uint32_t n_hold_notes;
+    uint32_t max_combo;
+    bool is_convert;
+} RefxManiaDifficultyAttributes;
+
+/* Only the field of `mode` is filled, all others are zeroed. */
+typedef struct RefxDifficultyAttributes {
+    uint8_t mode;
+    RefxOsuDifficultyAttributes osu;
+    RefxTaikoDifficultyAttributes taiko;
+    RefxCatchDifficultyAttributes catch_;
+    RefxManiaDifficultyAttributes mania;
+} RefxDifficultyAttributes;
+
+/* `speed_deviation` is NaN if unknown. */
+typedef struct RefxOsuPerformanceAttributes {
+    RefxOsuDifficultyAttributes difficulty;
+    double pp;
+    double pp_acc;
+    double pp_aim;
+    double pp_flashlight;
+    double pp_speed;
+    double effective_miss_count;
+    double speed_deviation;
+    double combo_based_estimated_miss_count;
+    double score_based_estimated_miss_count;
+    double aim_estimated_slider_breaks;
+    double speed_estimated_slider_breaks;
+} RefxOsuPerformanceAttributes;
+
+/* `estimated_unstable_rate` is NaN if unknown. */
+typedef struct RefxTaikoPerformanceAttributes {
+    RefxTaikoDifficultyAttributes difficulty;
+    double pp;
+    double pp_acc;
+    double pp_difficulty;
+    double effective_miss_count;
+    double estimated_unstable_rate;
+} RefxTaikoPerformanceAttributes;
+
+typedef struct RefxCatchPerformanceAttributes {
+    RefxCatchDifficultyAttributes difficulty;
+    double pp;
+} RefxCatchPerformanceAttributes;
+
+typedef struct RefxManiaPerformanceAttributes {
+    RefxManiaDifficultyAttributes difficulty;
+    double pp;
+    double pp_difficulty;
+} RefxManiaPerformanceAttributes;
+
+/* Only the field of `mode` is filled, all others are zeroed. */
+typedef struct RefxPerformanceAttributes {
+    uint8_t mode;
+    RefxOsuPerformanceAttributes osu;
+    RefxTaikoPerformanceAttributes taiko;
+    RefxCatchPerformanceAttributes catch_;
+    RefxManiaPerformanceAttributes mania;
+} RefxPerformanceAttributes;
+
+typedef struct RefxScoreState {
+    uint32_t max_combo;
+    uint32_t osu_large_tick_hits;
+    uint32_t osu_small_tick_hits;
+    uint32_t slider_end_hits;
+    uint32_t n_geki;
+    uint32_t n_katu;
+    uint32_t n300;
+    uint32_t n100;
+    uint32_t n50;
+    uint32_t misses;
+} RefxScoreState;
+
+/* Beatmap */
+
+RefxBeatmap *refx_beatmap_from_path(const char *path);
+RefxBeatmap *refx_beatmap_from_bytes(const uint8_t *data, size_t len);
+void refx_beatmap_free(RefxBeatmap *map);
+/* 255 if the handle is null */
+uint8_t refx_beatmap_mode(const RefxBeatmap *map);
+double refx_beatmap_bpm(const RefxBeatmap *map);
+bool refx_beatmap_is_suspicious(const RefxBeatmap *map);
+bool refx_beatmap_convert(RefxBeatmap *map, uint8_t mode, uint32_t mods);
+
+/* Difficulty */
+
+RefxDifficulty *refx_difficulty_new(void);
+void refx_difficulty_free(RefxDifficulty *difficulty);
+bool refx_difficulty_mods(RefxDifficulty *handle, uint32_t mods);
+bool refx_difficulty_passed_objects(RefxDifficulty *handle, uint32_t passed_objects);
+bool refx_difficulty_clock_rate(RefxDifficulty *handle, double clock_rate);
+bool refx_difficulty_ar(RefxDifficulty *handle, float ar, bool with_mods);
+bool refx_difficulty_cs(RefxDifficulty *handle, float cs, bool with_mods);
+bool refx_difficulty_hp(RefxDifficulty *handle, float hp, bool with_mods);
+bool refx_difficulty_od(RefxDifficulty *handle, float od, bool with_mods);
+bool refx_difficulty_hardrock_offsets(RefxDifficulty *handle, bool hardrock_offsets);
+bool refx_difficulty_lazer(RefxDifficulty *handle, bool lazer);
+bool refx_difficulty_calculate(
+    const RefxDifficulty *difficulty,
+    const RefxBeatmap *map,
+    RefxDifficultyAttributes *out
+);
+
+/* Performance */
+
+/* The map must not be freed before the performance handle. */
+RefxPerformance *refx_performance_new(const RefxBeatmap *map);
+void refx_performance_free(RefxPerformance *performance);
+bool refx_performance_mods(RefxPerformance *handle, uint32_t mods);
+bool refx_performance_passed_objects(RefxPerformance *handle, uint32_t passed_objects);
+bool refx_performance_clock_rate(RefxPerformance *handle, double clock_rate);
+bool refx_performance_ar(RefxPerformance *handle, float ar, bool with_mods);
+bool refx_performance_cs(RefxPerformance *handle, float cs, bool with_mods);
+bool refx_performance_hp(RefxPerformance *handle, float hp, bool with_mods);
+bool refx_performance_od(RefxPerformance *handle, float od, bool with_mods);
+bool refx_performance_hardrock_offsets(RefxPerformance *handle, bool hardrock_offsets);
+bool refx_performance_lazer(RefxPerformance *handle, bool lazer);
+bool refx_performance_accuracy(RefxPerformance *handle, double acc);
+bool refx_performance_combo(RefxPerformance *handle, uint32_t combo);
+bool refx_performance_misses(RefxPerformance *handle, uint32_t misses);
+bool refx_performance_n300(RefxPerformance *handle, uint32_t n300);
+bool refx_performance_n100(RefxPerformance *handle, uint32_t n100);
+bool refx_performance_n50(RefxPerformance *handle, uint32_t n50);
+bool refx_performance_n_geki(RefxPerformance *handle, uint32_t n_geki);
+bool refx_performance_n_katu(RefxPerformance *handle, uint32_t n_katu);
+bool refx_performance_large_tick_hits(RefxPerformance *handle, uint32_t large_tick_hits);
+bool refx_performance_small_tick_hits(RefxPerformance *handle, uint32_t small_tick_hits);
+bool refx_performance_slider_end_hits(RefxPerformance *handle, uint32_t slider_end_hits);
+bool refx_performance_legacy_total_score(RefxPerformance *handle, int64_t total_score);
+bool refx_performance_difficulty(RefxPerformance *performance, const RefxDifficulty *difficulty);
+bool refx_performance_mode(RefxPerformance *performance, uint8_t mode);
+bool refx_performance_state(RefxPerformance *performance, const RefxScoreState *state);
+bool refx_performance_generate_state(RefxPerformance *performance, RefxScoreState *out);
+bool refx_performance_calculate(
+    const RefxPerformance *performance,
+    RefxPerformanceAttributes *out
+);
+
+/* Gradual calculation */
+
+RefxGradualDifficulty *refx_gradual_difficulty_new(
+    const RefxDifficulty *difficulty,
+    const RefxBeatmap *map
+);
+void refx_gradual_difficulty_free(RefxGradualDifficulty *gradual);
+bool refx_gradual_difficulty_next(RefxGradualDifficulty *gradual, RefxDifficultyAttributes *out);
+bool refx_gradual_difficulty_nth(
+    RefxGradualDifficulty *gradual,
+    size_t n,
+    RefxDifficultyAttributes *out
+);
+size_t refx_gradual_difficulty_len(const RefxGradualDifficulty *gradual);
+
+RefxGradualPerformance *refx_gradual_performance_new(
+    const RefxDifficulty *difficulty,
+    const RefxBeatmap *map
+);
+void refx_gradual_performance_free(RefxGradualPerformance *gradual);
+bool refx_gradual_performance_next(
+    RefxGradualPerformance *gradual,
+    const RefxScoreState *state,
+    RefxPerformanceAttributes *out
+);
+bool refx_gradual_performance_nth(
+    RefxGradualPerformance *gradual,
+    const RefxScoreState *state,
+    size_t n,
+    RefxPerformanceAttributes *out
+);
+size_t refx_gradual_performance_len(const RefxGradualPerformance *gradual);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* REFX_PP_H */
@@ -0,0 +1,246 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use crate::model::mode::ConvertError;
+
+/// A token to cancel calculations, e.g. from another thread.
+///
+/// Clones of a token share their state so cancelling one cancels all of
+/// them.
+///
+/// # Example
+///
+/// ```
+/// use refx_pp::{Beatmap, Difficulty};
+/// use refx_pp::any::{CancellationToken, Cancelled};
+///
+/// let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+/// let token = CancellationToken::new();
+///
+/// let difficulty = Difficulty::new().cancellation_token(token.clone());
+///
+/// // e.g. on another thread
+/// token.cancel();
+///
+/// assert_eq!(difficulty.try_calculate(&map).unwrap_err(), Cancelled);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a new [`CancellationToken`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel all calculations that use this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.cancelled, &other.cancelled)
+    }
+}
+
+/// Cancellation state of a calculation.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Cancellation {
+    pub(crate) token: Option<CancellationToken>,
+    pub(crate) deadline: Option<Instant>,
+}
+
+impl Cancellation {
+    pub(crate) const NONE: Self = Self {
+        token: None,
+        deadline: None,
+    };
+
+    /// Whether the calculation should stop.
+    ///
+    /// Once this returns `true`, it will keep doing so.
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.token
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    pub(crate) fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Error when a calculation was cancelled through its [`CancellationToken`]
+/// or because its deadline passed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Error for Cancelled {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str("The calculation was cancelled")
+    }
+}
+
+/// Error type of a fallible calculation for a specific mode.
+#[derive(Copy, Clone, Debug)]
+pub enum CalculateError {
+    /// Failed to convert the map
+    Convert(ConvertError),
+    /// The calculation was cancelled
+    Cancelled,
+}
+
+impl From<ConvertError> for CalculateError {
+    fn from(err: ConvertError) -> Self {
+        Self::Convert(err)
+    }
+}
+
+impl From<Cancelled> for CalculateError {
+    fn from(_: Cancelled) -> Self {
+        Self::Cancelled
+    }
+}
+
+impl Error for CalculateError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CalculateError::Convert(err) => Some(err),
+            CalculateError::Cancelled => None,
+        }
+    }
+}
+
+impl Display for CalculateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            CalculateError::Convert(_) => f.write_str("Failed to convert the map"),
+            CalculateError::Cancelled => Display::fmt(&Cancelled, f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        any::DifficultyAttributes, catch::Catch, mania::Mania, taiko::Taiko, Beatmap, Difficulty,
+    };
+
+    use super::*;
+
+    fn map() -> Beatmap {
+        Beatmap::from_path("./resources/2785319.osu").unwrap()
+    }
+
+    #[test]
+    fn cancelled_token() {
+        let map = map();
+        let token = CancellationToken::new();
+        let difficulty = Difficulty::new().cancellation_token(token.clone());
+
+        assert!(difficulty.try_calculate(&map).is_ok());
+
+        token.cancel();
+
+        assert_eq!(difficulty.try_calculate(&map).unwrap_err(), Cancelled);
+        assert!(matches!(
+            difficulty.try_calculate_for_mode::<Taiko>(&map),
+            Err(CalculateError::Cancelled)
+        ));
+        assert!(matches!(
+            difficulty.try_calculate_for_mode::<Mania>(&map),
+            Err(CalculateError::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn cancelled_conversion() {
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let difficulty = Difficulty::new().cancellation_token(token);
+
+        // Conversion stops before the first hit object
+        let DifficultyAttributes::Osu(attrs) = difficulty.calculate(&map()) else {
+            panic!("expected osu attributes");
+        };
+
+        assert_eq!(attrs.max_combo, 0);
+
+        let catch = difficulty.calculate_for_mode::<Catch>(&map()).unwrap();
+        assert_eq!(catch.max_combo(), 0);
+    }
+
+    #[test]
+    fn deadline() {
+        let map = map();
+
+        let difficulty = Difficulty::new().deadline(Instant::now());
+        assert_eq!(difficulty.try_calculate(&map).unwrap_err(), Cancelled);
+
+        let deadline = Instant::now() + Duration::from_secs(30);
+        let difficulty = Difficulty::new().deadline(deadline);
+        assert!(difficulty.try_calculate(&map).is_ok());
+    }
+
+    #[test]
+    fn uncancelled_matches_calculate() {
+        let map = map();
+        let token = CancellationToken::new();
+
+        let expected = Difficulty::new().calculate(&map);
+        let actual = Difficulty::new()
+            .cancellation_token(token)
+            .try_calculate(&map)
+            .unwrap();
+
+        assert_eq!(actual, expected);
+
+        let DifficultyAttributes::Osu(_) = actual else {
+            panic!("expected osu attributes");
+        };
+    }
+
+    #[test]
+    fn token_clones_share_state() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+
+        assert_eq!(token, clone);
+        assert_ne!(token, CancellationToken::new());
+
+        clone.cancel();
+        assert!(token.is_cancelled());
+    }
+}
@@ -0,0 +1,373 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+    num::NonZeroUsize,
+};
+
+use rosu_map::section::general::GameMode;
+
+use crate::{
+    model::mode::ConvertError,
+    util::codec::{Decode, Encode},
+    Beatmap, Difficulty,
+};
+
+use super::ModsDependent;
+
+/// Periodic snapshots of a gradual calculator's state so that it can be
+/// rewound without processing everything from the start.
+pub(crate) struct Checkpoints<T> {
+    interval: Option<NonZeroUsize>,
+    /// Object index and the state after processing that many objects, sorted
+    /// by index. The first entry is always the initial state.
+    states: Vec<(usize, T)>,
+}
+
+impl<T: Clone> Checkpoints<T> {
+    pub(crate) fn new(initial: T) -> Self {
+        Self {
+            interval: None,
+            states: vec![(0, initial)],
+        }
+    }
+
+    /// Take a snapshot every `interval` objects. `0` disables snapshots.
+    ///
+    /// Already stored snapshots are kept.
+    pub(crate) const fn set_interval(&mut self, interval: usize) {
+        self.interval = NonZeroUsize::new(interval);
+    }
+
+    /// Store a snapshot if `idx` is a multiple of the interval and no later
+    /// snapshot has been stored yet.
+    pub(crate) fn save(&mut self, idx: usize, state: impl FnOnce() -> T) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+
+        let is_new = self.states.last().is_some_and(|(last, _)| *last < idx);
+
+        if is_new && idx % interval.get() == 0 {
+            self.states.push((idx, state()));
+        }
+    }
+
+    /// The latest snapshot at or before `idx` if resuming from it requires
+    /// less processing than continuing from the current index `curr`.
+    pub(crate) fn restore(&self, curr: usize, idx: usize) -> Option<(usize, T)> {
+        let i = self.states.partition_point(|(n, _)| *n <= idx);
+        let (n, ref state) = self.states[i.saturating_sub(1)];
+
+        (curr > idx || n > curr).then(|| (n, state.clone()))
+    }
+}
+
+/// Identifies serialized gradual calculator states.
+const STATE_MAGIC: [u8; 4] = *b"RXGS";
+/// Incremented whenever the layout of serialized states changes.
+const STATE_VERSION: u8 = 3;
+
+/// What a serialized gradual calculator state must agree on with the
+/// calculator it is resumed into.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct StateHeader {
+    pub(crate) mode: GameMode,
+    pub(crate) clock_rate: f64,
+    /// See [`StateHeader::mods_hash`].
+    pub(crate) mods: u64,
+    pub(crate) ar: Option<ModsDependent>,
+    pub(crate) cs: Option<ModsDependent>,
+    pub(crate) hp: Option<ModsDependent>,
+    pub(crate) od: Option<ModsDependent>,
+    pub(crate) hardrock_offsets: bool,
+    pub(crate) simulate_mod_geometry: bool,
+    pub(crate) lazer: bool,
+    /// Total amount of objects the calculator iterates over.
+    pub(crate) n_objects: usize,
+    /// See [`StateHeader::map_hash`].
+    pub(crate) map_hash: u64,
+}
+
+impl StateHeader {
+    pub(crate) fn new(
+        mode: GameMode,
+        difficulty: &Difficulty,
+        n_objects: usize,
+        map_hash: u64,
+    ) -> Self {
+        Self {
+            mode,
+            clock_rate: difficulty.get_clock_rate(),
+            mods: Self::mods_hash(difficulty),
+            ar: difficulty.get_ar(),
+            cs: difficulty.get_cs(),
+            hp: difficulty.get_hp(),
+            od: difficulty.get_od(),
+            hardrock_offsets: difficulty.get_hardrock_offsets(),
+            simulate_mod_geometry: difficulty.get_simulate_mod_geometry(),
+            lazer: difficulty.get_lazer(),
+            n_objects,
+            map_hash,
+        }
+    }
+
+    /// Identifies a map through the start times and positions of its hit
+    /// objects.
+    pub(crate) fn map_hash(map: &Beatmap) -> u64 {
+        let bytes = map.hit_objects.iter().flat_map(|h| {
+            let [x, y] = [h.pos.x, h.pos.y].map(|coord| coord.to_bits().to_le_bytes());
+            let time = h.start_time.to_bits().to_le_bytes();
+
+            time.into_iter().chain(x).chain(y)
+        });
+
+        fnv1a(bytes)
+    }
+
+    /// Identifies the mods including their settings, e.g. the values of
+    /// `DifficultyAdjust` or the speed change of `DoubleTime`.
+    ///
+    /// Only considers what the calculation depends on so that equivalent
+    /// legacy, intermode, and lazer mods have the same hash.
+    fn mods_hash(difficulty: &Difficulty) -> u64 {
+        let mods = difficulty.get_mods();
+        let mut buf = Vec::new();
+
+        mods.bits().encode(&mut buf);
+        mods.clock_rate().encode(&mut buf);
+        (mods.reflection() as u8).encode(&mut buf);
+        mods.ar().encode(&mut buf);
+        mods.cs().encode(&mut buf);
+        mods.hp().encode(&mut buf);
+        mods.od().encode(&mut buf);
+        mods.mania_keys().encode(&mut buf);
+        mods.dual_stages().encode(&mut buf);
+        mods.scroll_speed().encode(&mut buf);
+        mods.random_seed().map(f64::from).encode(&mut buf);
+        mods.attraction_strength().encode(&mut buf);
+        mods.only_fade_approach_circles().encode(&mut buf);
+        mods.wiggle_strength().encode(&mut buf);
+        mods.barrel_roll_speed().encode(&mut buf);
+        mods.start_scale().encode(&mut buf);
+
+        fnv1a(buf)
+    }
+
+    /// The mode of a serialized state without validating the rest of it.
+    pub(crate) fn peek_mode(state: &[u8]) -> Option<GameMode> {
+        let (magic, rest) = state.split_first_chunk::<4>()?;
+
+        if *magic != STATE_MAGIC || rest.first() != Some(&STATE_VERSION) {
+            return None;
+        }
+
+        match rest.get(1)? {
+            0 => Some(GameMode::Osu),
+            1 => Some(GameMode::Taiko),
+            2 => Some(GameMode::Catch),
+            3 => Some(GameMode::Mania),
+            _ => None,
+        }
+    }
+}
+
+/// FNV-1a so that hashes stay the same across processes and compiler
+/// versions.
+fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    bytes.into_iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+impl Encode for ModsDependent {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.value.encode(buf);
+        self.with_mods.encode(buf);
+    }
+}
+
+impl Decode for ModsDependent {
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        Some(Self {
+            value: f32::decode(bytes)?,
+            with_mods: bool::decode(bytes)?,
+        })
+    }
+}
+
+/// Serializes a gradual calculator's state.
+pub(crate) struct StateWriter {
+    buf: Vec<u8>,
+}
+
+impl StateWriter {
+    pub(crate) fn new(header: StateHeader, idx: usize) -> Self {
+        let mut buf = STATE_MAGIC.to_vec();
+        buf.push(STATE_VERSION);
+        buf.push(header.mode as u8);
+        header.clock_rate.encode(&mut buf);
+        header.mods.encode(&mut buf);
+        header.ar.encode(&mut buf);
+        header.cs.encode(&mut buf);
+        header.hp.encode(&mut buf);
+        header.od.encode(&mut buf);
+        header.hardrock_offsets.encode(&mut buf);
+        header.simulate_mod_geometry.encode(&mut buf);
+        header.lazer.encode(&mut buf);
+        header.n_objects.encode(&mut buf);
+        header.map_hash.encode(&mut buf);
+        idx.encode(&mut buf);
+
+        Self { buf }
+    }
+
+    pub(crate) fn write<T: Encode + ?Sized>(&mut self, value: &T) -> &mut Self {
+        value.encode(&mut self.buf);
+
+        self
+    }
+
+    pub(crate) fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+/// Deserializes a gradual calculator's state that was written through
+/// [`StateWriter`].
+pub(crate) struct StateReader<'a> {
+    bytes: &'a [u8],
+    idx: usize,
+}
+
+impl<'a> StateReader<'a> {
+    /// Validates the header against the calculator that is being resumed.
+    pub(crate) fn new(state: &'a [u8], expected: StateHeader) -> Result<Self, ResumeError> {
+        if StateHeader::peek_mode(state) != Some(expected.mode) {
+            return Err(ResumeError::Invalid);
+        }
+
+        let mut this = Self {
+            bytes: &state[STATE_MAGIC.len() + 2..],
+            idx: 0,
+        };
+
+        let header = StateHeader {
+            mode: expected.mode,
+            clock_rate: this.read()?,
+            mods: this.read()?,
+            ar: this.read()?,
+            cs: this.read()?,
+            hp: this.read()?,
+            od: this.read()?,
+            hardrock_offsets: this.read()?,
+            simulate_mod_geometry: this.read()?,
+            lazer: this.read()?,
+            n_objects: this.read()?,
+            map_hash: this.read()?,
+        };
+
+        if header != expected {
+            return Err(ResumeError::Mismatch);
+        }
+
+        this.idx = this.read()?;
+
+        if this.idx > header.n_objects {
+            return Err(ResumeError::Invalid);
+        }
+
+        Ok(this)
+    }
+
+    /// The amount of objects that were processed.
+    pub(crate) const fn idx(&self) -> usize {
+        self.idx
+    }
+
+    pub(crate) fn read<T: Decode>(&mut self) -> Result<T, ResumeError> {
+        T::decode(&mut self.bytes).ok_or(ResumeError::Invalid)
+    }
+
+    /// Ensures that all bytes were read.
+    pub(crate) const fn finish(self) -> Result<(), ResumeError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(ResumeError::Invalid)
+        }
+    }
+}
+
+/// Error when resuming a gradual calculator from a serialized state.
+#[derive(Copy, Clone, Debug)]
+pub enum ResumeError {
+    /// Failed to convert the map
+    Convert(ConvertError),
+    /// The state is malformed, belongs to another mode, or was serialized
+    /// by an incompatible version
+    Invalid,
+    /// The state was serialized for a different map or different
+    /// [`Difficulty`] settings
+    Mismatch,
+}
+
+impl From<ConvertError> for ResumeError {
+    fn from(err: ConvertError) -> Self {
+        Self::Convert(err)
+    }
+}
+
+impl Error for ResumeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ResumeError::Convert(err) => Some(err),
+            ResumeError::Invalid | ResumeError::Mismatch => None,
+        }
+    }
+}
+
+impl Display for ResumeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ResumeError::Convert(_) => f.write_str("Failed to convert the map"),
+            ResumeError::Invalid => f.write_str("Invalid gradual calculator state"),
+            ResumeError::Mismatch => {
+                f.write_str("Gradual calculator state belongs to different map or settings")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_and_restore() {
+        let mut checkpoints = Checkpoints::new(0);
+
+        checkpoints.save(5, || 5);
+        assert_eq!(checkpoints.restore(8, 7), Some((0, 0)));
+
+        checkpoints.set_interval(5);
+
+        for i in 1..=12 {
+            checkpoints.save(i, || i);
+        }
+
+        // Already stored snapshots are not overwritten
+        checkpoints.save(5, || 100);
+
+        assert_eq!(checkpoints.restore(12, 4), Some((0, 0)));
+        assert_eq!(checkpoints.restore(12, 5), Some((5, 5)));
+        assert_eq!(checkpoints.restore(12, 9), Some((5, 5)));
+        assert_eq!(checkpoints.restore(3, 100), Some((10, 10)));
+
+        // Continuing from the current index is cheaper
+        assert_eq!(checkpoints.restore(7, 9), None);
+        assert_eq!(checkpoints.restore(10, 100), None);
+    }
+}
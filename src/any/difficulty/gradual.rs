@@ -1,7 +1,7 @@
 use rosu_map::section::general::GameMode;
 
 use crate::{
-    any::DifficultyAttributes,
+    any::{difficulty::checkpoints::StateHeader, DifficultyAttributes, ResumeError},
     catch::{Catch, CatchGradualDifficulty},
     mania::{Mania, ManiaGradualDifficulty},
     model::mode::{ConvertError, IGameMode},
@@ -69,6 +69,104 @@ impl GradualDifficulty {
             GameMode::Mania => Mania::gradual_difficulty(difficulty, map).map(Self::Mania),
         }
     }
+
+    /// Take a snapshot of the calculator's state every `interval` processed
+    /// objects so that [`GradualDifficulty::seek`] can rewind without
+    /// starting over.
+    ///
+    /// Snapshots are disabled by default, i.e. an interval of `0`. Each
+    /// snapshot stores the skills' strain history so smaller intervals trade
+    /// memory for faster seeking.
+    #[must_use]
+    pub fn checkpoint_interval(self, interval: usize) -> Self {
+        match self {
+            Self::Osu(gradual) => Self::Osu(gradual.checkpoint_interval(interval)),
+            Self::Taiko(gradual) => Self::Taiko(gradual.checkpoint_interval(interval)),
+            Self::Catch(gradual) => Self::Catch(gradual.checkpoint_interval(interval)),
+            Self::Mania(gradual) => Self::Mania(gradual.checkpoint_interval(interval)),
+        }
+    }
+
+    /// Move to the state after processing `idx` objects so that the next call
+    /// of [`Iterator::next`] processes the object at index `idx`.
+    ///
+    /// Seeking backwards resumes from the latest snapshot at or before `idx`,
+    /// see [`GradualDifficulty::checkpoint_interval`].
+    pub fn seek(&mut self, idx: usize) {
+        match self {
+            Self::Osu(gradual) => gradual.seek(idx),
+            Self::Taiko(gradual) => gradual.seek(idx),
+            Self::Catch(gradual) => gradual.seek(idx),
+            Self::Mania(gradual) => gradual.seek(idx),
+        }
+    }
+
+    /// Process all objects that are judged at or before `time`, i.e. the
+    /// current playback position in milliseconds, and return the attributes
+    /// at that moment.
+    ///
+    /// Objects with a duration such as sliders or hold notes are judged at
+    /// their end time. Already processed objects are never rewound, see
+    /// [`GradualDifficulty::seek`].
+    ///
+    /// Returns `None` if no object has been processed yet.
+    pub fn advance_to_time(&mut self, time: f64) -> Option<DifficultyAttributes> {
+        match self {
+            Self::Osu(gradual) => gradual.advance_to_time(time).map(DifficultyAttributes::Osu),
+            Self::Taiko(gradual) => gradual
+                .advance_to_time(time)
+                .map(DifficultyAttributes::Taiko),
+            Self::Catch(gradual) => gradual
+                .advance_to_time(time)
+                .map(DifficultyAttributes::Catch),
+            Self::Mania(gradual) => gradual
+                .advance_to_time(time)
+                .map(DifficultyAttributes::Mania),
+        }
+    }
+
+    /// Serialize the current state, i.e. the amount of processed objects, the
+    /// partial attributes, and the skills' strain history.
+    ///
+    /// The state can be resumed through [`GradualDifficulty::resume`], e.g.
+    /// in another process. Snapshots of
+    /// [`GradualDifficulty::checkpoint_interval`] are not included.
+    pub fn save_state(&self) -> Vec<u8> {
+        match self {
+            Self::Osu(gradual) => gradual.save_state(),
+            Self::Taiko(gradual) => gradual.save_state(),
+            Self::Catch(gradual) => gradual.save_state(),
+            Self::Mania(gradual) => gradual.save_state(),
+        }
+    }
+
+    /// Resume a state that was serialized through
+    /// [`GradualDifficulty::save_state`].
+    ///
+    /// The mode is stored in the state. The difficulty objects are rebuilt
+    /// from the map so `difficulty` and `map` must be the same as for the
+    /// serialized calculator.
+    pub fn resume(
+        difficulty: Difficulty,
+        map: &Beatmap,
+        state: &[u8],
+    ) -> Result<Self, ResumeError> {
+        match StateHeader::peek_mode(state) {
+            Some(GameMode::Osu) => {
+                OsuGradualDifficulty::resume(difficulty, map, state).map(Self::Osu)
+            }
+            Some(GameMode::Taiko) => {
+                TaikoGradualDifficulty::resume(difficulty, map, state).map(Self::Taiko)
+            }
+            Some(GameMode::Catch) => {
+                CatchGradualDifficulty::resume(difficulty, map, state).map(Self::Catch)
+            }
+            Some(GameMode::Mania) => {
+                ManiaGradualDifficulty::resume(difficulty, map, state).map(Self::Mania)
+            }
+            None => Err(ResumeError::Invalid),
+        }
+    }
 }
 
 impl Iterator for GradualDifficulty {
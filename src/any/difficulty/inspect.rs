@@ -1,6 +1,8 @@
+use std::time::Instant;
+
 use crate::{model::mods::GameMods, Difficulty};
 
-use super::ModsDependent;
+use super::{cancellation::CancellationToken, ModsDependent};
 
 /// [`Difficulty`] but all fields are public for inspection.
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -32,6 +34,14 @@ pub struct InspectDifficulty {
     ///
     /// Defaults to `true`.
     pub lazer: Option<bool>,
+    /// Whether objects should be moved the way visual mods move them.
+    ///
+    /// Only relevant for osu!standard.
+    pub simulate_mod_geometry: Option<bool>,
+    /// A token to cancel the calculation.
+    pub cancellation_token: Option<CancellationToken>,
+    /// A point in time after which the calculation should stop.
+    pub deadline: Option<Instant>,
 }
 
 impl InspectDifficulty {
@@ -47,6 +57,9 @@ impl InspectDifficulty {
             od,
             hardrock_offsets,
             lazer,
+            simulate_mod_geometry,
+            cancellation_token,
+            deadline,
         } = self;
 
         let mut difficulty = Difficulty::new().mods(mods);
@@ -83,6 +96,18 @@ impl InspectDifficulty {
             difficulty = difficulty.lazer(lazer);
         }
 
+        if let Some(simulate_mod_geometry) = simulate_mod_geometry {
+            difficulty = difficulty.simulate_mod_geometry(simulate_mod_geometry);
+        }
+
+        if let Some(token) = cancellation_token {
+            difficulty = difficulty.cancellation_token(token);
+        }
+
+        if let Some(deadline) = deadline {
+            difficulty = difficulty.deadline(deadline);
+        }
+
         difficulty
     }
 }
@@ -1,6 +1,7 @@
 use std::{
     fmt::{Debug, Formatter, Result as FmtResult},
     num::NonZeroU64,
+    time::Instant,
 };
 
 use rosu_map::section::general::GameMode;
@@ -16,6 +17,10 @@ use crate::{
 
 use super::{attributes::DifficultyAttributes, InspectDifficulty, Strains};
 
+use self::cancellation::{CalculateError, Cancellation, CancellationToken, Cancelled};
+
+pub mod cancellation;
+pub mod checkpoints;
 pub mod gradual;
 pub mod inspect;
 pub mod object;
@@ -54,6 +59,8 @@ pub struct Difficulty {
     od: Option<ModsDependent>,
     hardrock_offsets: Option<bool>,
     lazer: Option<bool>,
+    simulate_mod_geometry: Option<bool>,
+    cancellation: Cancellation,
 }
 
 /// Wrapper for beatmap attributes in [`Difficulty`].
@@ -90,6 +97,8 @@ impl Difficulty {
             od: None,
             hardrock_offsets: None,
             lazer: None,
+            simulate_mod_geometry: None,
+            cancellation: Cancellation::NONE,
         }
     }
 
@@ -106,6 +115,8 @@ impl Difficulty {
             od,
             hardrock_offsets,
             lazer,
+            simulate_mod_geometry,
+            cancellation,
         } = self;
 
         InspectDifficulty {
@@ -118,6 +129,9 @@ impl Difficulty {
             od,
             hardrock_offsets,
             lazer,
+            simulate_mod_geometry,
+            cancellation_token: cancellation.token,
+            deadline: cancellation.deadline,
         }
     }
 
@@ -265,7 +279,45 @@ impl Difficulty {
         self
     }
 
+    /// Whether objects should be moved the way visual mods such as Wiggle or
+    /// Barrel Roll move them in osu!lazer before evaluating difficulty.
+    ///
+    /// Only relevant for osu!standard. Defaults to `false`.
+    pub const fn simulate_mod_geometry(mut self, simulate_mod_geometry: bool) -> Self {
+        self.simulate_mod_geometry = Some(simulate_mod_geometry);
+
+        self
+    }
+
+    /// Specify a [`CancellationToken`] to stop the calculation early.
+    ///
+    /// The token is checked for each hit object while converting maps,
+    /// creating difficulty objects, and processing skills. Only
+    /// [`Difficulty::try_calculate`] and
+    /// [`Difficulty::try_calculate_for_mode`] report whether the calculation
+    /// was cancelled. All other methods, e.g. [`Difficulty::calculate`],
+    /// return incomplete attributes on cancellation that should be discarded.
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation.token = Some(token);
+
+        self
+    }
+
+    /// Specify a point in time after which the calculation should stop.
+    ///
+    /// The deadline is checked the same way as a [`CancellationToken`], see
+    /// [`Difficulty::cancellation_token`].
+    pub const fn deadline(mut self, deadline: Instant) -> Self {
+        self.cancellation.deadline = Some(deadline);
+
+        self
+    }
+
     /// Perform the difficulty calculation.
+    ///
+    /// If the calculation was cancelled through a [`CancellationToken`] or
+    /// [`Difficulty::deadline`], the returned attributes are incomplete and
+    /// meaningless. Use [`Difficulty::try_calculate`] to detect cancellation.
     #[allow(clippy::missing_panics_doc)]
     pub fn calculate(&self, map: &Beatmap) -> DifficultyAttributes {
         match map.mode {
@@ -284,6 +336,145 @@ impl Difficulty {
         }
     }
 
+    /// Perform the difficulty calculation for multiple mod combinations on
+    /// the same map.
+    ///
+    /// All settings besides the mods are taken from `self`. The resulting
+    /// attributes are in the same order as the given mods.
+    ///
+    /// On osu!standard maps, mod combinations that don't change the object
+    /// geometry, e.g. NM and HD or DT and HDDT, share their converted objects
+    /// so this is faster than calculating each combination separately.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use refx_pp::{Beatmap, Difficulty};
+    ///
+    /// let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+    ///
+    /// // NM, HD, HR, DT, HDDT, HDHR
+    /// let attrs = Difficulty::new().calculate_many(&map, [0, 8, 16, 64, 72, 24]);
+    ///
+    /// assert_eq!(attrs.len(), 6);
+    /// ```
+    pub fn calculate_many<M: Into<GameMods>>(
+        &self,
+        map: &Beatmap,
+        mods: impl IntoIterator<Item = M>,
+    ) -> Vec<DifficultyAttributes> {
+        let difficulties: Vec<_> = mods
+            .into_iter()
+            .map(|mods| self.clone().mods(mods))
+            .collect();
+
+        Self::calculate_all(&difficulties, map)
+    }
+
+    /// Same as [`Difficulty::calculate_many`] but also returns the strains of
+    /// each mod combination, see [`Difficulty::strains`].
+    ///
+    /// Attributes and strains of a mod combination stem from the same
+    /// calculation so this is faster than calling both methods.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn calculate_many_with_strains<M: Into<GameMods>>(
+        &self,
+        map: &Beatmap,
+        mods: impl IntoIterator<Item = M>,
+    ) -> Vec<(DifficultyAttributes, Strains)> {
+        let difficulties: Vec<_> = mods
+            .into_iter()
+            .map(|mods| self.clone().mods(mods))
+            .collect();
+
+        match map.mode {
+            GameMode::Osu => Osu::difficulty_many_with_strains(&difficulties, map)
+                .expect("no conversion required")
+                .into_iter()
+                .map(|(attrs, strains)| (DifficultyAttributes::Osu(attrs), Strains::Osu(strains)))
+                .collect(),
+            GameMode::Taiko => difficulties
+                .iter()
+                .map(|difficulty| {
+                    let (attrs, strains) = Taiko::difficulty_with_strains(difficulty, map)
+                        .expect("no conversion required");
+
+                    (DifficultyAttributes::Taiko(attrs), Strains::Taiko(strains))
+                })
+                .collect(),
+            GameMode::Catch => difficulties
+                .iter()
+                .map(|difficulty| {
+                    let (attrs, strains) = Catch::difficulty_with_strains(difficulty, map)
+                        .expect("no conversion required");
+
+                    (DifficultyAttributes::Catch(attrs), Strains::Catch(strains))
+                })
+                .collect(),
+            GameMode::Mania => difficulties
+                .iter()
+                .map(|difficulty| {
+                    let (attrs, strains) = Mania::difficulty_with_strains(difficulty, map)
+                        .expect("no conversion required");
+
+                    (DifficultyAttributes::Mania(attrs), Strains::Mania(strains))
+                })
+                .collect(),
+        }
+    }
+
+    /// Perform the difficulty calculation for multiple clock rates on the
+    /// same map.
+    ///
+    /// All settings besides the clock rate are taken from `self`. The
+    /// resulting attributes are in the same order as the given rates.
+    ///
+    /// On osu!standard maps, the rate-independent parts such as slider paths
+    /// and stacking are only computed once so this is faster than calculating
+    /// each rate separately.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use refx_pp::{Beatmap, Difficulty};
+    ///
+    /// let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+    ///
+    /// // 0.5x, 0.55x, ..., 2.0x
+    /// let rates = (10..=40).map(|i| f64::from(i) * 0.05);
+    /// let attrs = Difficulty::new().mods(8).calculate_rates(&map, rates);
+    ///
+    /// assert_eq!(attrs.len(), 31);
+    /// ```
+    pub fn calculate_rates(
+        &self,
+        map: &Beatmap,
+        clock_rates: impl IntoIterator<Item = f64>,
+    ) -> Vec<DifficultyAttributes> {
+        let difficulties: Vec<_> = clock_rates
+            .into_iter()
+            .map(|clock_rate| self.clone().clock_rate(clock_rate))
+            .collect();
+
+        Self::calculate_all(&difficulties, map)
+    }
+
+    /// Calculate all difficulties on the same map and share as much work
+    /// between them as possible.
+    fn calculate_all(difficulties: &[Self], map: &Beatmap) -> Vec<DifficultyAttributes> {
+        match map.mode {
+            GameMode::Osu => Osu::difficulty_many(difficulties, map)
+                .expect("no conversion required")
+                .into_iter()
+                .map(DifficultyAttributes::Osu)
+                .collect(),
+            GameMode::Taiko | GameMode::Catch | GameMode::Mania => difficulties
+                .iter()
+                .map(|difficulty| difficulty.calculate(map))
+                .collect(),
+        }
+    }
+
     /// Perform the difficulty calculation for a specific [`IGameMode`].
     pub fn calculate_for_mode<M: IGameMode>(
         &self,
@@ -292,6 +483,37 @@ impl Difficulty {
         M::difficulty(self, map)
     }
 
+    /// Perform the difficulty calculation but return an error if it was
+    /// cancelled through a [`CancellationToken`] or [`deadline`].
+    ///
+    /// Note that the deadline is also checked after the calculation finished
+    /// so a result is only returned if it was produced in time.
+    ///
+    /// [`deadline`]: Difficulty::deadline
+    pub fn try_calculate(&self, map: &Beatmap) -> Result<DifficultyAttributes, Cancelled> {
+        self.cancellation.check()?;
+        let attrs = self.calculate(map);
+        self.cancellation.check()?;
+
+        Ok(attrs)
+    }
+
+    /// Perform the difficulty calculation for a specific [`IGameMode`] but
+    /// return an error if it was cancelled through a [`CancellationToken`]
+    /// or [`deadline`].
+    ///
+    /// [`deadline`]: Difficulty::deadline
+    pub fn try_calculate_for_mode<M: IGameMode>(
+        &self,
+        map: &Beatmap,
+    ) -> Result<M::DifficultyAttributes, CalculateError> {
+        self.cancellation.check()?;
+        let attrs = M::difficulty(self, map)?;
+        self.cancellation.check()?;
+
+        Ok(attrs)
+    }
+
     /// Perform the difficulty calculation but instead of evaluating the skill
     /// strains, return them as is.
     ///
@@ -383,6 +605,14 @@ impl Difficulty {
     pub(crate) fn get_lazer(&self) -> bool {
         self.lazer.unwrap_or(true)
     }
+
+    pub(crate) fn get_simulate_mod_geometry(&self) -> bool {
+        self.simulate_mod_geometry.unwrap_or(false)
+    }
+
+    pub(crate) const fn get_cancellation(&self) -> &Cancellation {
+        &self.cancellation
+    }
 }
 
 const fn non_zero_u64_to_f64(n: NonZeroU64) -> f64 {
@@ -401,6 +631,8 @@ impl Debug for Difficulty {
             od,
             hardrock_offsets,
             lazer,
+            simulate_mod_geometry,
+            cancellation,
         } = self;
 
         f.debug_struct("Difficulty")
@@ -413,6 +645,9 @@ impl Debug for Difficulty {
             .field("od", od)
             .field("hardrock_offsets", hardrock_offsets)
             .field("lazer", lazer)
+            .field("simulate_mod_geometry", simulate_mod_geometry)
+            .field("cancellation_token", &cancellation.token)
+            .field("deadline", &cancellation.deadline)
             .finish()
     }
 }
@@ -0,0 +1,96 @@
+/// The judgement of a hit object or one of its nested objects, as in
+/// osu!lazer.
+///
+/// Mode-specific judgements map as follows:
+/// - osu!standard: circles and sliders are [`Great`], [`Ok`], [`Meh`], or
+///   [`Miss`]; slider heads without slider accuracy, ticks, and repeats are
+///   [`LargeTickHit`] or [`LargeTickMiss`]; slider ends are
+///   [`SliderTailHit`] with slider accuracy and [`SmallTickHit`] without.
+/// - osu!taiko: circles are [`Great`], [`Ok`], or [`Miss`].
+/// - osu!catch: fruits are [`Great`] or [`Miss`], droplets are
+///   [`LargeTickHit`] or [`LargeTickMiss`], and tiny droplets are
+///   [`SmallTickHit`] or [`SmallTickMiss`].
+/// - osu!mania: notes are [`Perfect`], [`Great`], [`Good`], [`Ok`],
+///   [`Meh`], or [`Miss`].
+///
+/// [`Great`]: HitResult::Great
+/// [`Ok`]: HitResult::Ok
+/// [`Meh`]: HitResult::Meh
+/// [`Miss`]: HitResult::Miss
+/// [`Perfect`]: HitResult::Perfect
+/// [`Good`]: HitResult::Good
+/// [`LargeTickHit`]: HitResult::LargeTickHit
+/// [`LargeTickMiss`]: HitResult::LargeTickMiss
+/// [`SmallTickHit`]: HitResult::SmallTickHit
+/// [`SmallTickMiss`]: HitResult::SmallTickMiss
+/// [`SliderTailHit`]: HitResult::SliderTailHit
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HitResult {
+    /// The object was missed.
+    Miss,
+    /// The lowest hit judgement.
+    Meh,
+    /// A hit judgement between [`Meh`](Self::Meh) and
+    /// [`Good`](Self::Good).
+    Ok,
+    /// A hit judgement between [`Ok`](Self::Ok) and [`Great`](Self::Great).
+    Good,
+    /// The highest hit judgement besides [`Perfect`](Self::Perfect).
+    Great,
+    /// The highest hit judgement.
+    Perfect,
+    /// A small tick, e.g. a tiny droplet, was missed.
+    SmallTickMiss,
+    /// A small tick, e.g. a tiny droplet, was hit.
+    SmallTickHit,
+    /// A large tick, e.g. a slider tick or droplet, was missed.
+    LargeTickMiss,
+    /// A large tick, e.g. a slider tick or droplet, was hit.
+    LargeTickHit,
+    /// A small bonus, e.g. a spinner tick, was hit.
+    SmallBonus,
+    /// A large bonus, e.g. a spinner bonus tick, was hit.
+    LargeBonus,
+    /// A miss that does not affect the combo or accuracy.
+    IgnoreMiss,
+    /// A hit that does not affect the combo or accuracy.
+    IgnoreHit,
+    /// A miss that only resets the combo.
+    ComboBreak,
+    /// The tail of a slider was hit.
+    SliderTailHit,
+    /// A hit that only increases the combo, as in osu!stable.
+    LegacyComboIncrease,
+}
+
+impl HitResult {
+    /// Whether the judgement is a successful hit.
+    pub const fn is_hit(self) -> bool {
+        !matches!(
+            self,
+            Self::Miss
+                | Self::SmallTickMiss
+                | Self::LargeTickMiss
+                | Self::IgnoreMiss
+                | Self::ComboBreak
+        )
+    }
+
+    /// Whether the judgement increases or resets the combo.
+    pub const fn affects_combo(self) -> bool {
+        matches!(
+            self,
+            Self::Miss
+                | Self::Meh
+                | Self::Ok
+                | Self::Good
+                | Self::Great
+                | Self::Perfect
+                | Self::LargeTickHit
+                | Self::LargeTickMiss
+                | Self::ComboBreak
+                | Self::SliderTailHit
+                | Self::LegacyComboIncrease
+        )
+    }
+}
@@ -1,9 +1,15 @@
 pub use self::{
     attributes::{DifficultyAttributes, PerformanceAttributes},
     difficulty::{
-        gradual::GradualDifficulty, inspect::InspectDifficulty, Difficulty, ModsDependent,
+        cancellation::{CalculateError, CancellationToken, Cancelled},
+        checkpoints::ResumeError,
+        gradual::GradualDifficulty,
+        inspect::InspectDifficulty,
+        Difficulty, ModsDependent,
     },
+    hit_result::HitResult,
     performance::{
+        events::HitEventPerformance,
         gradual::GradualPerformance,
         into::{IntoModePerformance, IntoPerformance},
         HitResultPriority, Performance,
@@ -14,6 +20,7 @@ pub use self::{
 
 mod attributes;
 pub(crate) mod difficulty;
+mod hit_result;
 mod performance;
 mod score_state;
 mod strains;
@@ -0,0 +1,395 @@
+use std::{cmp, collections::BTreeSet};
+
+use rosu_map::section::general::GameMode;
+
+use crate::{
+    any::{DifficultyAttributes, GradualDifficulty, HitResult, PerformanceAttributes, ScoreState},
+    model::mode::ConvertError,
+    Beatmap, Difficulty,
+};
+
+/// Gradually calculate performance attributes from individual judgements.
+///
+/// Unlike [`GradualPerformance`], which requires the caller to keep track of
+/// the cumulative [`ScoreState`], this type is fed every [`HitResult`] as it
+/// happens and maintains the score state, the current combo, and the
+/// maximum combo itself.
+///
+/// An object is considered complete once it receives its own judgement,
+/// e.g. [`HitResult::Great`] for a slider after its ticks or
+/// [`HitResult::LargeTickHit`] for a droplet. Hold notes on osu!lazer
+/// complete after both their head and tail were judged.
+///
+/// [`HitEventPerformance::record`] assigns each judgement to the next
+/// incomplete object. If judgements of multiple objects interleave, e.g. the
+/// head of a hold note is judged while another hold note is still being
+/// held, use [`HitEventPerformance::record_object`] instead.
+///
+/// # Example
+///
+/// ```
+/// use refx_pp::{Beatmap, Difficulty};
+/// use refx_pp::any::{HitEventPerformance, HitResult};
+///
+/// let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+/// let mut gradual = HitEventPerformance::new(Difficulty::new(), &map);
+///
+/// for _ in 0..10 {
+///     gradual.record(HitResult::Great);
+/// }
+///
+/// gradual.record(HitResult::Miss);
+///
+/// let attrs = gradual.calculate().unwrap();
+/// assert_eq!(gradual.state().max_combo, 10);
+/// println!("PP: {}", attrs.pp());
+/// ```
+///
+/// [`GradualPerformance`]: crate::GradualPerformance
+pub struct HitEventPerformance {
+    difficulty: Difficulty,
+    gradual: GradualDifficulty,
+    attrs: Option<DifficultyAttributes>,
+    state: ScoreState,
+    combo: u32,
+    /// Completed objects that have not been processed by `gradual` yet.
+    pending: usize,
+    /// Indices of completed objects that come after an incomplete object.
+    completed: BTreeSet<usize>,
+    /// Indices of hold notes whose head has been judged but not their tail.
+    open_holds: BTreeSet<usize>,
+}
+
+impl HitEventPerformance {
+    /// Create a [`HitEventPerformance`] for a map of any mode.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn new(difficulty: Difficulty, map: &Beatmap) -> Self {
+        Self::new_with_mode(difficulty, map, map.mode).expect("no conversion required")
+    }
+
+    /// Create a [`HitEventPerformance`] for a [`Beatmap`] on a specific
+    /// [`GameMode`].
+    pub fn new_with_mode(
+        difficulty: Difficulty,
+        map: &Beatmap,
+        mode: GameMode,
+    ) -> Result<Self, ConvertError> {
+        let gradual = GradualDifficulty::new_with_mode(difficulty.clone(), map, mode)?;
+
+        Ok(Self {
+            difficulty,
+            gradual,
+            attrs: None,
+            state: ScoreState::new(),
+            combo: 0,
+            pending: 0,
+            completed: BTreeSet::new(),
+            open_holds: BTreeSet::new(),
+        })
+    }
+
+    /// Record the next judgement.
+    ///
+    /// The judgement belongs to the first object that is not complete yet.
+    /// Judgements that do not apply to the map's mode are ignored besides
+    /// their effect on the combo.
+    pub fn record(&mut self, result: HitResult) {
+        let mut idx = self.next_object_idx();
+
+        while self.completed.contains(&idx) {
+            idx += 1;
+        }
+
+        self.record_object(idx, result);
+    }
+
+    /// Record the judgement of the hit object at index `idx`.
+    ///
+    /// Objects may be completed in any order, e.g. the tail of a hold note
+    /// after the head of the next hold note, but only the leading completed
+    /// objects count towards [`HitEventPerformance::passed_objects`].
+    /// Judgements that do not apply to the map's mode are ignored besides
+    /// their effect on the combo.
+    pub fn record_object(&mut self, idx: usize, result: HitResult) {
+        if result.affects_combo() {
+            if result.is_hit() {
+                self.combo += 1;
+                self.state.max_combo = cmp::max(self.state.max_combo, self.combo);
+            } else {
+                self.combo = 0;
+            }
+        }
+
+        let state = &mut self.state;
+
+        let (counter, judges_object) = match self.gradual {
+            GradualDifficulty::Osu(_) => match result {
+                HitResult::Great => (&mut state.n300, true),
+                HitResult::Ok => (&mut state.n100, true),
+                HitResult::Meh => (&mut state.n50, true),
+                HitResult::Miss => (&mut state.misses, true),
+                HitResult::LargeTickHit => (&mut state.osu_large_tick_hits, false),
+                HitResult::SmallTickHit => (&mut state.osu_small_tick_hits, false),
+                HitResult::SliderTailHit => (&mut state.slider_end_hits, false),
+                _ => return,
+            },
+            GradualDifficulty::Taiko(_) => match result {
+                HitResult::Great => (&mut state.n300, true),
+                HitResult::Ok => (&mut state.n100, true),
+                HitResult::Miss => (&mut state.misses, true),
+                _ => return,
+            },
+            GradualDifficulty::Catch(_) => match result {
+                HitResult::Great => (&mut state.n300, true),
+                HitResult::LargeTickHit => (&mut state.n100, true),
+                HitResult::Miss | HitResult::LargeTickMiss => (&mut state.misses, true),
+                HitResult::SmallTickHit => (&mut state.n50, false),
+                HitResult::SmallTickMiss => (&mut state.n_katu, false),
+                _ => return,
+            },
+            GradualDifficulty::Mania(_) => match result {
+                HitResult::Perfect => (&mut state.n_geki, true),
+                HitResult::Great => (&mut state.n300, true),
+                HitResult::Good => (&mut state.n_katu, true),
+                HitResult::Ok => (&mut state.n100, true),
+                HitResult::Meh => (&mut state.n50, true),
+                HitResult::Miss => (&mut state.misses, true),
+                _ => return,
+            },
+        };
+
+        *counter += 1;
+
+        if !judges_object {
+            return;
+        }
+
+        let next_idx = self.next_object_idx();
+
+        if idx < next_idx {
+            return;
+        }
+
+        // Hold notes award two hitresults in lazer
+        if let GradualDifficulty::Mania(ref gradual) = self.gradual {
+            if self.difficulty.get_lazer()
+                && gradual.is_hold_note(idx)
+                && self.open_holds.insert(idx)
+            {
+                return;
+            }
+        }
+
+        self.open_holds.remove(&idx);
+
+        if idx > next_idx {
+            self.completed.insert(idx);
+
+            return;
+        }
+
+        self.pending += 1;
+
+        while self.completed.remove(&(next_idx + self.pending)) {
+            self.pending += 1;
+        }
+    }
+
+    /// Index of the first object that is not complete yet.
+    const fn next_object_idx(&self) -> usize {
+        self.processed_objects() + self.pending
+    }
+
+    /// The amount of objects processed by `gradual`.
+    const fn processed_objects(&self) -> usize {
+        match self.gradual {
+            GradualDifficulty::Osu(ref gradual) => gradual.idx,
+            GradualDifficulty::Taiko(ref gradual) => gradual.idx,
+            GradualDifficulty::Catch(ref gradual) => gradual.idx,
+            GradualDifficulty::Mania(ref gradual) => gradual.idx,
+        }
+    }
+
+    /// Calculate the performance attributes for all judgements so far.
+    ///
+    /// Returns `None` if no object has been completed yet.
+    pub fn calculate(&mut self) -> Option<PerformanceAttributes> {
+        if self.pending > 0 {
+            if let Some(attrs) = self.gradual.nth(self.pending - 1) {
+                self.attrs = Some(attrs);
+            }
+
+            self.pending = 0;
+        }
+
+        let performance = self
+            .attrs
+            .clone()?
+            .performance()
+            .difficulty(self.difficulty.clone())
+            .passed_objects(self.passed_objects() as u32)
+            .state(self.state.clone())
+            .calculate();
+
+        Some(performance)
+    }
+
+    /// The score state of all judgements so far.
+    pub const fn state(&self) -> &ScoreState {
+        &self.state
+    }
+
+    /// The current combo.
+    pub const fn combo(&self) -> u32 {
+        self.combo
+    }
+
+    /// The amount of completed objects.
+    pub fn passed_objects(&self) -> usize {
+        self.processed_objects() + cmp::min(self.pending, self.gradual.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rosu_map::util::Pos;
+
+    use crate::{model::beatmap::BeatmapBuilder, GradualPerformance};
+
+    use super::*;
+
+    #[test]
+    fn matches_gradual_performance() {
+        let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+        let difficulty = Difficulty::new().mods(8); // HD
+
+        let mut events = HitEventPerformance::new(difficulty.clone(), &map);
+        assert!(events.calculate().is_none());
+
+        for result in [HitResult::Great, HitResult::LargeTickHit, HitResult::Ok] {
+            events.record(result);
+        }
+
+        for _ in 0..5 {
+            events.record(HitResult::Great);
+        }
+
+        events.record(HitResult::Miss);
+        events.record(HitResult::Meh);
+
+        let mut state = ScoreState::new();
+        state.max_combo = 8;
+        state.osu_large_tick_hits = 1;
+        state.n300 = 6;
+        state.n100 = 1;
+        state.n50 = 1;
+        state.misses = 1;
+
+        assert_eq!(events.state(), &state);
+        assert_eq!(events.combo(), 1);
+        assert_eq!(events.passed_objects(), 9);
+
+        let expected = GradualPerformance::new(difficulty, &map).nth(state, 8);
+        assert_eq!(events.calculate(), expected);
+    }
+
+    #[test]
+    fn catch_tiny_droplets() {
+        let map = Beatmap::from_path("./resources/2118524.osu").unwrap();
+        let mut events = HitEventPerformance::new(Difficulty::new(), &map);
+
+        events.record(HitResult::Great);
+        events.record(HitResult::SmallTickHit);
+        events.record(HitResult::SmallTickMiss);
+        events.record(HitResult::LargeTickHit);
+
+        assert_eq!(events.passed_objects(), 2);
+        assert_eq!(events.combo(), 2);
+        assert_eq!(events.state().n50, 1);
+        assert_eq!(events.state().n_katu, 1);
+    }
+
+    #[test]
+    fn mania_lazer_hold_notes() {
+        let map = Beatmap::from_path("./resources/1638954.osu").unwrap();
+
+        let n_holds = map.hit_objects.iter().filter(|h| h.is_hold_note()).count();
+        assert!(n_holds > 0);
+
+        let n_results = map.hit_objects.len() + n_holds;
+
+        let mut lazer = HitEventPerformance::new(Difficulty::new().lazer(true), &map);
+        let mut stable = HitEventPerformance::new(Difficulty::new().lazer(false), &map);
+
+        for _ in 0..n_results {
+            lazer.record(HitResult::Perfect);
+            stable.record(HitResult::Perfect);
+        }
+
+        assert_eq!(lazer.passed_objects(), map.hit_objects.len());
+        assert_eq!(stable.passed_objects(), map.hit_objects.len());
+        assert_eq!(lazer.state().n_geki as usize, n_results);
+    }
+
+    #[test]
+    fn mania_interleaved_hold_notes() {
+        let map = BeatmapBuilder::new()
+            .mode(GameMode::Mania)
+            .cs(4.0)
+            .timing_point(0.0, 500.0)
+            .hold(64.0, 1000.0, 1000.0)
+            .hold(192.0, 1500.0, 1000.0)
+            .hold(320.0, 3000.0, 500.0)
+            .build()
+            .unwrap();
+
+        let mut events = HitEventPerformance::new(Difficulty::new().lazer(true), &map);
+
+        // Head A, head B, tail A, tail B
+        events.record_object(0, HitResult::Perfect);
+        events.record_object(1, HitResult::Perfect);
+        assert_eq!(events.passed_objects(), 0);
+
+        events.record_object(0, HitResult::Great);
+        assert_eq!(events.passed_objects(), 1);
+
+        events.record_object(1, HitResult::Good);
+        assert_eq!(events.passed_objects(), 2);
+
+        // The tail of the last hold note completes it
+        events.record(HitResult::Perfect);
+        assert_eq!(events.passed_objects(), 2);
+        events.record(HitResult::Perfect);
+        assert_eq!(events.passed_objects(), 3);
+
+        assert_eq!(events.state().n_geki, 4);
+        assert_eq!(events.state().n300, 1);
+        assert_eq!(events.state().n_katu, 1);
+    }
+
+    #[test]
+    fn out_of_order_completion() {
+        let map = BeatmapBuilder::new()
+            .mode(GameMode::Mania)
+            .cs(4.0)
+            .timing_point(0.0, 500.0)
+            .hold(64.0, 1000.0, 2000.0)
+            .circle(Pos::new(192.0, 192.0), 1500.0)
+            .circle(Pos::new(320.0, 192.0), 2000.0)
+            .build()
+            .unwrap();
+
+        let mut events = HitEventPerformance::new(Difficulty::new().lazer(true), &map);
+
+        events.record_object(0, HitResult::Perfect);
+        events.record_object(1, HitResult::Perfect);
+        events.record_object(2, HitResult::Perfect);
+        assert_eq!(events.passed_objects(), 0);
+        assert!(events.calculate().is_none());
+
+        // The hold note's tail completes all three objects at once
+        events.record_object(0, HitResult::Perfect);
+        assert_eq!(events.passed_objects(), 3);
+        assert!(events.calculate().is_some());
+    }
+}
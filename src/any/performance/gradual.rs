@@ -1,7 +1,7 @@
 use rosu_map::section::general::GameMode;
 
 use crate::{
-    any::{PerformanceAttributes, ScoreState},
+    any::{difficulty::checkpoints::StateHeader, PerformanceAttributes, ResumeError, ScoreState},
     catch::{Catch, CatchGradualPerformance},
     mania::{Mania, ManiaGradualPerformance},
     model::mode::{ConvertError, IGameMode},
@@ -153,6 +153,109 @@ impl GradualPerformance {
         }
     }
 
+    /// Process all objects that are judged at or before `time`, i.e. the
+    /// current playback position in milliseconds, and calculate the
+    /// performance attributes for the resulting score state.
+    ///
+    /// See [`GradualDifficulty::advance_to_time`].
+    ///
+    /// [`GradualDifficulty::advance_to_time`]: crate::GradualDifficulty::advance_to_time
+    pub fn advance_to_time(
+        &mut self,
+        state: ScoreState,
+        time: f64,
+    ) -> Option<PerformanceAttributes> {
+        match self {
+            GradualPerformance::Osu(gradual) => gradual
+                .advance_to_time(state.into(), time)
+                .map(PerformanceAttributes::Osu),
+            GradualPerformance::Taiko(gradual) => gradual
+                .advance_to_time(state.into(), time)
+                .map(PerformanceAttributes::Taiko),
+            GradualPerformance::Catch(gradual) => gradual
+                .advance_to_time(state.into(), time)
+                .map(PerformanceAttributes::Catch),
+            GradualPerformance::Mania(gradual) => gradual
+                .advance_to_time(state.into(), time)
+                .map(PerformanceAttributes::Mania),
+        }
+    }
+
+    /// Take a snapshot of the calculator's state every `interval` processed
+    /// objects so that [`GradualPerformance::seek`] can rewind without
+    /// starting over.
+    ///
+    /// See [`GradualDifficulty::checkpoint_interval`].
+    ///
+    /// [`GradualDifficulty::checkpoint_interval`]: crate::GradualDifficulty::checkpoint_interval
+    #[must_use]
+    pub fn checkpoint_interval(self, interval: usize) -> Self {
+        match self {
+            Self::Osu(gradual) => Self::Osu(gradual.checkpoint_interval(interval)),
+            Self::Taiko(gradual) => Self::Taiko(gradual.checkpoint_interval(interval)),
+            Self::Catch(gradual) => Self::Catch(gradual.checkpoint_interval(interval)),
+            Self::Mania(gradual) => Self::Mania(gradual.checkpoint_interval(interval)),
+        }
+    }
+
+    /// Move to the state after processing `idx` objects so that the next call
+    /// of [`GradualPerformance::next`] processes the object at index `idx`.
+    ///
+    /// See [`GradualDifficulty::seek`].
+    ///
+    /// [`GradualDifficulty::seek`]: crate::GradualDifficulty::seek
+    pub fn seek(&mut self, idx: usize) {
+        match self {
+            Self::Osu(gradual) => gradual.seek(idx),
+            Self::Taiko(gradual) => gradual.seek(idx),
+            Self::Catch(gradual) => gradual.seek(idx),
+            Self::Mania(gradual) => gradual.seek(idx),
+        }
+    }
+
+    /// Serialize the current state so that it can be resumed through
+    /// [`GradualPerformance::resume`], e.g. in another process.
+    ///
+    /// See [`GradualDifficulty::save_state`].
+    ///
+    /// [`GradualDifficulty::save_state`]: crate::GradualDifficulty::save_state
+    pub fn save_state(&self) -> Vec<u8> {
+        match self {
+            Self::Osu(gradual) => gradual.save_state(),
+            Self::Taiko(gradual) => gradual.save_state(),
+            Self::Catch(gradual) => gradual.save_state(),
+            Self::Mania(gradual) => gradual.save_state(),
+        }
+    }
+
+    /// Resume a state that was serialized through
+    /// [`GradualPerformance::save_state`].
+    ///
+    /// See [`GradualDifficulty::resume`].
+    ///
+    /// [`GradualDifficulty::resume`]: crate::GradualDifficulty::resume
+    pub fn resume(
+        difficulty: Difficulty,
+        map: &Beatmap,
+        state: &[u8],
+    ) -> Result<Self, ResumeError> {
+        match StateHeader::peek_mode(state) {
+            Some(GameMode::Osu) => {
+                OsuGradualPerformance::resume(difficulty, map, state).map(Self::Osu)
+            }
+            Some(GameMode::Taiko) => {
+                TaikoGradualPerformance::resume(difficulty, map, state).map(Self::Taiko)
+            }
+            Some(GameMode::Catch) => {
+                CatchGradualPerformance::resume(difficulty, map, state).map(Self::Catch)
+            }
+            Some(GameMode::Mania) => {
+                ManiaGradualPerformance::resume(difficulty, map, state).map(Self::Mania)
+            }
+            None => Err(ResumeError::Invalid),
+        }
+    }
+
     /// Returns the amount of remaining objects.
     #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
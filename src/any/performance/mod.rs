@@ -9,6 +9,7 @@ use self::into::IntoPerformance;
 
 use super::{attributes::PerformanceAttributes, score_state::ScoreState};
 
+pub mod events;
 pub mod gradual;
 pub mod into;
 
@@ -253,6 +254,18 @@ impl<'map> Performance<'map> {
         }
     }
 
+    /// Whether objects should be moved the way visual mods such as Wiggle or
+    /// Barrel Roll move them in osu!lazer before evaluating difficulty.
+    ///
+    /// Only relevant for osu!standard.
+    pub fn simulate_mod_geometry(self, simulate_mod_geometry: bool) -> Self {
+        if let Self::Osu(osu) = self {
+            Self::Osu(osu.simulate_mod_geometry(simulate_mod_geometry))
+        } else {
+            self
+        }
+    }
+
     /// Provide parameters through a [`ScoreState`].
     pub fn state(self, state: ScoreState) -> Self {
         match self {
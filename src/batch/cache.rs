@@ -0,0 +1,84 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use rosu_map::section::general::GameMode;
+
+use crate::{model::mode::ConvertError, Beatmap, GameMods};
+
+/// Converted maps shared across all jobs of a batch.
+///
+/// Maps are identified by the address of their [`Arc`] so jobs must share
+/// the same [`Arc`] to share the conversion.
+#[derive(Default)]
+pub(super) struct ConvertCache {
+    entries: Mutex<HashMap<ConvertKey, Arc<Entry>>>,
+}
+
+/// Everything that affects the outcome of a conversion.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+struct ConvertKey {
+    map: usize,
+    mode: u8,
+    mania_keys: Option<u32>,
+    dual_stages: bool,
+}
+
+struct Entry {
+    /// Keeps the original map alive so that its address is not re-used for
+    /// another map within the same batch.
+    _source: Arc<Beatmap>,
+    converted: OnceLock<Result<Arc<Beatmap>, ConvertError>>,
+}
+
+impl ConvertCache {
+    /// Returns the map converted to the given mode, converting it only if
+    /// no other job did so already.
+    pub(super) fn get(
+        &self,
+        map: &Arc<Beatmap>,
+        mode: GameMode,
+        mods: &GameMods,
+    ) -> Result<Arc<Beatmap>, ConvertError> {
+        if map.mode == mode {
+            return Ok(Arc::clone(map));
+        }
+
+        let key = ConvertKey {
+            map: Arc::as_ptr(map) as usize,
+            mode: mode as u8,
+            mania_keys: (mode == GameMode::Mania)
+                .then(|| mods.mania_keys().map(f32::to_bits))
+                .flatten(),
+            dual_stages: mode == GameMode::Mania && mods.dual_stages(),
+        };
+
+        let entry = {
+            let mut entries = self.entries.lock().unwrap();
+
+            let entry = entries.entry(key).or_insert_with(|| {
+                Arc::new(Entry {
+                    _source: Arc::clone(map),
+                    converted: OnceLock::new(),
+                })
+            });
+
+            Arc::clone(entry)
+        };
+
+        // Converting outside of the lock so other maps are not blocked
+        entry
+            .converted
+            .get_or_init(|| {
+                map.convert_ref(mode, mods)
+                    .map(|map| Arc::new(map.into_owned()))
+            })
+            .clone()
+    }
+
+    #[cfg(test)]
+    pub(super) fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+}
@@ -0,0 +1,417 @@
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use rosu_map::section::general::GameMode;
+
+use crate::{
+    any::{DifficultyAttributes, PerformanceAttributes},
+    model::mode::ConvertError,
+    osu::Osu,
+    Beatmap, Difficulty, Performance,
+};
+
+use self::cache::ConvertCache;
+
+mod cache;
+
+/// Calculate many difficulty and performance jobs on a pool of threads.
+///
+/// Difficulty jobs that share the same [`Arc<Beatmap>`] and require the same
+/// conversion also share the converted map, i.e. each map is only converted
+/// once per batch regardless of how many jobs use it.
+///
+/// Additionally, osu!standard difficulty jobs on the same [`Arc<Beatmap>`]
+/// are calculated together on one thread so that settings with the same
+/// object geometry share their converted objects, see
+/// [`Difficulty::calculate_many`].
+///
+/// Results are returned in the same order as their jobs.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use refx_pp::{Beatmap, Difficulty};
+/// use refx_pp::batch::{BatchCalculator, BatchJob, BatchOutput};
+///
+/// let map = Arc::new(Beatmap::from_path("./resources/2785319.osu").unwrap());
+///
+/// let jobs = [0, 8, 16, 24].map(|mods| {
+///     BatchJob::difficulty(Arc::clone(&map), Difficulty::new().mods(mods))
+/// });
+///
+/// let results = BatchCalculator::new().threads(2).calculate(jobs);
+///
+/// for result in results {
+///     if let Ok(BatchOutput::Difficulty(attrs)) = result {
+///         println!("Stars: {}", attrs.stars());
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct BatchCalculator {
+    threads: Option<NonZeroUsize>,
+}
+
+impl BatchCalculator {
+    /// Create a new [`BatchCalculator`].
+    pub const fn new() -> Self {
+        Self { threads: None }
+    }
+
+    /// Specify the amount of worker threads.
+    ///
+    /// If unspecified or `0`, [`thread::available_parallelism`] is used.
+    pub const fn threads(mut self, threads: usize) -> Self {
+        self.threads = NonZeroUsize::new(threads);
+
+        self
+    }
+
+    /// Calculate all jobs and return their results in the same order.
+    pub fn calculate(
+        &self,
+        jobs: impl IntoIterator<Item = BatchJob>,
+    ) -> Vec<Result<BatchOutput, ConvertError>> {
+        self.calculate_with_cache(jobs, &ConvertCache::default())
+    }
+
+    fn calculate_with_cache(
+        &self,
+        jobs: impl IntoIterator<Item = BatchJob>,
+        cache: &ConvertCache,
+    ) -> Vec<Result<BatchOutput, ConvertError>> {
+        let jobs: Vec<_> = jobs.into_iter().collect();
+        let n_jobs = jobs.len();
+        let work = Work::plan(jobs);
+
+        let threads = self
+            .threads
+            .or_else(|| thread::available_parallelism().ok())
+            .map_or(1, NonZeroUsize::get)
+            .min(work.len());
+
+        let queue = Mutex::new(work.into_iter());
+
+        let next_work = || queue.lock().unwrap().next();
+
+        let finished: Vec<_> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut finished = Vec::new();
+
+                        while let Some(work) = next_work() {
+                            work.calculate(cache, &mut finished);
+                        }
+
+                        finished
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect()
+        });
+
+        let mut results: Vec<_> = (0..n_jobs).map(|_| None).collect();
+
+        for (i, result) in finished {
+            results[i] = Some(result);
+        }
+
+        results
+            .into_iter()
+            .map(|result| result.expect("every job is calculated"))
+            .collect()
+    }
+}
+
+/// A job for the [`BatchCalculator`].
+#[allow(clippy::large_enum_variant)]
+pub enum BatchJob {
+    /// Calculate the difficulty attributes of a map.
+    Difficulty {
+        map: Arc<Beatmap>,
+        difficulty: Difficulty,
+        /// The mode to convert the map to. If `None`, the map's own mode is
+        /// used.
+        mode: Option<GameMode>,
+    },
+    /// Calculate performance attributes.
+    ///
+    /// To avoid re-calculating the difficulty, the [`Performance`] should be
+    /// created from previously calculated attributes.
+    Performance(Performance<'static>),
+}
+
+impl BatchJob {
+    /// Calculate the difficulty attributes of a map on its own mode.
+    pub const fn difficulty(map: Arc<Beatmap>, difficulty: Difficulty) -> Self {
+        Self::Difficulty {
+            map,
+            difficulty,
+            mode: None,
+        }
+    }
+
+    /// Calculate the difficulty attributes of a map converted to a specific
+    /// [`GameMode`].
+    pub const fn difficulty_with_mode(
+        map: Arc<Beatmap>,
+        difficulty: Difficulty,
+        mode: GameMode,
+    ) -> Self {
+        Self::Difficulty {
+            map,
+            difficulty,
+            mode: Some(mode),
+        }
+    }
+
+    /// Calculate the performance attributes of a score.
+    pub fn performance(performance: impl Into<Performance<'static>>) -> Self {
+        Self::Performance(performance.into())
+    }
+
+    fn calculate(self, cache: &ConvertCache) -> Result<BatchOutput, ConvertError> {
+        match self {
+            Self::Difficulty {
+                map,
+                difficulty,
+                mode,
+            } => {
+                let mode = mode.unwrap_or(map.mode);
+                let map = cache.get(&map, mode, difficulty.get_mods())?;
+
+                Ok(BatchOutput::Difficulty(difficulty.calculate(&map)))
+            }
+            Self::Performance(performance) => Ok(BatchOutput::Performance(performance.calculate())),
+        }
+    }
+}
+
+/// Jobs that are calculated together on the same thread.
+#[allow(clippy::large_enum_variant)]
+enum Work {
+    Single(usize, BatchJob),
+    /// osu!standard difficulty jobs on the same unconverted map.
+    OsuDifficulties {
+        map: Arc<Beatmap>,
+        jobs: Vec<(usize, Difficulty)>,
+    },
+}
+
+impl Work {
+    /// Group the jobs so that osu!standard difficulty jobs on the same map
+    /// can share their converted objects.
+    fn plan(jobs: Vec<BatchJob>) -> Vec<Self> {
+        let mut work = Vec::with_capacity(jobs.len());
+
+        // Index into `work` of the group of each map. The maps are kept
+        // alive by their group so their pointers can't be reused.
+        let mut groups: HashMap<*const Beatmap, usize> = HashMap::new();
+
+        for (i, job) in jobs.into_iter().enumerate() {
+            let (map, difficulty) = match job {
+                BatchJob::Difficulty {
+                    map,
+                    difficulty,
+                    mode: None | Some(GameMode::Osu),
+                } if map.mode == GameMode::Osu => (map, difficulty),
+                job => {
+                    work.push(Self::Single(i, job));
+
+                    continue;
+                }
+            };
+
+            if let Some(&idx) = groups.get(&Arc::as_ptr(&map)) {
+                if let Self::OsuDifficulties { jobs, .. } = &mut work[idx] {
+                    jobs.push((i, difficulty));
+                }
+            } else {
+                groups.insert(Arc::as_ptr(&map), work.len());
+
+                work.push(Self::OsuDifficulties {
+                    map,
+                    jobs: vec![(i, difficulty)],
+                });
+            }
+        }
+
+        work
+    }
+
+    fn calculate(
+        self,
+        cache: &ConvertCache,
+        finished: &mut Vec<(usize, Result<BatchOutput, ConvertError>)>,
+    ) {
+        match self {
+            Self::Single(i, job) => finished.push((i, job.calculate(cache))),
+            Self::OsuDifficulties { map, jobs } => {
+                let difficulties: Vec<_> = jobs
+                    .iter()
+                    .map(|(_, difficulty)| difficulty.clone())
+                    .collect();
+
+                let attrs =
+                    Osu::difficulty_many(&difficulties, &map).expect("no conversion required");
+
+                let outputs = attrs
+                    .into_iter()
+                    .map(|attrs| Ok(BatchOutput::Difficulty(DifficultyAttributes::Osu(attrs))));
+
+                finished.extend(jobs.into_iter().map(|(i, _)| i).zip(outputs));
+            }
+        }
+    }
+}
+
+/// The result of a [`BatchJob`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum BatchOutput {
+    Difficulty(DifficultyAttributes),
+    Performance(PerformanceAttributes),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::any::ScoreState;
+
+    use super::*;
+
+    #[test]
+    fn matches_sequential() {
+        let map = Arc::new(Beatmap::from_path("./resources/2785319.osu").unwrap());
+        let mods = [0, 8, 16, 64, 88];
+
+        let jobs =
+            mods.map(|mods| BatchJob::difficulty(Arc::clone(&map), Difficulty::new().mods(mods)));
+        let results = BatchCalculator::new().threads(3).calculate(jobs);
+
+        for (mods, result) in mods.into_iter().zip(results) {
+            let expected = Difficulty::new().mods(mods).calculate(&map);
+            assert_eq!(result.unwrap(), BatchOutput::Difficulty(expected));
+        }
+    }
+
+    #[test]
+    fn shares_conversions() {
+        let map = Arc::new(Beatmap::from_path("./resources/2785319.osu").unwrap());
+
+        let jobs = (0..8).map(|i| {
+            let difficulty = Difficulty::new().clock_rate(1.0 + f64::from(i) / 10.0);
+
+            BatchJob::difficulty_with_mode(Arc::clone(&map), difficulty, GameMode::Taiko)
+        });
+
+        let cache = ConvertCache::default();
+        let results = BatchCalculator::new()
+            .threads(4)
+            .calculate_with_cache(jobs, &cache);
+
+        assert_eq!(cache.len(), 1);
+        assert!(results.iter().all(Result::is_ok));
+
+        let jobs = [
+            BatchJob::difficulty_with_mode(Arc::clone(&map), Difficulty::new(), GameMode::Catch),
+            BatchJob::difficulty_with_mode(
+                Arc::clone(&map),
+                Difficulty::new().mods(8),
+                GameMode::Mania,
+            ),
+            BatchJob::difficulty_with_mode(
+                Arc::clone(&map),
+                Difficulty::new().mods(16),
+                GameMode::Mania,
+            ),
+            BatchJob::difficulty(Arc::clone(&map), Difficulty::new()),
+        ];
+
+        BatchCalculator::new().calculate_with_cache(jobs, &cache);
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[test]
+    fn groups_osu_difficulties() {
+        let osu = Arc::new(Beatmap::from_path("./resources/2785319.osu").unwrap());
+        let taiko = Arc::new(Beatmap::from_path("./resources/1028484.osu").unwrap());
+
+        let jobs = vec![
+            BatchJob::difficulty(Arc::clone(&osu), Difficulty::new()),
+            BatchJob::difficulty(Arc::clone(&taiko), Difficulty::new()),
+            BatchJob::difficulty_with_mode(
+                Arc::clone(&osu),
+                Difficulty::new().mods(8),
+                GameMode::Osu,
+            ),
+            BatchJob::difficulty_with_mode(Arc::clone(&osu), Difficulty::new(), GameMode::Taiko),
+            BatchJob::difficulty(Arc::clone(&osu), Difficulty::new().mods(64)),
+        ];
+
+        let work = Work::plan(jobs);
+        assert_eq!(work.len(), 3);
+
+        let Work::OsuDifficulties { ref jobs, .. } = work[0] else {
+            panic!("expected osu!standard group");
+        };
+
+        let indices: Vec<_> = jobs.iter().map(|(i, _)| *i).collect();
+        assert_eq!(indices, [0, 2, 4]);
+
+        let mut finished = Vec::new();
+
+        for work in work {
+            work.calculate(&ConvertCache::default(), &mut finished);
+        }
+
+        finished.sort_by_key(|(i, _)| *i);
+
+        let expected = [
+            Difficulty::new().calculate(&osu),
+            Difficulty::new().calculate(&taiko),
+            Difficulty::new().mods(8).calculate(&osu),
+        ];
+
+        for ((_, result), expected) in finished.into_iter().zip(expected) {
+            assert_eq!(result.unwrap(), BatchOutput::Difficulty(expected));
+        }
+    }
+
+    #[test]
+    fn performance_and_errors() {
+        let map = Arc::new(Beatmap::from_path("./resources/1028484.osu").unwrap());
+        let attrs = Difficulty::new().calculate(&map);
+
+        let mut state = ScoreState::new();
+        state.max_combo = 100;
+        state.n300 = 100;
+
+        let jobs = [
+            BatchJob::performance(attrs.clone().performance().state(state.clone())),
+            BatchJob::difficulty_with_mode(Arc::clone(&map), Difficulty::new(), GameMode::Osu),
+        ];
+
+        let mut results = BatchCalculator::new().calculate(jobs).into_iter();
+
+        let expected = attrs.performance().state(state).calculate();
+        assert_eq!(
+            results.next().unwrap().unwrap(),
+            BatchOutput::Performance(expected)
+        );
+        assert!(matches!(
+            results.next(),
+            Some(Err(ConvertError::Convert { .. }))
+        ));
+    }
+}
@@ -0,0 +1,460 @@
+use std::{fmt::Display, path::PathBuf, str::FromStr};
+
+use refx_pp::model::{
+    mode::GameMode,
+    mods::rosu_mods::{GameModsIntermode, GameModsLegacy},
+};
+
+pub const HELP: &str = "\
+Calculate difficulty and performance attributes of an osu! beatmap.
+
+Usage: refx-pp <PATH> [OPTIONS]
+       refx-pp batch <DIR> [OPTIONS]
+       refx-pp serve [OPTIONS]
+
+Run `refx-pp batch --help` or `refx-pp serve --help` for the options of the
+other commands.
+
+Arguments:
+  <PATH>                   Path to a .osu file
+
+Options:
+  -m, --mods <MODS>        Mods as bits (e.g. 72) or acronyms (e.g. HDDT)
+      --mode <MODE>        Convert the map to osu, taiko, catch, or mania
+  -a, --acc <ACC>          Accuracy in percent
+      --n300 <N>           Amount of 300s
+      --n100 <N>           Amount of 100s
+      --n50 <N>            Amount of 50s
+      --n-geki <N>         Amount of gekis (mania: 320s)
+      --n-katu <N>         Amount of katus (mania: 200s, catch: tiny droplet misses)
+  -x, --misses <N>         Amount of misses
+  -c, --combo <N>          Maximum combo
+      --passed <N>         Amount of passed objects for failed plays
+      --clock-rate <RATE>  Custom clock rate
+      --ar <AR>            Override the approach rate
+      --cs <CS>            Override the circle size
+      --od <OD>            Override the overall difficulty
+      --hp <HP>            Override the drain rate
+      --with-mods          Apply mods on top of the AR/CS/OD/HP overrides
+      --stable             Calculate for osu!stable instead of osu!lazer
+      --json               Print JSON instead of a table
+  -h, --help               Print this help
+";
+
+pub const BATCH_HELP: &str = "\
+Calculate star ratings and strain statistics of all .osu files in a directory.
+
+Usage: refx-pp batch <DIR> [OPTIONS]
+
+Arguments:
+  <DIR>                    Directory that is searched recursively for .osu files
+
+Options:
+  -m, --mods <MODS>        Comma-separated mod combinations [default: NM]
+  -f, --format <FORMAT>    Output format, either csv or jsonl [default: csv]
+  -o, --output <FILE>      Write to a file instead of stdout
+      --stable             Calculate for osu!stable instead of osu!lazer
+  -h, --help               Print this help
+";
+
+pub const SERVE_HELP: &str = "\
+Serve difficulty, performance, and strain calculations over HTTP.
+
+Usage: refx-pp serve [OPTIONS]
+
+Endpoints:
+  POST /difficulty         Difficulty attributes
+  POST /performance        Difficulty and performance attributes
+  POST /strains            Strains of every skill
+
+The request body is the content of a .osu file. Every response contains the
+map's hash; passing it as the `hash` query parameter with an empty body
+reuses the cached map. Calculation options are passed as query parameters
+named like the options of the calculate command, e.g.
+`/performance?mods=HDDT&acc=98.5&misses=2&stable`.
+
+Options:
+      --addr <ADDR>        Address to listen on [default: 127.0.0.1:8080]
+      --cache <N>          Amount of decoded maps to keep in memory [default: 64]
+      --workers <N>        Amount of requests to handle concurrently
+                           [default: number of CPUs]
+  -h, --help               Print this help
+";
+
+/// Parsed command-line arguments.
+#[derive(Debug, Default, PartialEq)]
+pub struct Args {
+    pub path: PathBuf,
+    pub mods: GameModsIntermode,
+    pub mode: Option<GameMode>,
+    pub acc: Option<f64>,
+    pub n300: Option<u32>,
+    pub n100: Option<u32>,
+    pub n50: Option<u32>,
+    pub n_geki: Option<u32>,
+    pub n_katu: Option<u32>,
+    pub misses: Option<u32>,
+    pub combo: Option<u32>,
+    pub passed_objects: Option<u32>,
+    pub clock_rate: Option<f64>,
+    pub ar: Option<f32>,
+    pub cs: Option<f32>,
+    pub od: Option<f32>,
+    pub hp: Option<f32>,
+    pub with_mods: bool,
+    pub stable: bool,
+    pub json: bool,
+}
+
+impl Args {
+    /// Apply a single option, fetching its value only if the option needs
+    /// one.
+    ///
+    /// Returns `false` if the option is unknown.
+    pub fn set(
+        &mut self,
+        name: &str,
+        mut value: impl FnMut() -> Result<String, String>,
+    ) -> Result<bool, String> {
+        match name {
+            "-m" | "--mods" => self.mods = parse_mods(&value()?)?,
+            "--mode" => self.mode = Some(parse_mode(&value()?)?),
+            "-a" | "--acc" => self.acc = Some(parse(name, &value()?)?),
+            "--n300" => self.n300 = Some(parse(name, &value()?)?),
+            "--n100" => self.n100 = Some(parse(name, &value()?)?),
+            "--n50" => self.n50 = Some(parse(name, &value()?)?),
+            "--n-geki" => self.n_geki = Some(parse(name, &value()?)?),
+            "--n-katu" => self.n_katu = Some(parse(name, &value()?)?),
+            "-x" | "--misses" => self.misses = Some(parse(name, &value()?)?),
+            "-c" | "--combo" => self.combo = Some(parse(name, &value()?)?),
+            "--passed" => self.passed_objects = Some(parse(name, &value()?)?),
+            "--clock-rate" => self.clock_rate = Some(parse(name, &value()?)?),
+            "--ar" => self.ar = Some(parse(name, &value()?)?),
+            "--cs" => self.cs = Some(parse(name, &value()?)?),
+            "--od" => self.od = Some(parse(name, &value()?)?),
+            "--hp" => self.hp = Some(parse(name, &value()?)?),
+            "--with-mods" => self.with_mods = true,
+            "--stable" => self.stable = true,
+            "--json" => self.json = true,
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+}
+
+/// Parsed arguments of the batch command.
+#[derive(Debug, PartialEq)]
+pub struct BatchArgs {
+    pub dir: PathBuf,
+    pub mods: Vec<GameModsIntermode>,
+    pub format: Format,
+    pub output: Option<PathBuf>,
+    pub stable: bool,
+}
+
+/// Parsed arguments of the serve command.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ServeArgs {
+    pub addr: String,
+    pub cache: usize,
+    /// `0` means one worker per CPU.
+    pub workers: usize,
+}
+
+impl Default for ServeArgs {
+    fn default() -> Self {
+        Self {
+            addr: String::from("127.0.0.1:8080"),
+            cache: 64,
+            workers: 0,
+        }
+    }
+}
+
+/// Output format of the batch command.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Csv,
+    JsonLines,
+}
+
+/// What the command-line arguments ask for.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Help,
+    BatchHelp,
+    Calculate(Box<Args>),
+    Batch(BatchArgs),
+    ServeHelp,
+    Serve(ServeArgs),
+}
+
+impl Command {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut args = args.into_iter().peekable();
+
+        if args.next_if(|arg| arg == "batch").is_some() {
+            return Self::parse_batch(args);
+        } else if args.next_if(|arg| arg == "serve").is_some() {
+            return Self::parse_serve(args);
+        }
+
+        let mut parsed = Args::default();
+        let mut path = None;
+
+        while let Some(arg) = args.next() {
+            // Support both `--opt value` and `--opt=value`
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_owned())),
+                _ => (arg.as_str(), None),
+            };
+
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("missing value for `{name}`"))
+            };
+
+            match name {
+                "-h" | "--help" => return Ok(Self::Help),
+                _ if parsed.set(name, &mut value)? => {}
+                _ if name.starts_with('-') && name.len() > 1 => {
+                    return Err(format!("unknown option `{name}`"))
+                }
+                _ if path.is_none() => path = Some(PathBuf::from(arg)),
+                _ => return Err(format!("unexpected argument `{arg}`")),
+            }
+        }
+
+        parsed.path = path.ok_or("missing beatmap path")?;
+
+        Ok(Self::Calculate(Box::new(parsed)))
+    }
+
+    fn parse_batch(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut dir = None;
+        let mut mods = None;
+        let mut format = Format::default();
+        let mut output = None;
+        let mut stable = false;
+
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_owned())),
+                _ => (arg.as_str(), None),
+            };
+
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("missing value for `{name}`"))
+            };
+
+            match name {
+                "-h" | "--help" => return Ok(Self::BatchHelp),
+                "-m" | "--mods" => {
+                    let value = value()?;
+                    let list = value.split(',').map(str::trim).map(parse_mods);
+                    mods = Some(list.collect::<Result<_, _>>()?);
+                }
+                "-f" | "--format" => format = parse_format(&value()?)?,
+                "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+                "--stable" => stable = true,
+                _ if name.starts_with('-') && name.len() > 1 => {
+                    return Err(format!("unknown option `{name}`"))
+                }
+                _ if dir.is_none() => dir = Some(PathBuf::from(arg)),
+                _ => return Err(format!("unexpected argument `{arg}`")),
+            }
+        }
+
+        Ok(Self::Batch(BatchArgs {
+            dir: dir.ok_or("missing directory")?,
+            mods: mods.unwrap_or_else(|| vec![GameModsIntermode::new()]),
+            format,
+            output,
+            stable,
+        }))
+    }
+
+    fn parse_serve(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = ServeArgs::default();
+
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_owned())),
+                _ => (arg.as_str(), None),
+            };
+
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("missing value for `{name}`"))
+            };
+
+            match name {
+                "-h" | "--help" => return Ok(Self::ServeHelp),
+                "--addr" => parsed.addr = value()?,
+                "--cache" => parsed.cache = parse(name, &value()?)?,
+                "--workers" => parsed.workers = parse(name, &value()?)?,
+                _ if name.starts_with('-') && name.len() > 1 => {
+                    return Err(format!("unknown option `{name}`"))
+                }
+                _ => return Err(format!("unexpected argument `{arg}`")),
+            }
+        }
+
+        Ok(Self::Serve(parsed))
+    }
+}
+
+fn parse<T>(name: &str, value: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value
+        .parse()
+        .map_err(|err| format!("invalid value `{value}` for `{name}`: {err}"))
+}
+
+/// Parse mods either as bits or as acronyms.
+pub fn parse_mods(value: &str) -> Result<GameModsIntermode, String> {
+    if let Ok(bits) = value.parse::<u32>() {
+        return GameModsLegacy::try_from_bits(bits)
+            .map(GameModsIntermode::from)
+            .ok_or_else(|| format!("invalid mod bits `{bits}`"));
+    }
+
+    GameModsIntermode::try_from_acronyms(value).ok_or_else(|| format!("invalid mods `{value}`"))
+}
+
+fn parse_format(value: &str) -> Result<Format, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "csv" => Ok(Format::Csv),
+        "jsonl" | "json" => Ok(Format::JsonLines),
+        _ => Err(format!("invalid format `{value}`")),
+    }
+}
+
+pub fn parse_mode(value: &str) -> Result<GameMode, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "osu" | "std" | "0" => Ok(GameMode::Osu),
+        "taiko" | "1" => Ok(GameMode::Taiko),
+        "catch" | "ctb" | "fruits" | "2" => Ok(GameMode::Catch),
+        "mania" | "3" => Ok(GameMode::Mania),
+        _ => Err(format!("invalid mode `{value}`")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Command, String> {
+        Command::parse(args.iter().map(ToString::to_string))
+    }
+
+    #[test]
+    fn full() {
+        let command = parse_args(&[
+            "map.osu",
+            "-m",
+            "HDDT",
+            "--acc=98.5",
+            "-x",
+            "2",
+            "--combo",
+            "500",
+            "--ar",
+            "9.5",
+            "--stable",
+            "--json",
+        ])
+        .unwrap();
+
+        let Command::Calculate(args) = command else {
+            panic!("expected calculate command");
+        };
+
+        assert_eq!(args.path, PathBuf::from("map.osu"));
+        assert_eq!(args.mods.bits(), 72);
+        assert_eq!(args.acc, Some(98.5));
+        assert_eq!(args.misses, Some(2));
+        assert_eq!(args.combo, Some(500));
+        assert_eq!(args.ar, Some(9.5));
+        assert!(args.stable && args.json && !args.with_mods);
+    }
+
+    #[test]
+    fn mods() {
+        assert_eq!(parse_mods("24").unwrap().bits(), 24);
+        assert_eq!(parse_mods("hdhr").unwrap().bits(), 24);
+        assert_eq!(parse_mods("NM").unwrap().bits(), 0);
+        assert!(parse_mods("QQ").is_err());
+    }
+
+    #[test]
+    fn batch() {
+        let command = parse_args(&["batch", "maps", "-m", "NM, HD,DT", "--format=jsonl"]).unwrap();
+
+        let Command::Batch(args) = command else {
+            panic!("expected batch command");
+        };
+
+        assert_eq!(args.dir, PathBuf::from("maps"));
+        assert_eq!(
+            args.mods.iter().map(|mods| mods.bits()).collect::<Vec<_>>(),
+            [0, 8, 64]
+        );
+        assert_eq!(args.format, Format::JsonLines);
+        assert_eq!(args.output, None);
+
+        let Ok(Command::Batch(args)) = parse_args(&["batch", "maps"]) else {
+            panic!("expected batch command");
+        };
+
+        assert_eq!(args.mods, [GameModsIntermode::new()]);
+        assert_eq!(args.format, Format::Csv);
+
+        assert_eq!(parse_args(&["batch", "-h"]), Ok(Command::BatchHelp));
+        assert!(parse_args(&["batch"]).is_err());
+        assert!(parse_args(&["batch", "maps", "--format", "xml"]).is_err());
+        assert!(parse_args(&["batch", "maps", "--mods", "HD,QQ"]).is_err());
+    }
+
+    #[test]
+    fn serve() {
+        assert_eq!(
+            parse_args(&["serve"]),
+            Ok(Command::Serve(ServeArgs::default()))
+        );
+
+        let Ok(Command::Serve(args)) =
+            parse_args(&["serve", "--addr=0.0.0.0:3000", "--cache=8", "--workers=4"])
+        else {
+            panic!("expected serve command");
+        };
+
+        assert_eq!(args.addr, "0.0.0.0:3000");
+        assert_eq!(args.cache, 8);
+        assert_eq!(args.workers, 4);
+        assert_eq!(parse_args(&["serve", "--help"]), Ok(Command::ServeHelp));
+        assert!(parse_args(&["serve", "--cache", "many"]).is_err());
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse_args(&["--help"]), Ok(Command::Help));
+        assert!(parse_args(&[]).is_err());
+        assert!(parse_args(&["map.osu", "--acc"]).is_err());
+        assert!(parse_args(&["map.osu", "--combo", "abc"]).is_err());
+        assert!(parse_args(&["map.osu", "--unknown"]).is_err());
+        assert!(parse_args(&["a.osu", "b.osu"]).is_err());
+    }
+}
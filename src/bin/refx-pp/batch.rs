@@ -0,0 +1,278 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use refx_pp::{any::Strains, model::mods::rosu_mods::GameModsIntermode, Beatmap, Difficulty};
+
+use crate::{
+    args::{BatchArgs, Format},
+    output::{self, Fields, Value},
+};
+
+/// Skills whose strains are summarized, across all modes.
+const SKILLS: [&str; 10] = [
+    "aim",
+    "aim_no_sliders",
+    "speed",
+    "flashlight",
+    "color",
+    "reading",
+    "rhythm",
+    "stamina",
+    "movement",
+    "strain",
+];
+
+/// Column names of the strain statistics in the same order as [`SKILLS`].
+const STRAIN_COLUMNS: [[&str; 3]; 10] = [
+    ["aim_peak", "aim_mean", "aim_median"],
+    [
+        "aim_no_sliders_peak",
+        "aim_no_sliders_mean",
+        "aim_no_sliders_median",
+    ],
+    ["speed_peak", "speed_mean", "speed_median"],
+    ["flashlight_peak", "flashlight_mean", "flashlight_median"],
+    ["color_peak", "color_mean", "color_median"],
+    ["reading_peak", "reading_mean", "reading_median"],
+    ["rhythm_peak", "rhythm_mean", "rhythm_median"],
+    ["stamina_peak", "stamina_mean", "stamina_median"],
+    ["movement_peak", "movement_mean", "movement_median"],
+    ["strain_peak", "strain_mean", "strain_median"],
+];
+
+const BASE_COLUMNS: [&str; 7] = [
+    "path",
+    "mods",
+    "mode",
+    "stars",
+    "max_combo",
+    "n_objects",
+    "error",
+];
+
+/// Run the batch command and return the amount of written rows.
+pub fn run(args: &BatchArgs) -> Result<usize, String> {
+    let paths = find_maps(&args.dir)
+        .map_err(|err| format!("failed to read `{}`: {err}", args.dir.display()))?;
+
+    let writer: Box<dyn Write> = match args.output {
+        Some(ref path) => {
+            let file = File::create(path)
+                .map_err(|err| format!("failed to create `{}`: {err}", path.display()))?;
+
+            Box::new(file)
+        }
+        None => Box::new(io::stdout().lock()),
+    };
+
+    let mut writer = BufWriter::new(writer);
+    let write_err = |err: io::Error| format!("failed to write output: {err}");
+
+    if args.format == Format::Csv {
+        let header = BASE_COLUMNS
+            .into_iter()
+            .chain(STRAIN_COLUMNS.into_iter().flatten());
+
+        writeln!(writer, "{}", header.collect::<Vec<_>>().join(",")).map_err(write_err)?;
+    }
+
+    let mut rows = 0;
+
+    for path in paths {
+        for row in map_rows(&path, &args.mods, !args.stable) {
+            let line = match args.format {
+                Format::Csv => csv_row(&row),
+                Format::JsonLines => {
+                    let mut line = String::new();
+                    output::write_object(&mut line, &row);
+
+                    line
+                }
+            };
+
+            writeln!(writer, "{line}").map_err(write_err)?;
+            rows += 1;
+        }
+    }
+
+    writer.flush().map_err(write_err)?;
+
+    Ok(rows)
+}
+
+/// Recursively collect all `.osu` files in sorted order.
+fn find_maps(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    fn visit(dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                visit(&path, paths)?;
+            } else if path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("osu"))
+            {
+                paths.push(path);
+            }
+        }
+
+        Ok(())
+    }
+
+    let mut paths = Vec::new();
+    visit(dir, &mut paths)?;
+    paths.sort_unstable();
+
+    Ok(paths)
+}
+
+/// Calculate one row per mod combination, or a single error row if the map
+/// could not be calculated.
+fn map_rows(path: &Path, mods: &[GameModsIntermode], lazer: bool) -> Vec<Fields> {
+    let path_value = Value::from(path.display().to_string().as_str());
+
+    let error_row = |err: String| {
+        vec![
+            ("path", path_value.clone()),
+            ("mods", Value::Null),
+            ("mode", Value::Null),
+            ("stars", Value::Null),
+            ("max_combo", Value::Null),
+            ("n_objects", Value::Null),
+            ("error", Value::Str(err)),
+        ]
+    };
+
+    let map = match Beatmap::from_path(path) {
+        Ok(map) => map,
+        Err(err) => return vec![error_row(format!("failed to decode: {err}"))],
+    };
+
+    if let Err(sus) = map.check_suspicion() {
+        return vec![error_row(format!("too suspicious: {sus}"))];
+    }
+
+    let results = Difficulty::new()
+        .lazer(lazer)
+        .calculate_many_with_strains(&map, mods);
+
+    mods.iter()
+        .zip(results)
+        .map(|(mods, (attrs, strains))| {
+            let mut row = vec![
+                ("path", path_value.clone()),
+                ("mods", Value::Str(mods.to_string())),
+                ("mode", output::mode_name(&attrs).into()),
+                ("stars", attrs.stars().into()),
+                ("max_combo", attrs.max_combo().into()),
+                ("n_objects", (map.hit_objects.len() as u32).into()),
+                ("error", Value::Null),
+            ];
+
+            row.extend(strain_stats(&strains));
+
+            row
+        })
+        .collect()
+}
+
+/// Peak, mean, and median of every skill's strains.
+fn strain_stats(strains: &Strains) -> Fields {
+    let skills = output::skill_strains(strains);
+
+    SKILLS
+        .iter()
+        .zip(STRAIN_COLUMNS)
+        .flat_map(|(skill, columns)| {
+            let values = skills
+                .iter()
+                .find(|(name, _)| name == skill)
+                .map(|(_, values)| *values);
+
+            let [peak, mean, median] = summarize(values.unwrap_or_default());
+
+            [(columns[0], peak), (columns[1], mean), (columns[2], median)]
+        })
+        .filter(|(_, value)| *value != Value::Null)
+        .collect()
+}
+
+// `usize::is_multiple_of` requires Rust 1.87
+#[allow(clippy::manual_is_multiple_of)]
+fn summarize(values: &[f64]) -> [Value; 3] {
+    if values.is_empty() {
+        return [Value::Null, Value::Null, Value::Null];
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable_by(f64::total_cmp);
+
+    let n = sorted.len();
+    let mean = sorted.iter().sum::<f64>() / n as f64;
+
+    let median = if n % 2 == 0 {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+    } else {
+        sorted[n / 2]
+    };
+
+    [sorted[n - 1].into(), mean.into(), median.into()]
+}
+
+/// Write the row's values in the order of the CSV header.
+fn csv_row(row: &[(&str, Value)]) -> String {
+    BASE_COLUMNS
+        .into_iter()
+        .chain(STRAIN_COLUMNS.into_iter().flatten())
+        .map(|column| {
+            row.iter()
+                .find(|(name, _)| *name == column)
+                .map_or_else(String::new, |(_, value)| output::csv_field(value))
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary() {
+        let [peak, mean, median] = summarize(&[1.0, 4.0, 2.0, 3.0]);
+
+        assert_eq!(peak, Value::Float(4.0));
+        assert_eq!(mean, Value::Float(2.5));
+        assert_eq!(median, Value::Float(2.5));
+        assert_eq!(summarize(&[]), [Value::Null, Value::Null, Value::Null]);
+    }
+
+    #[test]
+    fn rows() {
+        let path = Path::new("./resources/1028484.osu");
+        let mods = [GameModsIntermode::new(), GameModsIntermode::from_bits(64)];
+
+        let rows = map_rows(path, &mods, true);
+        assert_eq!(rows.len(), 2);
+
+        let csv = csv_row(&rows[1]);
+        let columns = BASE_COLUMNS.len() + 3 * STRAIN_COLUMNS.len();
+        assert_eq!(csv.split(',').count(), columns);
+        assert!(csv.starts_with("./resources/1028484.osu,DT,taiko,"));
+
+        let rows = map_rows(Path::new("./resources/missing.osu"), &mods, true);
+        assert_eq!(rows.len(), 1);
+        assert!(matches!(rows[0][6], ("error", Value::Str(_))));
+    }
+
+    #[test]
+    fn finds_maps() {
+        let paths = find_maps(Path::new("./resources")).unwrap();
+
+        assert!(paths.windows(2).all(|w| w[0] < w[1]));
+        assert!(paths.iter().any(|path| path.ends_with("2785319.osu")));
+    }
+}
@@ -0,0 +1,239 @@
+//! Command-line calculator for difficulty and performance attributes.
+
+use std::{borrow::Cow, env, process::ExitCode};
+
+use refx_pp::{any::DifficultyAttributes, Beatmap, Difficulty, GameMods, Performance};
+
+use self::{
+    args::{Args, BatchArgs, Command, ServeArgs, BATCH_HELP, HELP, SERVE_HELP},
+    output::{difficulty_fields, json, performance_fields, table, Fields, Value},
+};
+
+mod args;
+mod batch;
+mod output;
+mod server;
+
+fn main() -> ExitCode {
+    let command = match Command::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("error: {err}\n\n{HELP}");
+
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let args = match command {
+        Command::Help => {
+            print!("{HELP}");
+
+            return ExitCode::SUCCESS;
+        }
+        Command::BatchHelp => {
+            print!("{BATCH_HELP}");
+
+            return ExitCode::SUCCESS;
+        }
+        Command::Batch(args) => return run_batch(&args),
+        Command::ServeHelp => {
+            print!("{SERVE_HELP}");
+
+            return ExitCode::SUCCESS;
+        }
+        Command::Serve(args) => return run_server(&args),
+        Command::Calculate(args) => args,
+    };
+
+    match calculate(&args) {
+        Ok(output) => {
+            println!("{output}");
+
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_batch(args: &BatchArgs) -> ExitCode {
+    match batch::run(args) {
+        Ok(rows) => {
+            eprintln!("wrote {rows} rows");
+
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_server(args: &ServeArgs) -> ExitCode {
+    match server::Server::bind(&args.addr, args.cache, args.workers) {
+        Ok(server) => {
+            if let Ok(addr) = server.local_addr() {
+                eprintln!("listening on http://{addr}");
+            }
+
+            server.run();
+
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: failed to bind `{}`: {err}", args.addr);
+
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn calculate(args: &Args) -> Result<String, String> {
+    let map = Beatmap::from_path(&args.path)
+        .map_err(|err| format!("failed to decode `{}`: {err}", args.path.display()))?;
+
+    if let Err(sus) = map.check_suspicion() {
+        return Err(format!("map is too suspicious for calculation: {sus:?}"));
+    }
+
+    let sections = performance_sections(&map, args)?;
+
+    if args.json {
+        Ok(json(&sections))
+    } else {
+        Ok(table(&sections))
+    }
+}
+
+/// Convert the map if the arguments specify a mode.
+fn convert<'a>(map: &'a Beatmap, args: &Args, mods: &GameMods) -> Result<Cow<'a, Beatmap>, String> {
+    match args.mode {
+        Some(mode) => map
+            .convert_ref(mode, mods)
+            .map_err(|err| format!("failed to convert map: {err}")),
+        None => Ok(Cow::Borrowed(map)),
+    }
+}
+
+/// Difficulty and performance attributes of the score described by the
+/// arguments.
+fn performance_sections(map: &Beatmap, args: &Args) -> Result<[(&'static str, Fields); 2], String> {
+    let mods = GameMods::from(&args.mods);
+    let map = convert(map, args, &mods)?;
+
+    let difficulty = difficulty(args, mods);
+    let diff_attrs = difficulty.calculate(&map);
+
+    let mut performance = Performance::new(diff_attrs.clone())
+        .difficulty(difficulty)
+        .lazer(!args.stable);
+
+    if let Some(acc) = args.acc {
+        performance = performance.accuracy(acc);
+    }
+
+    let counts = [
+        (args.n300, Performance::n300 as fn(_, _) -> _),
+        (args.n100, Performance::n100),
+        (args.n50, Performance::n50),
+        (args.n_geki, Performance::n_geki),
+        (args.n_katu, Performance::n_katu),
+        (args.misses, Performance::misses),
+        (args.combo, Performance::combo),
+    ];
+
+    for (value, set) in counts {
+        if let Some(value) = value {
+            performance = set(performance, value);
+        }
+    }
+
+    let state = performance.generate_state();
+    let perf_attrs = performance.calculate();
+
+    let mut perf_fields = performance_fields(&perf_attrs);
+
+    perf_fields.extend([
+        ("combo", Value::from(state.max_combo)),
+        ("n300", state.n300.into()),
+        ("n100", state.n100.into()),
+        ("n50", state.n50.into()),
+        ("n_geki", state.n_geki.into()),
+        ("n_katu", state.n_katu.into()),
+        ("misses", state.misses.into()),
+    ]);
+
+    if let DifficultyAttributes::Osu(_) = diff_attrs {
+        perf_fields.extend([
+            ("large_tick_hits", Value::from(state.osu_large_tick_hits)),
+            ("small_tick_hits", state.osu_small_tick_hits.into()),
+            ("slider_end_hits", state.slider_end_hits.into()),
+        ]);
+    }
+
+    Ok([
+        ("difficulty", difficulty_fields(&diff_attrs)),
+        ("performance", perf_fields),
+    ])
+}
+
+fn difficulty(args: &Args, mods: GameMods) -> Difficulty {
+    let mut difficulty = Difficulty::new().mods(mods).lazer(!args.stable);
+
+    if let Some(clock_rate) = args.clock_rate {
+        difficulty = difficulty.clock_rate(clock_rate);
+    }
+
+    if let Some(passed_objects) = args.passed_objects {
+        difficulty = difficulty.passed_objects(passed_objects);
+    }
+
+    let overrides = [
+        (args.ar, Difficulty::ar as fn(_, _, _) -> _),
+        (args.cs, Difficulty::cs),
+        (args.od, Difficulty::od),
+        (args.hp, Difficulty::hp),
+    ];
+
+    for (value, set) in overrides {
+        if let Some(value) = value {
+            // `true` would use the value as is
+            difficulty = set(difficulty, value, !args.with_mods);
+        }
+    }
+
+    difficulty
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ar(args: &[&str]) -> f64 {
+        let args = ["./resources/2785319.osu"].iter().chain(args);
+        let Ok(Command::Calculate(args)) = Command::parse(args.map(ToString::to_string)) else {
+            panic!("expected calculate command");
+        };
+
+        let map = Beatmap::from_path(&args.path).unwrap();
+        let mods = GameMods::from(&args.mods);
+
+        let DifficultyAttributes::Osu(attrs) = difficulty(&args, mods).calculate(&map) else {
+            panic!("expected osu!standard attributes");
+        };
+
+        attrs.ar
+    }
+
+    #[test]
+    fn overrides_with_mods() {
+        assert!((ar(&["-m", "HR", "--ar", "9"]) - 9.0).abs() < f64::EPSILON);
+        assert!((ar(&["-m", "HR", "--ar", "9", "--with-mods"]) - 10.0).abs() < f64::EPSILON);
+        assert!((ar(&["--ar", "9", "--with-mods"]) - 9.0).abs() < f64::EPSILON);
+    }
+}
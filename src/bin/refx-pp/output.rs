@@ -0,0 +1,379 @@
+use std::fmt::{self, Write};
+
+use refx_pp::any::{DifficultyAttributes, PerformanceAttributes, Strains};
+
+/// A single printable value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Float(f64),
+    Int(i64),
+    Bool(bool),
+    Str(String),
+    List(Vec<f64>),
+    Null,
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<u32> for Value {
+    fn from(value: u32) -> Self {
+        Self::Int(i64::from(value))
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Self::Str(value.to_owned())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Float(value) => write!(f, "{value:.4}"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Str(value) => f.write_str(value),
+            Self::List(values) => {
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+
+                    write!(f, "{value:.4}")?;
+                }
+
+                Ok(())
+            }
+            Self::Null => f.write_str("-"),
+        }
+    }
+}
+
+/// Named values of one section, e.g. difficulty attributes.
+pub type Fields = Vec<(&'static str, Value)>;
+
+pub const fn mode_name(attrs: &DifficultyAttributes) -> &'static str {
+    match attrs {
+        DifficultyAttributes::Osu(_) => "osu",
+        DifficultyAttributes::Taiko(_) => "taiko",
+        DifficultyAttributes::Catch(_) => "catch",
+        DifficultyAttributes::Mania(_) => "mania",
+    }
+}
+
+pub fn difficulty_fields(attrs: &DifficultyAttributes) -> Fields {
+    let mut fields = vec![("mode", Value::from(mode_name(attrs)))];
+
+    match attrs {
+        DifficultyAttributes::Osu(attrs) => fields.extend([
+            ("stars", attrs.stars.into()),
+            ("max_combo", attrs.max_combo.into()),
+            ("aim", attrs.aim.into()),
+            ("speed", attrs.speed.into()),
+            ("flashlight", attrs.flashlight.into()),
+            ("slider_factor", attrs.slider_factor.into()),
+            ("speed_note_count", attrs.speed_note_count.into()),
+            (
+                "aim_difficult_slider_count",
+                attrs.aim_difficult_slider_count.into(),
+            ),
+            (
+                "aim_difficult_strain_count",
+                attrs.aim_difficult_strain_count.into(),
+            ),
+            (
+                "speed_difficult_strain_count",
+                attrs.speed_difficult_strain_count.into(),
+            ),
+            ("ar", attrs.ar.into()),
+            ("od", attrs.od().into()),
+            ("hp", attrs.hp.into()),
+            ("great_hit_window", attrs.great_hit_window.into()),
+            ("ok_hit_window", attrs.ok_hit_window.into()),
+            ("meh_hit_window", attrs.meh_hit_window.into()),
+            ("n_circles", attrs.n_circles.into()),
+            ("n_sliders", attrs.n_sliders.into()),
+            ("n_large_ticks", attrs.n_large_ticks.into()),
+            ("n_spinners", attrs.n_spinners.into()),
+        ]),
+        DifficultyAttributes::Taiko(attrs) => fields.extend([
+            ("stars", attrs.stars.into()),
+            ("max_combo", attrs.max_combo.into()),
+            ("stamina", attrs.stamina.into()),
+            ("rhythm", attrs.rhythm.into()),
+            ("color", attrs.color.into()),
+            ("reading", attrs.reading.into()),
+            ("mono_stamina_factor", attrs.mono_stamina_factor.into()),
+            ("great_hit_window", attrs.great_hit_window.into()),
+            ("ok_hit_window", attrs.ok_hit_window.into()),
+            ("is_convert", attrs.is_convert.into()),
+        ]),
+        DifficultyAttributes::Catch(attrs) => fields.extend([
+            ("stars", attrs.stars.into()),
+            ("max_combo", attrs.max_combo().into()),
+            ("ar", attrs.ar.into()),
+            ("n_fruits", attrs.n_fruits.into()),
+            ("n_droplets", attrs.n_droplets.into()),
+            ("n_tiny_droplets", attrs.n_tiny_droplets.into()),
+            ("is_convert", attrs.is_convert.into()),
+        ]),
+        DifficultyAttributes::Mania(attrs) => fields.extend([
+            ("stars", attrs.stars.into()),
+            ("max_combo", attrs.max_combo.into()),
+            ("n_objects", attrs.n_objects.into()),
+            ("n_hold_notes", attrs.n_hold_notes.into()),
+            ("is_convert", attrs.is_convert.into()),
+        ]),
+    }
+
+    fields
+}
+
+pub fn performance_fields(attrs: &PerformanceAttributes) -> Fields {
+    match attrs {
+        PerformanceAttributes::Osu(attrs) => vec![
+            ("pp", attrs.pp.into()),
+            ("pp_aim", attrs.pp_aim.into()),
+            ("pp_speed", attrs.pp_speed.into()),
+            ("pp_acc", attrs.pp_acc.into()),
+            ("pp_flashlight", attrs.pp_flashlight.into()),
+            ("effective_miss_count", attrs.effective_miss_count.into()),
+            ("speed_deviation", attrs.speed_deviation.into()),
+        ],
+        PerformanceAttributes::Taiko(attrs) => vec![
+            ("pp", attrs.pp.into()),
+            ("pp_difficulty", attrs.pp_difficulty.into()),
+            ("pp_acc", attrs.pp_acc.into()),
+            ("effective_miss_count", attrs.effective_miss_count.into()),
+            (
+                "estimated_unstable_rate",
+                attrs.estimated_unstable_rate.into(),
+            ),
+        ],
+        PerformanceAttributes::Catch(attrs) => vec![("pp", attrs.pp.into())],
+        PerformanceAttributes::Mania(attrs) => vec![
+            ("pp", attrs.pp.into()),
+            ("pp_difficulty", attrs.pp_difficulty.into()),
+        ],
+    }
+}
+
+/// The strain peaks of each skill.
+pub fn skill_strains(strains: &Strains) -> Vec<(&'static str, &[f64])> {
+    match strains {
+        Strains::Osu(strains) => vec![
+            ("aim", &strains.aim),
+            ("aim_no_sliders", &strains.aim_no_sliders),
+            ("speed", &strains.speed),
+            ("flashlight", &strains.flashlight),
+        ],
+        Strains::Taiko(strains) => vec![
+            ("color", &strains.color),
+            ("reading", &strains.reading),
+            ("rhythm", &strains.rhythm),
+            ("stamina", &strains.stamina),
+        ],
+        Strains::Catch(strains) => vec![("movement", &strains.movement)],
+        Strains::Mania(strains) => vec![("strain", &strains.strains)],
+    }
+}
+
+pub fn strains_fields(strains: &Strains) -> Fields {
+    let mut fields = vec![("section_len", Value::from(strains.section_len()))];
+
+    let skills = skill_strains(strains)
+        .into_iter()
+        .map(|(name, values)| (name, Value::List(values.to_vec())));
+
+    fields.extend(skills);
+
+    fields
+}
+
+/// Render sections as aligned two-column tables.
+pub fn table(sections: &[(&str, Fields)]) -> String {
+    let width = sections
+        .iter()
+        .flat_map(|(_, fields)| fields.iter())
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut out = String::new();
+
+    for (i, (title, fields)) in sections.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+
+        let _ = writeln!(out, "{title}");
+
+        for (name, value) in fields {
+            let _ = writeln!(out, "  {name:<width$}  {value}");
+        }
+    }
+
+    out
+}
+
+/// Render sections as a single JSON object with one nested object per
+/// section.
+pub fn json(sections: &[(&str, Fields)]) -> String {
+    let mut out = String::from("{");
+
+    for (i, (title, fields)) in sections.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+
+        write_str(&mut out, title);
+        out.push(':');
+        write_object(&mut out, fields);
+    }
+
+    out.push('}');
+
+    out
+}
+
+/// Append a flat JSON object.
+pub fn write_object(out: &mut String, fields: &[(&str, Value)]) {
+    out.push('{');
+
+    for (i, (name, value)) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+
+        write_str(out, name);
+        out.push(':');
+        write_value(out, value);
+    }
+
+    out.push('}');
+}
+
+fn write_value(out: &mut String, value: &Value) {
+    match value {
+        // JSON has no representation for NaN or infinity
+        Value::Float(value) if value.is_finite() => {
+            let _ = write!(out, "{value}");
+        }
+        Value::Float(_) | Value::Null => out.push_str("null"),
+        Value::Int(value) => {
+            let _ = write!(out, "{value}");
+        }
+        Value::Bool(value) => {
+            let _ = write!(out, "{value}");
+        }
+        Value::Str(value) => write_str(out, value),
+        Value::List(values) => {
+            out.push('[');
+
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+
+                write_value(out, &Value::Float(*value));
+            }
+
+            out.push(']');
+        }
+    }
+}
+
+fn write_str(out: &mut String, s: &str) {
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+}
+
+/// Format a value as a CSV field, quoting it if necessary.
+pub fn csv_field(value: &Value) -> String {
+    let value = match value {
+        Value::Null => return String::new(),
+        Value::Float(value) => value.to_string(),
+        value => value.to_string(),
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_escaping() {
+        let fields = vec![
+            ("name", Value::from("a \"b\"\n")),
+            ("float", Value::from(1.5)),
+            ("nan", Value::from(f64::NAN)),
+            ("none", Value::from(None::<f64>)),
+            ("int", Value::from(3_u32)),
+            ("list", Value::List(vec![0.5, f64::INFINITY])),
+        ];
+
+        assert_eq!(
+            json(&[("section", fields)]),
+            r#"{"section":{"name":"a \"b\"\n","float":1.5,"nan":null,"none":null,"int":3,"list":[0.5,null]}}"#
+        );
+    }
+
+    #[test]
+    fn csv_quoting() {
+        assert_eq!(csv_field(&Value::from("a,b")), "\"a,b\"");
+        assert_eq!(csv_field(&Value::from("say \"hi\"")), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field(&Value::from(0.25)), "0.25");
+        assert_eq!(csv_field(&Value::Null), "");
+    }
+
+    #[test]
+    fn table_alignment() {
+        let sections = [
+            ("A", vec![("x", Value::from(1_u32))]),
+            ("B", vec![("long_name", Value::from(true))]),
+        ];
+
+        assert_eq!(
+            table(&sections),
+            "A\n  x          1\n\nB\n  long_name  true\n"
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter, Result as FmtResult},
+    sync::{Arc, Mutex},
+};
+
+use refx_pp::Beatmap;
+
+use super::sha256::sha256;
+
+/// Least-recently-used cache of decoded maps, keyed by the SHA-256 hash of
+/// their file content.
+pub struct MapCache {
+    capacity: usize,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    maps: HashMap<MapHash, Entry>,
+    tick: u64,
+}
+
+struct Entry {
+    map: Arc<Beatmap>,
+    last_used: u64,
+}
+
+impl Inner {
+    fn touch(&mut self, hash: MapHash) -> Option<Arc<Beatmap>> {
+        self.tick += 1;
+        let entry = self.maps.get_mut(&hash)?;
+        entry.last_used = self.tick;
+
+        Some(Arc::clone(&entry.map))
+    }
+}
+
+impl MapCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::default(),
+        }
+    }
+
+    /// Get a previously inserted map.
+    pub fn get(&self, hash: MapHash) -> Option<Arc<Beatmap>> {
+        self.inner.lock().unwrap().touch(hash)
+    }
+
+    /// Decode a map unless it is already cached and return it alongside its
+    /// hash.
+    ///
+    /// Maps that fail to decode or are too suspicious are not cached.
+    pub fn insert(&self, bytes: &[u8]) -> Result<(MapHash, Arc<Beatmap>), String> {
+        let hash = MapHash::new(bytes);
+
+        if let Some(map) = self.get(hash) {
+            return Ok((hash, map));
+        }
+
+        // Decode without holding the lock so that other requests don't have
+        // to wait
+        let map = Beatmap::from_bytes(bytes).map_err(|err| format!("failed to decode: {err}"))?;
+
+        if let Err(sus) = map.check_suspicion() {
+            return Err(format!("map is too suspicious for calculation: {sus}"));
+        }
+
+        let map = Arc::new(map);
+
+        if self.capacity == 0 {
+            return Ok((hash, map));
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.tick += 1;
+
+        let entry = Entry {
+            map: Arc::clone(&map),
+            last_used: inner.tick,
+        };
+
+        inner.maps.insert(hash, entry);
+
+        if inner.maps.len() > self.capacity {
+            let oldest = inner
+                .maps
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(hash, _)| *hash);
+
+            if let Some(oldest) = oldest {
+                inner.maps.remove(&oldest);
+            }
+        }
+
+        Ok((hash, map))
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.inner.lock().unwrap().maps.len()
+    }
+}
+
+/// SHA-256 hash of a map's file content.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MapHash([u8; 32]);
+
+impl MapHash {
+    pub fn new(bytes: &[u8]) -> Self {
+        Self(sha256(bytes))
+    }
+
+    /// Parse the lowercase or uppercase hex representation.
+    pub fn parse(hex: &str) -> Option<Self> {
+        if hex.len() != 64 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let mut hash = [0; 32];
+
+        for (byte, i) in hash.iter_mut().zip((0..64).step_by(2)) {
+            *byte = u8::from_str_radix(&hex[i..i + 2], 16).ok()?;
+        }
+
+        Some(Self(hash))
+    }
+}
+
+impl Display for MapHash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let maps = ["1028484", "2118524", "2785319"]
+            .map(|id| fs::read(format!("./resources/{id}.osu")).unwrap());
+
+        let cache = MapCache::new(2);
+        let (first, _) = cache.insert(&maps[0]).unwrap();
+        let (second, _) = cache.insert(&maps[1]).unwrap();
+
+        assert_eq!(cache.insert(&maps[0]).unwrap().0, first);
+        assert!(cache.get(first).is_some());
+
+        let (third, _) = cache.insert(&maps[2]).unwrap();
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(first).is_some());
+        assert!(cache.get(second).is_none());
+        assert!(cache.get(third).is_some());
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let bytes = fs::read("./resources/1028484.osu").unwrap();
+        let cache = MapCache::new(0);
+
+        let (hash, _) = cache.insert(&bytes).unwrap();
+
+        assert_eq!(hash, MapHash::new(&bytes));
+        assert!(cache.get(hash).is_none());
+    }
+
+    #[test]
+    fn hash_hex() {
+        let hash = MapHash::new(b"abc");
+        let hex = hash.to_string();
+
+        assert_eq!(
+            hex,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(MapHash::parse(&hex), Some(hash));
+        assert_eq!(MapHash::parse(&hex.to_uppercase()), Some(hash));
+        assert_eq!(MapHash::parse(&hex[..62]), None);
+        assert_eq!(MapHash::parse(&hex.replace('b', "g")), None);
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    fmt::Display,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::TcpStream,
+};
+
+use crate::output::{self, Value};
+
+/// Maximum size of the request line and of each header line.
+const MAX_LINE_LEN: u64 = 8 * 1024;
+
+/// Maximum amount of header lines.
+const MAX_HEADERS: usize = 64;
+
+/// Maximum size of a request body, i.e. of a .osu file.
+const MAX_BODY_LEN: usize = 32 * 1024 * 1024;
+
+/// A parsed HTTP request.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Read a request from the stream.
+    ///
+    /// If the client expects it, a `100 Continue` is sent before the body is
+    /// read.
+    pub fn read(stream: &TcpStream) -> Result<Self, HttpError> {
+        let mut reader = BufReader::new(stream);
+
+        let request_line = read_line(&mut reader)?;
+        let mut parts = request_line.split_ascii_whitespace();
+
+        let (Some(method), Some(target), Some(_version), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(HttpError::bad_request("malformed request line"));
+        };
+
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+        let mut content_len = 0;
+        let mut expect_continue = false;
+
+        for i in 0.. {
+            let line = read_line(&mut reader)?;
+
+            if line.is_empty() {
+                break;
+            } else if i == MAX_HEADERS {
+                return Err(HttpError::new(431, "too many headers"));
+            }
+
+            let Some((name, value)) = line.split_once(':') else {
+                return Err(HttpError::bad_request("malformed header"));
+            };
+
+            let value = value.trim();
+
+            if name.eq_ignore_ascii_case("content-length") {
+                content_len = value
+                    .parse()
+                    .map_err(|_| HttpError::bad_request("invalid content length"))?;
+            } else if name.eq_ignore_ascii_case("transfer-encoding") {
+                return Err(HttpError::new(411, "content length required"));
+            } else if name.eq_ignore_ascii_case("expect") {
+                expect_continue = value.eq_ignore_ascii_case("100-continue");
+            }
+        }
+
+        if content_len > MAX_BODY_LEN {
+            return Err(HttpError::new(413, "request body is too large"));
+        }
+
+        if expect_continue && content_len > 0 {
+            let mut writer = stream;
+            writer
+                .write_all(b"HTTP/1.1 100 Continue\r\n\r\n")
+                .map_err(HttpError::io)?;
+        }
+
+        let mut body = vec![0; content_len];
+        reader.read_exact(&mut body).map_err(HttpError::io)?;
+
+        Ok(Self {
+            method: method.to_owned(),
+            path: path.to_owned(),
+            query: parse_query(query)?,
+            body,
+        })
+    }
+}
+
+/// Read a single CRLF-terminated line without its line ending.
+fn read_line(reader: &mut impl BufRead) -> Result<String, HttpError> {
+    let mut line = String::new();
+
+    reader
+        .take(MAX_LINE_LEN)
+        .read_line(&mut line)
+        .map_err(HttpError::io)?;
+
+    if !line.ends_with('\n') {
+        return Err(HttpError::bad_request("incomplete or too long line"));
+    }
+
+    line.truncate(line.trim_end_matches(['\r', '\n']).len());
+
+    Ok(line)
+}
+
+fn parse_query(query: &str) -> Result<Vec<(String, String)>, HttpError> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+            Ok((percent_decode(key)?, percent_decode(value)?))
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> Result<String, HttpError> {
+    let invalid = || HttpError::bad_request(format!("invalid query component `{s}`"));
+
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+
+    while let Some(byte) = iter.next() {
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = [iter.next(), iter.next()];
+                let [Some(hi), Some(lo)] = hex else {
+                    return Err(invalid());
+                };
+
+                let hex = std::str::from_utf8(&[hi, lo])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(invalid)?;
+
+                bytes.push(hex);
+            }
+            byte => bytes.push(byte),
+        }
+    }
+
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+/// A JSON response.
+#[derive(Debug)]
+pub struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    pub const fn ok(body: String) -> Self {
+        Self { status: 200, body }
+    }
+
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\n\
+            Content-Type: application/json\r\n\
+            Content-Length: {}\r\n\
+            Connection: close\r\n\r\n{}",
+            self.status,
+            reason(self.status),
+            self.body.len(),
+            self.body,
+        )?;
+
+        writer.flush()
+    }
+}
+
+impl From<HttpError> for Response {
+    fn from(err: HttpError) -> Self {
+        let mut body = String::new();
+        output::write_object(&mut body, &[("error", Value::Str(err.message))]);
+
+        Self {
+            status: err.status,
+            body,
+        }
+    }
+}
+
+const fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+/// An error that is sent back to the client.
+#[derive(Debug, PartialEq, Eq)]
+pub struct HttpError {
+    pub status: u16,
+    pub message: String,
+}
+
+impl HttpError {
+    pub fn new(status: u16, message: impl Display) -> Self {
+        Self {
+            status,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn bad_request(message: impl Display) -> Self {
+        Self::new(400, message)
+    }
+
+    pub fn not_found(message: impl Display) -> Self {
+        Self::new(404, message)
+    }
+
+    fn io(err: io::Error) -> Self {
+        Self::bad_request(format!("failed to read request: {err}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query() {
+        let query = parse_query("mods=HD%2CDT&acc=98.5&stable&&name=a+b").unwrap();
+
+        let expected = [
+            ("mods", "HD,DT"),
+            ("acc", "98.5"),
+            ("stable", ""),
+            ("name", "a b"),
+        ]
+        .map(|(key, value)| (key.to_owned(), value.to_owned()));
+
+        assert_eq!(query, expected);
+        assert!(parse_query("mods=%4").is_err());
+        assert!(parse_query("mods=%zz").is_err());
+        assert!(parse_query("mods=%ff").is_err());
+    }
+
+    #[test]
+    fn error_response() {
+        let mut bytes = Vec::new();
+
+        Response::from(HttpError::not_found("no \"map\""))
+            .write_to(&mut bytes)
+            .unwrap();
+
+        let response = String::from_utf8(bytes).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(response.contains("Content-Length: 22\r\n"));
+        assert!(response.ends_with("\r\n\r\n{\"error\":\"no \\\"map\\\"\"}"));
+    }
+}
@@ -0,0 +1,315 @@
+use std::{
+    io,
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    num::NonZeroUsize,
+    sync::{
+        mpsc::{self, TrySendError},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use refx_pp::{Beatmap, GameMods};
+
+use crate::{
+    args::Args,
+    convert, difficulty,
+    output::{self, Fields, Value},
+    performance_sections,
+};
+
+use self::{
+    cache::{MapCache, MapHash},
+    http::{HttpError, Request, Response},
+};
+
+mod cache;
+mod http;
+mod sha256;
+
+/// How long to wait for a client to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many accepted connections may wait for a worker before further
+/// connections are rejected.
+const QUEUE_LEN: usize = 64;
+
+/// HTTP server answering calculation requests with JSON.
+pub struct Server {
+    listener: TcpListener,
+    cache: Arc<MapCache>,
+    workers: NonZeroUsize,
+}
+
+impl Server {
+    /// Bind the server to an address, caching up to `cache_size` maps and
+    /// handling connections on `workers` threads.
+    ///
+    /// If `workers` is `0`, [`thread::available_parallelism`] is used.
+    pub fn bind(addr: impl ToSocketAddrs, cache_size: usize, workers: usize) -> io::Result<Self> {
+        let workers = NonZeroUsize::new(workers)
+            .or_else(|| thread::available_parallelism().ok())
+            .unwrap_or(NonZeroUsize::MIN);
+
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            cache: Arc::new(MapCache::new(cache_size)),
+            workers,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accept connections forever and hand them to a fixed amount of worker
+    /// threads.
+    ///
+    /// Connections that arrive while all workers are busy and the queue is
+    /// full are rejected with `503 Service Unavailable`.
+    pub fn run(&self) {
+        let (tx, rx) = mpsc::sync_channel::<TcpStream>(QUEUE_LEN);
+        let rx = Arc::new(Mutex::new(rx));
+
+        for _ in 0..self.workers.get() {
+            let rx = Arc::clone(&rx);
+            let cache = Arc::clone(&self.cache);
+
+            thread::spawn(move || loop {
+                let Ok(stream) = rx.lock().unwrap().recv() else {
+                    break;
+                };
+
+                handle_connection(&stream, &cache);
+            });
+        }
+
+        for stream in self.listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+
+            if let Err(TrySendError::Full(stream)) = tx.try_send(stream) {
+                let err = HttpError::new(503, "server is busy, try again later");
+                let _ = Response::from(err).write_to(&stream);
+            }
+        }
+    }
+}
+
+fn handle_connection(stream: &TcpStream, cache: &MapCache) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+
+    let response = Request::read(stream)
+        .and_then(|request| route(&request, cache))
+        .unwrap_or_else(Response::from);
+
+    let _ = response.write_to(stream);
+}
+
+/// The calculation endpoints.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Endpoint {
+    Difficulty,
+    Performance,
+    Strains,
+}
+
+fn route(request: &Request, cache: &MapCache) -> Result<Response, HttpError> {
+    let endpoint = match request.path.as_str() {
+        "/difficulty" => Endpoint::Difficulty,
+        "/performance" => Endpoint::Performance,
+        "/strains" => Endpoint::Strains,
+        path => return Err(HttpError::not_found(format!("unknown endpoint `{path}`"))),
+    };
+
+    if request.method != "POST" {
+        return Err(HttpError::new(405, "only POST requests are supported"));
+    }
+
+    let mut args = Args::default();
+    let mut hash = None;
+
+    for (key, value) in &request.query {
+        if key == "hash" {
+            let parsed = MapHash::parse(value)
+                .ok_or_else(|| HttpError::bad_request(format!("invalid hash `{value}`")))?;
+
+            hash = Some(parsed);
+
+            continue;
+        }
+
+        let known = args
+            .set(&format!("--{key}"), || Ok(value.clone()))
+            .map_err(HttpError::bad_request)?;
+
+        if !known {
+            return Err(HttpError::bad_request(format!(
+                "unknown query parameter `{key}`"
+            )));
+        }
+    }
+
+    let (hash, map) = if request.body.is_empty() {
+        let hash = hash.ok_or_else(|| HttpError::bad_request("missing map in request body"))?;
+
+        let map = cache
+            .get(hash)
+            .ok_or_else(|| HttpError::not_found(format!("map `{hash}` is not cached")))?;
+
+        (hash, map)
+    } else {
+        cache
+            .insert(&request.body)
+            .map_err(HttpError::bad_request)?
+    };
+
+    let map_fields = vec![("hash", Value::Str(hash.to_string()))];
+
+    let body = match endpoint {
+        Endpoint::Difficulty => {
+            let fields = difficulty_fields(&map, &args).map_err(HttpError::bad_request)?;
+
+            output::json(&[("map", map_fields), ("difficulty", fields)])
+        }
+        Endpoint::Performance => {
+            let [difficulty, performance] =
+                performance_sections(&map, &args).map_err(HttpError::bad_request)?;
+
+            output::json(&[("map", map_fields), difficulty, performance])
+        }
+        Endpoint::Strains => {
+            let fields = strains_fields(&map, &args).map_err(HttpError::bad_request)?;
+
+            output::json(&[("map", map_fields), ("strains", fields)])
+        }
+    };
+
+    Ok(Response::ok(body))
+}
+
+fn difficulty_fields(map: &Beatmap, args: &Args) -> Result<Fields, String> {
+    let mods = GameMods::from(&args.mods);
+    let map = convert(map, args, &mods)?;
+    let attrs = difficulty(args, mods).calculate(&map);
+
+    Ok(output::difficulty_fields(&attrs))
+}
+
+fn strains_fields(map: &Beatmap, args: &Args) -> Result<Fields, String> {
+    let mods = GameMods::from(&args.mods);
+    let map = convert(map, args, &mods)?;
+    let strains = difficulty(args, mods).strains(&map);
+
+    Ok(output::strains_fields(&strains))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{Read, Write},
+    };
+
+    use super::*;
+
+    fn spawn_server() -> SocketAddr {
+        let server = Server::bind("127.0.0.1:0", 4, 2).unwrap();
+        let addr = server.local_addr().unwrap();
+        thread::spawn(move || server.run());
+
+        addr
+    }
+
+    fn request(addr: SocketAddr, method: &str, target: &str, body: &[u8]) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+
+        write!(
+            stream,
+            "{method} {target} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n",
+            body.len()
+        )
+        .unwrap();
+        stream.write_all(body).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let status = response[9..12].parse().unwrap();
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+
+        (status, body.to_owned())
+    }
+
+    fn hash_of(body: &str) -> &str {
+        let start = body.find("\"hash\":\"").unwrap() + 8;
+
+        &body[start..start + 64]
+    }
+
+    #[test]
+    fn endpoints() {
+        let addr = spawn_server();
+        let map = fs::read("./resources/2785319.osu").unwrap();
+
+        let (status, body) = request(addr, "POST", "/difficulty?mods=HD", &map);
+        assert_eq!(status, 200, "{body}");
+        assert!(body.contains("\"difficulty\":{\"mode\":\"osu\",\"stars\":"));
+
+        let hash = hash_of(&body).to_owned();
+        assert_eq!(hash, MapHash::new(&map).to_string());
+
+        // The cached map is reused without sending it again
+        let (status, cached) = request(
+            addr,
+            "POST",
+            &format!("/difficulty?mods=HD&hash={hash}"),
+            &[],
+        );
+        assert_eq!(status, 200, "{cached}");
+        assert_eq!(cached, body);
+
+        let target = format!("/performance?hash={hash}&mods=HDDT&acc=98.5&misses=1&stable");
+        let (status, body) = request(addr, "POST", &target, &[]);
+        assert_eq!(status, 200, "{body}");
+        assert!(body.contains("\"performance\":{\"pp\":"));
+        assert!(body.contains("\"misses\":1"));
+
+        let (status, body) = request(
+            addr,
+            "POST",
+            &format!("/strains?hash={hash}&mode=taiko"),
+            &[],
+        );
+        assert_eq!(status, 200, "{body}");
+        assert!(body.contains("\"section_len\":400,\"color\":["));
+    }
+
+    #[test]
+    fn errors() {
+        let addr = spawn_server();
+        let map = fs::read("./resources/1028484.osu").unwrap();
+        let uncached = format!("/difficulty?hash={}", "0".repeat(64));
+
+        let cases = [
+            ("GET", "/difficulty", &map[..], 405),
+            ("POST", "/unknown", &map, 404),
+            ("POST", "/difficulty", &[], 400),
+            ("POST", &uncached, &[], 404),
+            ("POST", "/difficulty?hash=0123456789abcdef", &[], 400),
+            ("POST", "/difficulty?hash=xyz", &[], 400),
+            ("POST", "/difficulty?unknown=1", &map, 400),
+            ("POST", "/difficulty?acc=high", &map, 400),
+            ("POST", "/performance?mode=osu", &map, 400),
+        ];
+
+        for (method, target, body, expected) in cases {
+            let (status, response) = request(addr, method, target, body);
+
+            assert_eq!(status, expected, "{method} {target}: {response}");
+            assert!(response.starts_with("{\"error\":"));
+        }
+    }
+}
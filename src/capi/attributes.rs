@@ -0,0 +1,391 @@
+use rosu_map::section::general::GameMode;
+
+use crate::{
+    any::{DifficultyAttributes, PerformanceAttributes, ScoreState},
+    catch::{CatchDifficultyAttributes, CatchPerformanceAttributes},
+    mania::{ManiaDifficultyAttributes, ManiaPerformanceAttributes},
+    osu::{OsuDifficultyAttributes, OsuPerformanceAttributes},
+    taiko::{TaikoDifficultyAttributes, TaikoPerformanceAttributes},
+};
+
+/// C representation of [`OsuDifficultyAttributes`].
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RefxOsuDifficultyAttributes {
+    pub aim: f64,
+    pub aim_difficult_slider_count: f64,
+    pub speed: f64,
+    pub flashlight: f64,
+    pub slider_factor: f64,
+    pub speed_note_count: f64,
+    pub aim_difficult_strain_count: f64,
+    pub speed_difficult_strain_count: f64,
+    pub ar: f64,
+    pub great_hit_window: f64,
+    pub ok_hit_window: f64,
+    pub meh_hit_window: f64,
+    pub hp: f64,
+    pub n_circles: u32,
+    pub n_sliders: u32,
+    pub n_large_ticks: u32,
+    pub n_spinners: u32,
+    pub stars: f64,
+    pub max_combo: u32,
+    pub aim_top_weighted_slider_factor: f64,
+    pub speed_top_weighted_slider_factor: f64,
+    pub nested_score_per_object: f64,
+    pub legacy_score_base_multiplier: f64,
+    pub maximum_legacy_combo_score: f64,
+}
+
+impl From<&OsuDifficultyAttributes> for RefxOsuDifficultyAttributes {
+    fn from(attrs: &OsuDifficultyAttributes) -> Self {
+        Self {
+            aim: attrs.aim,
+            aim_difficult_slider_count: attrs.aim_difficult_slider_count,
+            speed: attrs.speed,
+            flashlight: attrs.flashlight,
+            slider_factor: attrs.slider_factor,
+            speed_note_count: attrs.speed_note_count,
+            aim_difficult_strain_count: attrs.aim_difficult_strain_count,
+            speed_difficult_strain_count: attrs.speed_difficult_strain_count,
+            ar: attrs.ar,
+            great_hit_window: attrs.great_hit_window,
+            ok_hit_window: attrs.ok_hit_window,
+            meh_hit_window: attrs.meh_hit_window,
+            hp: attrs.hp,
+            n_circles: attrs.n_circles,
+            n_sliders: attrs.n_sliders,
+            n_large_ticks: attrs.n_large_ticks,
+            n_spinners: attrs.n_spinners,
+            stars: attrs.stars,
+            max_combo: attrs.max_combo,
+            aim_top_weighted_slider_factor: attrs.aim_top_weighted_slider_factor,
+            speed_top_weighted_slider_factor: attrs.speed_top_weighted_slider_factor,
+            nested_score_per_object: attrs.nested_score_per_object,
+            legacy_score_base_multiplier: attrs.legacy_score_base_multiplier,
+            maximum_legacy_combo_score: attrs.maximum_legacy_combo_score,
+        }
+    }
+}
+
+/// C representation of [`TaikoDifficultyAttributes`].
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RefxTaikoDifficultyAttributes {
+    pub stamina: f64,
+    pub rhythm: f64,
+    pub color: f64,
+    pub reading: f64,
+    pub great_hit_window: f64,
+    pub ok_hit_window: f64,
+    pub mono_stamina_factor: f64,
+    pub stars: f64,
+    pub max_combo: u32,
+    pub is_convert: bool,
+}
+
+impl From<&TaikoDifficultyAttributes> for RefxTaikoDifficultyAttributes {
+    fn from(attrs: &TaikoDifficultyAttributes) -> Self {
+        Self {
+            stamina: attrs.stamina,
+            rhythm: attrs.rhythm,
+            color: attrs.color,
+            reading: attrs.reading,
+            great_hit_window: attrs.great_hit_window,
+            ok_hit_window: attrs.ok_hit_window,
+            mono_stamina_factor: attrs.mono_stamina_factor,
+            stars: attrs.stars,
+            max_combo: attrs.max_combo,
+            is_convert: attrs.is_convert,
+        }
+    }
+}
+
+/// C representation of [`CatchDifficultyAttributes`].
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RefxCatchDifficultyAttributes {
+    pub stars: f64,
+    pub ar: f64,
+    pub n_fruits: u32,
+    pub n_droplets: u32,
+    pub n_tiny_droplets: u32,
+    pub max_combo: u32,
+    pub is_convert: bool,
+}
+
+impl From<&CatchDifficultyAttributes> for RefxCatchDifficultyAttributes {
+    fn from(attrs: &CatchDifficultyAttributes) -> Self {
+        Self {
+            stars: attrs.stars,
+            ar: attrs.ar,
+            n_fruits: attrs.n_fruits,
+            n_droplets: attrs.n_droplets,
+            n_tiny_droplets: attrs.n_tiny_droplets,
+            max_combo: attrs.max_combo(),
+            is_convert: attrs.is_convert,
+        }
+    }
+}
+
+/// C representation of [`ManiaDifficultyAttributes`].
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RefxManiaDifficultyAttributes {
+    pub stars: f64,
+    pub n_objects: u32,
+    pub n_hold_notes: u32,
+    pub max_combo: u32,
+    pub is_convert: bool,
+}
+
+impl From<&ManiaDifficultyAttributes> for RefxManiaDifficultyAttributes {
+    fn from(attrs: &ManiaDifficultyAttributes) -> Self {
+        Self {
+            stars: attrs.stars,
+            n_objects: attrs.n_objects,
+            n_hold_notes: attrs.n_hold_notes,
+            max_combo: attrs.max_combo,
+            is_convert: attrs.is_convert,
+        }
+    }
+}
+
+/// C representation of [`DifficultyAttributes`].
+///
+/// Only the field of the attributes' mode is filled, all others are zeroed.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RefxDifficultyAttributes {
+    /// `0` for osu!standard, `1` for osu!taiko, `2` for osu!catch, and `3` for
+    /// osu!mania.
+    pub mode: u8,
+    pub osu: RefxOsuDifficultyAttributes,
+    pub taiko: RefxTaikoDifficultyAttributes,
+    /// Named `catch_` because `catch` is a keyword in C++.
+    pub catch_: RefxCatchDifficultyAttributes,
+    pub mania: RefxManiaDifficultyAttributes,
+}
+
+impl From<&DifficultyAttributes> for RefxDifficultyAttributes {
+    fn from(attrs: &DifficultyAttributes) -> Self {
+        let mut this = Self::default();
+
+        match attrs {
+            DifficultyAttributes::Osu(attrs) => {
+                this.mode = GameMode::Osu as u8;
+                this.osu = attrs.into();
+            }
+            DifficultyAttributes::Taiko(attrs) => {
+                this.mode = GameMode::Taiko as u8;
+                this.taiko = attrs.into();
+            }
+            DifficultyAttributes::Catch(attrs) => {
+                this.mode = GameMode::Catch as u8;
+                this.catch_ = attrs.into();
+            }
+            DifficultyAttributes::Mania(attrs) => {
+                this.mode = GameMode::Mania as u8;
+                this.mania = attrs.into();
+            }
+        }
+
+        this
+    }
+}
+
+/// C representation of [`OsuPerformanceAttributes`].
+///
+/// An unknown `speed_deviation` is represented as `NaN`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RefxOsuPerformanceAttributes {
+    pub difficulty: RefxOsuDifficultyAttributes,
+    pub pp: f64,
+    pub pp_acc: f64,
+    pub pp_aim: f64,
+    pub pp_flashlight: f64,
+    pub pp_speed: f64,
+    pub effective_miss_count: f64,
+    pub speed_deviation: f64,
+    pub combo_based_estimated_miss_count: f64,
+    pub score_based_estimated_miss_count: f64,
+    pub aim_estimated_slider_breaks: f64,
+    pub speed_estimated_slider_breaks: f64,
+}
+
+impl From<&OsuPerformanceAttributes> for RefxOsuPerformanceAttributes {
+    fn from(attrs: &OsuPerformanceAttributes) -> Self {
+        Self {
+            difficulty: (&attrs.difficulty).into(),
+            pp: attrs.pp,
+            pp_acc: attrs.pp_acc,
+            pp_aim: attrs.pp_aim,
+            pp_flashlight: attrs.pp_flashlight,
+            pp_speed: attrs.pp_speed,
+            effective_miss_count: attrs.effective_miss_count,
+            speed_deviation: attrs.speed_deviation.unwrap_or(f64::NAN),
+            combo_based_estimated_miss_count: attrs.combo_based_estimated_miss_count,
+            score_based_estimated_miss_count: attrs.score_based_estimated_miss_count,
+            aim_estimated_slider_breaks: attrs.aim_estimated_slider_breaks,
+            speed_estimated_slider_breaks: attrs.speed_estimated_slider_breaks,
+        }
+    }
+}
+
+/// C representation of [`TaikoPerformanceAttributes`].
+///
+/// An unknown `estimated_unstable_rate` is represented as `NaN`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RefxTaikoPerformanceAttributes {
+    pub difficulty: RefxTaikoDifficultyAttributes,
+    pub pp: f64,
+    pub pp_acc: f64,
+    pub pp_difficulty: f64,
+    pub effective_miss_count: f64,
+    pub estimated_unstable_rate: f64,
+}
+
+impl From<&TaikoPerformanceAttributes> for RefxTaikoPerformanceAttributes {
+    fn from(attrs: &TaikoPerformanceAttributes) -> Self {
+        Self {
+            difficulty: (&attrs.difficulty).into(),
+            pp: attrs.pp,
+            pp_acc: attrs.pp_acc,
+            pp_difficulty: attrs.pp_difficulty,
+            effective_miss_count: attrs.effective_miss_count,
+            estimated_unstable_rate: attrs.estimated_unstable_rate.unwrap_or(f64::NAN),
+        }
+    }
+}
+
+/// C representation of [`CatchPerformanceAttributes`].
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RefxCatchPerformanceAttributes {
+    pub difficulty: RefxCatchDifficultyAttributes,
+    pub pp: f64,
+}
+
+impl From<&CatchPerformanceAttributes> for RefxCatchPerformanceAttributes {
+    fn from(attrs: &CatchPerformanceAttributes) -> Self {
+        Self {
+            difficulty: (&attrs.difficulty).into(),
+            pp: attrs.pp,
+        }
+    }
+}
+
+/// C representation of [`ManiaPerformanceAttributes`].
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RefxManiaPerformanceAttributes {
+    pub difficulty: RefxManiaDifficultyAttributes,
+    pub pp: f64,
+    pub pp_difficulty: f64,
+}
+
+impl From<&ManiaPerformanceAttributes> for RefxManiaPerformanceAttributes {
+    fn from(attrs: &ManiaPerformanceAttributes) -> Self {
+        Self {
+            difficulty: (&attrs.difficulty).into(),
+            pp: attrs.pp,
+            pp_difficulty: attrs.pp_difficulty,
+        }
+    }
+}
+
+/// C representation of [`PerformanceAttributes`].
+///
+/// Only the field of the attributes' mode is filled, all others are zeroed.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RefxPerformanceAttributes {
+    /// `0` for osu!standard, `1` for osu!taiko, `2` for osu!catch, and `3` for
+    /// osu!mania.
+    pub mode: u8,
+    pub osu: RefxOsuPerformanceAttributes,
+    pub taiko: RefxTaikoPerformanceAttributes,
+    /// Named `catch_` because `catch` is a keyword in C++.
+    pub catch_: RefxCatchPerformanceAttributes,
+    pub mania: RefxManiaPerformanceAttributes,
+}
+
+impl From<&PerformanceAttributes> for RefxPerformanceAttributes {
+    fn from(attrs: &PerformanceAttributes) -> Self {
+        let mut this = Self::default();
+
+        match attrs {
+            PerformanceAttributes::Osu(attrs) => {
+                this.mode = GameMode::Osu as u8;
+                this.osu = attrs.into();
+            }
+            PerformanceAttributes::Taiko(attrs) => {
+                this.mode = GameMode::Taiko as u8;
+                this.taiko = attrs.into();
+            }
+            PerformanceAttributes::Catch(attrs) => {
+                this.mode = GameMode::Catch as u8;
+                this.catch_ = attrs.into();
+            }
+            PerformanceAttributes::Mania(attrs) => {
+                this.mode = GameMode::Mania as u8;
+                this.mania = attrs.into();
+            }
+        }
+
+        this
+    }
+}
+
+/// C representation of [`ScoreState`].
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RefxScoreState {
+    pub max_combo: u32,
+    pub osu_large_tick_hits: u32,
+    pub osu_small_tick_hits: u32,
+    pub slider_end_hits: u32,
+    pub n_geki: u32,
+    pub n_katu: u32,
+    pub n300: u32,
+    pub n100: u32,
+    pub n50: u32,
+    pub misses: u32,
+}
+
+impl From<&RefxScoreState> for ScoreState {
+    fn from(state: &RefxScoreState) -> Self {
+        Self {
+            max_combo: state.max_combo,
+            osu_large_tick_hits: state.osu_large_tick_hits,
+            osu_small_tick_hits: state.osu_small_tick_hits,
+            slider_end_hits: state.slider_end_hits,
+            n_geki: state.n_geki,
+            n_katu: state.n_katu,
+            n300: state.n300,
+            n100: state.n100,
+            n50: state.n50,
+            misses: state.misses,
+        }
+    }
+}
+
+impl From<&ScoreState> for RefxScoreState {
+    fn from(state: &ScoreState) -> Self {
+        Self {
+            max_combo: state.max_combo,
+            osu_large_tick_hits: state.osu_large_tick_hits,
+            osu_small_tick_hits: state.osu_small_tick_hits,
+            slider_end_hits: state.slider_end_hits,
+            n_geki: state.n_geki,
+            n_katu: state.n_katu,
+            n300: state.n300,
+            n100: state.n100,
+            n50: state.n50,
+            misses: state.misses,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::{ffi::CStr, os::raw::c_char, ptr, slice};
+
+use crate::{Beatmap, GameMods};
+
+use super::{catch_panic, free_handle, into_handle, mode_from_u8};
+
+/// Decode a [`Beatmap`] from the path of a `.osu` file.
+///
+/// Returns null if the path is not valid UTF-8 or if decoding failed.
+///
+/// # Safety
+///
+/// `path` must be null or a valid nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn refx_beatmap_from_path(path: *const c_char) -> *mut Beatmap {
+    catch_panic(ptr::null_mut(), || {
+        if path.is_null() {
+            return ptr::null_mut();
+        }
+
+        let path = unsafe { CStr::from_ptr(path) };
+
+        match path.to_str().map(Beatmap::from_path) {
+            Ok(Ok(map)) => into_handle(map),
+            _ => ptr::null_mut(),
+        }
+    })
+}
+
+/// Decode a [`Beatmap`] from the content of a `.osu` file.
+///
+/// Returns null if decoding failed.
+///
+/// # Safety
+///
+/// `data` must be null or valid for reads of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn refx_beatmap_from_bytes(data: *const u8, len: usize) -> *mut Beatmap {
+    catch_panic(ptr::null_mut(), || {
+        if data.is_null() {
+            return ptr::null_mut();
+        }
+
+        let bytes = unsafe { slice::from_raw_parts(data, len) };
+
+        Beatmap::from_bytes(bytes).map_or(ptr::null_mut(), into_handle)
+    })
+}
+
+/// Free a [`Beatmap`] handle.
+///
+/// # Safety
+///
+/// The handle must be null or valid and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn refx_beatmap_free(map: *mut Beatmap) {
+    catch_panic((), || unsafe { free_handle(map) });
+}
+
+/// The mode of the map or `255` if the handle is null.
+///
+/// # Safety
+///
+/// The handle must be null or valid.
+#[no_mangle]
+pub unsafe extern "C" fn refx_beatmap_mode(map: *const Beatmap) -> u8 {
+    catch_panic(u8::MAX, || {
+        unsafe { map.as_ref() }.map_or(u8::MAX, |map| map.mode as u8)
+    })
+}
+
+/// The main BPM of the map or `0.0` if the handle is null.
+///
+/// # Safety
+///
+/// The handle must be null or valid.
+#[no_mangle]
+pub unsafe extern "C" fn refx_beatmap_bpm(map: *const Beatmap) -> f64 {
+    catch_panic(0.0, || unsafe { map.as_ref() }.map_or(0.0, Beatmap::bpm))
+}
+
+/// Whether the map is too suspicious for further calculation.
+///
+/// See [`Beatmap::check_suspicion`].
+///
+/// # Safety
+///
+/// The handle must be null or valid.
+#[no_mangle]
+pub unsafe extern "C" fn refx_beatmap_is_suspicious(map: *const Beatmap) -> bool {
+    catch_panic(true, || {
+        unsafe { map.as_ref() }.is_none_or(|map| map.check_suspicion().is_err())
+    })
+}
+
+/// Convert the map to the given mode in-place.
+///
+/// Returns `false` if the mode is invalid or the conversion is not possible.
+///
+/// # Safety
+///
+/// The handle must be null or valid.
+#[no_mangle]
+pub unsafe extern "C" fn refx_beatmap_convert(map: *mut Beatmap, mode: u8, mods: u32) -> bool {
+    catch_panic(false, || {
+        let (Some(map), Some(mode)) = (unsafe { map.as_mut() }, mode_from_u8(mode)) else {
+            return false;
+        };
+
+        map.convert_mut(mode, &GameMods::from(mods)).is_ok()
+    })
+}
@@ -0,0 +1,58 @@
+use std::ptr;
+
+use crate::{Beatmap, Difficulty};
+
+use super::{catch_panic, free_handle, into_handle, write_out, RefxDifficultyAttributes};
+
+/// Create a new [`Difficulty`] handle with default settings.
+#[no_mangle]
+pub extern "C" fn refx_difficulty_new() -> *mut Difficulty {
+    catch_panic(ptr::null_mut(), || into_handle(Difficulty::new()))
+}
+
+/// Free a [`Difficulty`] handle.
+///
+/// # Safety
+///
+/// The handle must be null or valid and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn refx_difficulty_free(difficulty: *mut Difficulty) {
+    catch_panic((), || unsafe { free_handle(difficulty) });
+}
+
+setters!(Difficulty, Difficulty {
+    refx_difficulty_mods => mods(mods: u32);
+    refx_difficulty_passed_objects => passed_objects(passed_objects: u32);
+    refx_difficulty_clock_rate => clock_rate(clock_rate: f64);
+    refx_difficulty_ar => ar(ar: f32, with_mods: bool);
+    refx_difficulty_cs => cs(cs: f32, with_mods: bool);
+    refx_difficulty_hp => hp(hp: f32, with_mods: bool);
+    refx_difficulty_od => od(od: f32, with_mods: bool);
+    refx_difficulty_hardrock_offsets => hardrock_offsets(hardrock_offsets: bool);
+    refx_difficulty_lazer => lazer(lazer: bool);
+});
+
+/// Calculate the difficulty attributes of a map and write them into `out`.
+///
+/// # Safety
+///
+/// The handles must be null or valid and `out` must be null or valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn refx_difficulty_calculate(
+    difficulty: *const Difficulty,
+    map: *const Beatmap,
+    out: *mut RefxDifficultyAttributes,
+) -> bool {
+    catch_panic(false, || {
+        let (Some(difficulty), Some(map)) =
+            (unsafe { difficulty.as_ref() }, unsafe { map.as_ref() })
+        else {
+            return false;
+        };
+
+        let attrs = difficulty.calculate(map);
+
+        unsafe { write_out(out, (&attrs).into()) }
+    })
+}
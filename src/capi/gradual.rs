@@ -0,0 +1,190 @@
+use std::ptr;
+
+use crate::{any::ScoreState, Beatmap, Difficulty, GradualDifficulty, GradualPerformance};
+
+use super::{
+    catch_panic, free_handle, into_handle, write_out, RefxDifficultyAttributes,
+    RefxPerformanceAttributes, RefxScoreState,
+};
+
+/// Create a new [`GradualDifficulty`] handle.
+///
+/// The map is copied internally so it may be freed afterwards.
+///
+/// # Safety
+///
+/// The handles must be null or valid.
+#[no_mangle]
+pub unsafe extern "C" fn refx_gradual_difficulty_new(
+    difficulty: *const Difficulty,
+    map: *const Beatmap,
+) -> *mut GradualDifficulty {
+    catch_panic(ptr::null_mut(), || {
+        match (unsafe { difficulty.as_ref() }, unsafe { map.as_ref() }) {
+            (Some(difficulty), Some(map)) => {
+                into_handle(GradualDifficulty::new(difficulty.clone(), map))
+            }
+            _ => ptr::null_mut(),
+        }
+    })
+}
+
+/// Free a [`GradualDifficulty`] handle.
+///
+/// # Safety
+///
+/// The handle must be null or valid and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn refx_gradual_difficulty_free(gradual: *mut GradualDifficulty) {
+    catch_panic((), || unsafe { free_handle(gradual) });
+}
+
+/// Process the next hit object and write the current attributes into `out`.
+///
+/// Returns `false` once all hit objects have been processed.
+///
+/// # Safety
+///
+/// The handle must be null or valid and `out` must be null or valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn refx_gradual_difficulty_next(
+    gradual: *mut GradualDifficulty,
+    out: *mut RefxDifficultyAttributes,
+) -> bool {
+    catch_panic(false, || unsafe {
+        refx_gradual_difficulty_nth(gradual, 0, out)
+    })
+}
+
+/// Skip `n` hit objects, process the next one, and write the current
+/// attributes into `out`.
+///
+/// Returns `false` once all hit objects have been processed.
+///
+/// # Safety
+///
+/// The handle must be null or valid and `out` must be null or valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn refx_gradual_difficulty_nth(
+    gradual: *mut GradualDifficulty,
+    n: usize,
+    out: *mut RefxDifficultyAttributes,
+) -> bool {
+    catch_panic(false, || {
+        let Some(this) = (unsafe { gradual.as_mut() }) else {
+            return false;
+        };
+
+        match this.nth(n) {
+            Some(attrs) => unsafe { write_out(out, (&attrs).into()) },
+            None => false,
+        }
+    })
+}
+
+/// The amount of remaining hit objects.
+///
+/// # Safety
+///
+/// The handle must be null or valid.
+#[no_mangle]
+pub unsafe extern "C" fn refx_gradual_difficulty_len(gradual: *const GradualDifficulty) -> usize {
+    catch_panic(0, || {
+        unsafe { gradual.as_ref() }.map_or(0, ExactSizeIterator::len)
+    })
+}
+
+/// Create a new [`GradualPerformance`] handle.
+///
+/// The map is copied internally so it may be freed afterwards.
+///
+/// # Safety
+///
+/// The handles must be null or valid.
+#[no_mangle]
+pub unsafe extern "C" fn refx_gradual_performance_new(
+    difficulty: *const Difficulty,
+    map: *const Beatmap,
+) -> *mut GradualPerformance {
+    catch_panic(ptr::null_mut(), || {
+        match (unsafe { difficulty.as_ref() }, unsafe { map.as_ref() }) {
+            (Some(difficulty), Some(map)) => {
+                into_handle(GradualPerformance::new(difficulty.clone(), map))
+            }
+            _ => ptr::null_mut(),
+        }
+    })
+}
+
+/// Free a [`GradualPerformance`] handle.
+///
+/// # Safety
+///
+/// The handle must be null or valid and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn refx_gradual_performance_free(gradual: *mut GradualPerformance) {
+    catch_panic((), || unsafe { free_handle(gradual) });
+}
+
+/// Process the next hit object and write the performance attributes of the
+/// score state into `out`.
+///
+/// Returns `false` once all hit objects have been processed.
+///
+/// # Safety
+///
+/// The handle must be null or valid, `state` must be null or valid for reads,
+/// and `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn refx_gradual_performance_next(
+    gradual: *mut GradualPerformance,
+    state: *const RefxScoreState,
+    out: *mut RefxPerformanceAttributes,
+) -> bool {
+    catch_panic(false, || unsafe {
+        refx_gradual_performance_nth(gradual, state, 0, out)
+    })
+}
+
+/// Skip `n` hit objects, process the next one, and write the performance
+/// attributes of the score state into `out`.
+///
+/// Returns `false` once all hit objects have been processed.
+///
+/// # Safety
+///
+/// The handle must be null or valid, `state` must be null or valid for reads,
+/// and `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn refx_gradual_performance_nth(
+    gradual: *mut GradualPerformance,
+    state: *const RefxScoreState,
+    n: usize,
+    out: *mut RefxPerformanceAttributes,
+) -> bool {
+    catch_panic(false, || {
+        let (Some(this), Some(state)) = (unsafe { gradual.as_mut() }, unsafe { state.as_ref() })
+        else {
+            return false;
+        };
+
+        match this.nth(ScoreState::from(state), n) {
+            Some(attrs) => unsafe { write_out(out, (&attrs).into()) },
+            None => false,
+        }
+    })
+}
+
+/// The amount of remaining hit objects.
+///
+/// # Safety
+///
+/// The handle must be null or valid.
+#[no_mangle]
+pub unsafe extern "C" fn refx_gradual_performance_len(gradual: *const GradualPerformance) -> usize {
+    catch_panic(0, || {
+        unsafe { gradual.as_ref() }.map_or(0, GradualPerformance::len)
+    })
+}
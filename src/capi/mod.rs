@@ -0,0 +1,493 @@
+//! Handles are heap-allocated Rust values that are created and freed through
+//! their respective `refx_*_new` and `refx_*_free` functions. Attributes and
+//! score states are plain `#[repr(C)]` structs that are written into
+//! caller-provided memory.
+//!
+//! Functions that take a handle or an output pointer return `false` (or a
+//! null handle) if any of them is null or if the operation failed. Panics are
+//! caught at the boundary and count as failure too.
+//!
+//! Modes are passed as `u8`: `0` for osu!standard, `1` for osu!taiko, `2` for
+//! osu!catch, and `3` for osu!mania. Mods are passed as legacy bitflags.
+//!
+//! The matching C header is `include/refx_pp.h`. It is maintained by hand;
+//! the tests check that its identifiers and struct layouts match this module.
+//! To build the library, run
+//!
+//! ```sh
+//! cargo rustc --release --features capi --crate-type cdylib
+//! ```
+//!
+//! or `--crate-type staticlib` for a static library.
+//!
+//! # Safety
+//!
+//! Every handle passed to a function must either be null or have been
+//! returned by this API and not been freed yet. Handles must not be used by
+//! multiple threads at the same time.
+//!
+//! A handle created by `refx_performance_new` borrows its map and must be
+//! freed before the map. The gradual calculators copy the map instead.
+
+use std::panic::{self, AssertUnwindSafe};
+
+use rosu_map::section::general::GameMode;
+
+pub use self::{
+    attributes::{
+        RefxCatchDifficultyAttributes, RefxCatchPerformanceAttributes, RefxDifficultyAttributes,
+        RefxManiaDifficultyAttributes, RefxManiaPerformanceAttributes, RefxOsuDifficultyAttributes,
+        RefxOsuPerformanceAttributes, RefxPerformanceAttributes, RefxScoreState,
+        RefxTaikoDifficultyAttributes, RefxTaikoPerformanceAttributes,
+    },
+    beatmap::*,
+    difficulty::*,
+    gradual::*,
+    performance::*,
+};
+
+/// Define `extern "C"` functions that apply a builder method to a handle.
+macro_rules! setters {
+    (
+        $ty:ty, $doc_ty:ident {
+            $( $name:ident => $method:ident( $( $arg:ident: $arg_ty:ty ),* ); )*
+        }
+    ) => {
+        $(
+            #[doc = concat!(
+                "Apply [`", stringify!($doc_ty), "::", stringify!($method), "`] to the handle."
+            )]
+            ///
+            /// # Safety
+            ///
+            /// The handle must be null or valid.
+            #[no_mangle]
+            pub unsafe extern "C" fn $name(handle: *mut $ty, $( $arg: $arg_ty ),*) -> bool {
+                catch_panic(false, || {
+                    let Some(this) = (unsafe { handle.as_mut() }) else {
+                        return false;
+                    };
+
+                    *this = this.clone().$method($( $arg ),*);
+
+                    true
+                })
+            }
+        )*
+    };
+}
+
+mod attributes;
+mod beatmap;
+mod difficulty;
+mod gradual;
+mod performance;
+
+/// Parse a mode passed as `u8`.
+const fn mode_from_u8(mode: u8) -> Option<GameMode> {
+    match mode {
+        0 => Some(GameMode::Osu),
+        1 => Some(GameMode::Taiko),
+        2 => Some(GameMode::Catch),
+        3 => Some(GameMode::Mania),
+        _ => None,
+    }
+}
+
+/// Run the body of an `extern "C"` function and return `default` if it
+/// panicked because unwinding into foreign code is undefined behavior.
+fn catch_panic<T>(default: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(default)
+}
+
+/// Write a value into caller-provided memory.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+unsafe fn write_out<T>(out: *mut T, value: T) -> bool {
+    if out.is_null() {
+        return false;
+    }
+
+    unsafe { out.write(value) };
+
+    true
+}
+
+/// Move a value to the heap and hand out ownership as raw pointer.
+fn into_handle<T>(value: T) -> *mut T {
+    Box::into_raw(Box::new(value))
+}
+
+/// Drop a handle created by [`into_handle`].
+///
+/// # Safety
+///
+/// `handle` must be null or have been created by [`into_handle`] and not been
+/// freed yet.
+unsafe fn free_handle<T>(handle: *mut T) {
+    if !handle.is_null() {
+        drop(unsafe { Box::from_raw(handle) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        ffi::CString,
+        mem, ptr,
+    };
+
+    use crate::{any::PerformanceAttributes, Beatmap, Difficulty, GradualDifficulty};
+
+    use super::*;
+
+    const HEADER: &str = include_str!("../../include/refx_pp.h");
+
+    const SOURCES: [&str; 5] = [
+        include_str!("attributes.rs"),
+        include_str!("beatmap.rs"),
+        include_str!("difficulty.rs"),
+        include_str!("gradual.rs"),
+        include_str!("performance.rs"),
+    ];
+
+    /// All identifiers of the form `refx_*` or `Refx*`.
+    fn exports(source: &str) -> BTreeSet<&str> {
+        source
+            .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .filter(|word| word.starts_with("refx_") || word.starts_with("Refx"))
+            .collect()
+    }
+
+    /// Field names, offsets, and sizes as well as size and alignment of a
+    /// struct.
+    #[derive(Debug, PartialEq)]
+    struct Layout {
+        fields: Vec<(&'static str, usize, usize)>,
+        size: usize,
+        align: usize,
+    }
+
+    /// The layout of a `#[repr(C)]` struct.
+    ///
+    /// All fields must be listed, otherwise the pattern does not compile.
+    macro_rules! layout {
+        ( $ty:ident { $( $field:ident ),* $(,)? } ) => {{
+            let value = $ty::default();
+            let $ty { $( $field ),* } = &value;
+
+            let layout = Layout {
+                fields: vec![$(
+                    (stringify!($field), mem::offset_of!($ty, $field), mem::size_of_val($field)),
+                )*],
+                size: mem::size_of::<$ty>(),
+                align: mem::align_of::<$ty>(),
+            };
+
+            (stringify!($ty), layout)
+        }};
+    }
+
+    /// Layouts of all structs defined in the header as a C compiler lays
+    /// them out.
+    fn header_layouts() -> BTreeMap<&'static str, Layout> {
+        let mut layouts = BTreeMap::new();
+
+        for def in HEADER.split("typedef struct ").skip(1) {
+            // Opaque handles have no body
+            let Some((name, body)) = def.split_once('{') else {
+                continue;
+            };
+
+            let (body, _) = body.split_once('}').unwrap();
+            let mut fields = Vec::new();
+            let mut size = 0_usize;
+            let mut align = 1;
+
+            for field in body.split(';').map(str::trim).filter(|f| !f.is_empty()) {
+                let (ty, field) = field.rsplit_once(' ').unwrap();
+
+                let (field_size, field_align) = match ty {
+                    "bool" | "uint8_t" => (1, 1),
+                    "uint32_t" | "float" => (4, 4),
+                    "double" | "int64_t" | "size_t" => (8, 8),
+                    _ => {
+                        let nested: &Layout = &layouts[ty];
+
+                        (nested.size, nested.align)
+                    }
+                };
+
+                size = size.next_multiple_of(field_align);
+                fields.push((field, size, field_size));
+                size += field_size;
+                align = align.max(field_align);
+            }
+
+            let layout = Layout {
+                fields,
+                size: size.next_multiple_of(align),
+                align,
+            };
+
+            layouts.insert(name.trim(), layout);
+        }
+
+        layouts
+    }
+
+    #[test]
+    fn calculations() {
+        let path = CString::new("./resources/2785319.osu").unwrap();
+        let expected_map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+
+        unsafe {
+            let map = refx_beatmap_from_path(path.as_ptr());
+            assert!(!map.is_null());
+            assert_eq!(refx_beatmap_mode(map), 0);
+            assert!(!refx_beatmap_is_suspicious(map));
+
+            let difficulty = refx_difficulty_new();
+            assert!(refx_difficulty_mods(difficulty, 64));
+
+            let mut attrs = RefxDifficultyAttributes::default();
+            assert!(refx_difficulty_calculate(difficulty, map, &raw mut attrs));
+
+            let expected = Difficulty::new().mods(64).calculate(&expected_map);
+            assert_eq!(attrs, RefxDifficultyAttributes::from(&expected));
+
+            let performance = refx_performance_new(map);
+            assert!(refx_performance_difficulty(performance, difficulty));
+            assert!(refx_performance_accuracy(performance, 98.5));
+            assert!(refx_performance_misses(performance, 2));
+
+            let mut perf_attrs = RefxPerformanceAttributes::default();
+            assert!(refx_performance_calculate(performance, &raw mut perf_attrs));
+
+            let PerformanceAttributes::Osu(expected) = expected_map
+                .performance()
+                .mods(64)
+                .accuracy(98.5)
+                .misses(2)
+                .calculate()
+            else {
+                unreachable!()
+            };
+
+            assert_eq!(perf_attrs.mode, 0);
+            assert!((perf_attrs.osu.pp - expected.pp).abs() < f64::EPSILON);
+
+            assert!(refx_performance_mode(performance, 1));
+            assert!(!refx_performance_mode(performance, 4));
+            assert!(refx_performance_calculate(performance, &raw mut perf_attrs));
+            assert_eq!(perf_attrs.mode, 1);
+            assert!(perf_attrs.taiko.pp > 0.0);
+
+            let gradual = refx_gradual_difficulty_new(difficulty, map);
+            let n_objects = refx_gradual_difficulty_len(gradual);
+            assert_eq!(n_objects, expected_map.hit_objects.len());
+
+            let mut expected_gradual =
+                GradualDifficulty::new(Difficulty::new().mods(64), &expected_map);
+            assert!(refx_gradual_difficulty_nth(gradual, 9, &raw mut attrs));
+            let expected = expected_gradual.nth(9).unwrap();
+            assert_eq!(attrs, RefxDifficultyAttributes::from(&expected));
+
+            let mut n = 10;
+
+            while refx_gradual_difficulty_next(gradual, &raw mut attrs) {
+                n += 1;
+            }
+
+            assert_eq!(n, n_objects);
+
+            let gradual_perf = refx_gradual_performance_new(difficulty, map);
+            let state = RefxScoreState {
+                max_combo: 5,
+                n300: 5,
+                ..Default::default()
+            };
+
+            assert!(refx_gradual_performance_nth(
+                gradual_perf,
+                &raw const state,
+                4,
+                &raw mut perf_attrs
+            ));
+            assert!(perf_attrs.osu.pp > 0.0);
+            assert_eq!(refx_gradual_performance_len(gradual_perf), n_objects - 5);
+
+            refx_gradual_performance_free(gradual_perf);
+            refx_gradual_difficulty_free(gradual);
+            refx_performance_free(performance);
+            refx_difficulty_free(difficulty);
+            refx_beatmap_free(map);
+        }
+    }
+
+    #[test]
+    fn null_handles() {
+        unsafe {
+            let mut attrs = RefxDifficultyAttributes::default();
+
+            assert!(refx_beatmap_from_path(ptr::null()).is_null());
+            assert!(refx_beatmap_from_bytes(ptr::null(), 0).is_null());
+            assert_eq!(refx_beatmap_mode(ptr::null()), u8::MAX);
+            assert!(!refx_difficulty_mods(ptr::null_mut(), 8));
+            assert!(!refx_difficulty_calculate(
+                refx_difficulty_new(),
+                ptr::null(),
+                &raw mut attrs
+            ));
+            assert!(refx_performance_new(ptr::null()).is_null());
+            assert!(refx_gradual_difficulty_new(ptr::null(), ptr::null()).is_null());
+            assert_eq!(refx_gradual_performance_len(ptr::null()), 0);
+
+            refx_beatmap_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn header_matches_exports() {
+        let in_header = exports(HEADER);
+        let in_source: BTreeSet<_> = SOURCES.into_iter().flat_map(exports).collect();
+
+        // Opaque handle types only exist in the header
+        let opaque = [
+            "RefxBeatmap",
+            "RefxDifficulty",
+            "RefxPerformance",
+            "RefxGradualDifficulty",
+            "RefxGradualPerformance",
+        ];
+
+        let missing: Vec<_> = in_source.difference(&in_header).collect();
+        assert!(missing.is_empty(), "missing in header: {missing:?}");
+
+        let unknown: Vec<_> = in_header
+            .difference(&in_source)
+            .filter(|name| !opaque.contains(name))
+            .collect();
+        assert!(unknown.is_empty(), "unknown in header: {unknown:?}");
+    }
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn header_matches_layouts() {
+        let in_source = BTreeMap::from([
+            layout!(RefxOsuDifficultyAttributes {
+                aim,
+                aim_difficult_slider_count,
+                speed,
+                flashlight,
+                slider_factor,
+                speed_note_count,
+                aim_difficult_strain_count,
+                speed_difficult_strain_count,
+                ar,
+                great_hit_window,
+                ok_hit_window,
+                meh_hit_window,
+                hp,
+                n_circles,
+                n_sliders,
+                n_large_ticks,
+                n_spinners,
+                stars,
+                max_combo,
+                aim_top_weighted_slider_factor,
+                speed_top_weighted_slider_factor,
+                nested_score_per_object,
+                legacy_score_base_multiplier,
+                maximum_legacy_combo_score,
+            }),
+            layout!(RefxTaikoDifficultyAttributes {
+                stamina,
+                rhythm,
+                color,
+                reading,
+                great_hit_window,
+                ok_hit_window,
+                mono_stamina_factor,
+                stars,
+                max_combo,
+                is_convert,
+            }),
+            layout!(RefxCatchDifficultyAttributes {
+                stars,
+                ar,
+                n_fruits,
+                n_droplets,
+                n_tiny_droplets,
+                max_combo,
+                is_convert,
+            }),
+            layout!(RefxManiaDifficultyAttributes {
+                stars,
+                n_objects,
+                n_hold_notes,
+                max_combo,
+                is_convert,
+            }),
+            layout!(RefxDifficultyAttributes {
+                mode,
+                osu,
+                taiko,
+                catch_,
+                mania,
+            }),
+            layout!(RefxOsuPerformanceAttributes {
+                difficulty,
+                pp,
+                pp_acc,
+                pp_aim,
+                pp_flashlight,
+                pp_speed,
+                effective_miss_count,
+                speed_deviation,
+                combo_based_estimated_miss_count,
+                score_based_estimated_miss_count,
+                aim_estimated_slider_breaks,
+                speed_estimated_slider_breaks,
+            }),
+            layout!(RefxTaikoPerformanceAttributes {
+                difficulty,
+                pp,
+                pp_acc,
+                pp_difficulty,
+                effective_miss_count,
+                estimated_unstable_rate,
+            }),
+            layout!(RefxCatchPerformanceAttributes { difficulty, pp }),
+            layout!(RefxManiaPerformanceAttributes {
+                difficulty,
+                pp,
+                pp_difficulty,
+            }),
+            layout!(RefxPerformanceAttributes {
+                mode,
+                osu,
+                taiko,
+                catch_,
+                mania,
+            }),
+            layout!(RefxScoreState {
+                max_combo,
+                osu_large_tick_hits,
+                osu_small_tick_hits,
+                slider_end_hits,
+                n_geki,
+                n_katu,
+                n300,
+                n100,
+                n50,
+                misses,
+            }),
+        ]);
+
+        assert_eq!(header_layouts(), in_source);
+    }
+}
@@ -0,0 +1,184 @@
+use std::ptr;
+
+use crate::{any::ScoreState, Beatmap, Difficulty, Performance};
+
+use super::{
+    catch_panic, free_handle, into_handle, mode_from_u8, write_out, RefxPerformanceAttributes,
+    RefxScoreState,
+};
+
+/// Create a new [`Performance`] handle for a map.
+///
+/// Returns null if the map handle is null.
+///
+/// # Safety
+///
+/// The map handle must be null or valid. The map is borrowed by the returned
+/// handle so it must not be freed before the performance handle.
+#[no_mangle]
+pub unsafe extern "C" fn refx_performance_new(map: *const Beatmap) -> *mut Performance<'static> {
+    catch_panic(ptr::null_mut(), || {
+        unsafe { map.as_ref() }.map_or(ptr::null_mut(), |map| into_handle(Performance::new(map)))
+    })
+}
+
+/// Free a [`Performance`] handle.
+///
+/// # Safety
+///
+/// The handle must be null or valid and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn refx_performance_free(performance: *mut Performance<'static>) {
+    catch_panic((), || unsafe { free_handle(performance) });
+}
+
+setters!(Performance<'static>, Performance {
+    refx_performance_mods => mods(mods: u32);
+    refx_performance_passed_objects => passed_objects(passed_objects: u32);
+    refx_performance_clock_rate => clock_rate(clock_rate: f64);
+    refx_performance_ar => ar(ar: f32, with_mods: bool);
+    refx_performance_cs => cs(cs: f32, with_mods: bool);
+    refx_performance_hp => hp(hp: f32, with_mods: bool);
+    refx_performance_od => od(od: f32, with_mods: bool);
+    refx_performance_hardrock_offsets => hardrock_offsets(hardrock_offsets: bool);
+    refx_performance_lazer => lazer(lazer: bool);
+    refx_performance_accuracy => accuracy(acc: f64);
+    refx_performance_combo => combo(combo: u32);
+    refx_performance_misses => misses(misses: u32);
+    refx_performance_n300 => n300(n300: u32);
+    refx_performance_n100 => n100(n100: u32);
+    refx_performance_n50 => n50(n50: u32);
+    refx_performance_n_geki => n_geki(n_geki: u32);
+    refx_performance_n_katu => n_katu(n_katu: u32);
+    refx_performance_large_tick_hits => large_tick_hits(large_tick_hits: u32);
+    refx_performance_small_tick_hits => small_tick_hits(small_tick_hits: u32);
+    refx_performance_slider_end_hits => slider_end_hits(slider_end_hits: u32);
+    refx_performance_legacy_total_score => legacy_total_score(total_score: i64);
+});
+
+/// Use the settings of a [`Difficulty`] handle.
+///
+/// See [`Performance::difficulty`].
+///
+/// # Safety
+///
+/// The handles must be null or valid.
+#[no_mangle]
+pub unsafe extern "C" fn refx_performance_difficulty(
+    performance: *mut Performance<'static>,
+    difficulty: *const Difficulty,
+) -> bool {
+    catch_panic(false, || {
+        let (Some(this), Some(difficulty)) = (unsafe { performance.as_mut() }, unsafe {
+            difficulty.as_ref()
+        }) else {
+            return false;
+        };
+
+        *this = this.clone().difficulty(difficulty.clone());
+
+        true
+    })
+}
+
+/// Convert the map to the given mode.
+///
+/// Returns `false` if the mode is invalid or the conversion is not possible.
+///
+/// # Safety
+///
+/// The handle must be null or valid.
+#[no_mangle]
+pub unsafe extern "C" fn refx_performance_mode(
+    performance: *mut Performance<'static>,
+    mode: u8,
+) -> bool {
+    catch_panic(false, || {
+        let (Some(this), Some(mode)) = (unsafe { performance.as_mut() }, mode_from_u8(mode)) else {
+            return false;
+        };
+
+        match this.clone().try_mode(mode) {
+            Ok(converted) => {
+                *this = converted;
+
+                true
+            }
+            Err(_) => false,
+        }
+    })
+}
+
+/// Specify all hit results at once.
+///
+/// See [`Performance::state`].
+///
+/// # Safety
+///
+/// The handle must be null or valid and `state` must be null or valid for
+/// reads.
+#[no_mangle]
+pub unsafe extern "C" fn refx_performance_state(
+    performance: *mut Performance<'static>,
+    state: *const RefxScoreState,
+) -> bool {
+    catch_panic(false, || {
+        let (Some(this), Some(state)) =
+            (unsafe { performance.as_mut() }, unsafe { state.as_ref() })
+        else {
+            return false;
+        };
+
+        *this = this.clone().state(ScoreState::from(state));
+
+        true
+    })
+}
+
+/// Write the score state that will be used for the calculation into `out`.
+///
+/// See [`Performance::generate_state`].
+///
+/// # Safety
+///
+/// The handle must be null or valid and `out` must be null or valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn refx_performance_generate_state(
+    performance: *mut Performance<'static>,
+    out: *mut RefxScoreState,
+) -> bool {
+    catch_panic(false, || {
+        let Some(this) = (unsafe { performance.as_mut() }) else {
+            return false;
+        };
+
+        let state = this.generate_state();
+
+        unsafe { write_out(out, (&state).into()) }
+    })
+}
+
+/// Calculate the performance attributes and write them into `out`.
+///
+/// The handle stays valid and can be modified for further calculations.
+///
+/// # Safety
+///
+/// The handle must be null or valid and `out` must be null or valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn refx_performance_calculate(
+    performance: *const Performance<'static>,
+    out: *mut RefxPerformanceAttributes,
+) -> bool {
+    catch_panic(false, || {
+        let Some(this) = (unsafe { performance.as_ref() }) else {
+            return false;
+        };
+
+        let attrs = this.clone().calculate();
+
+        unsafe { write_out(out, (&attrs).into()) }
+    })
+}
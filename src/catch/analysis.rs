@@ -0,0 +1,232 @@
+use rosu_map::section::general::GameMode;
+
+use crate::{
+    any::difficulty::cancellation::Cancellation, model::mode::ConvertError, Beatmap, Difficulty,
+};
+
+use super::{
+    attributes::{ObjectCountBuilder, ObjectTimelines},
+    convert::convert_objects,
+};
+
+/// A fruit or droplet of an osu!catch map after conversion.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CatchAnalysisObject {
+    /// The start time adjusted to the clock rate.
+    pub start_time: f64,
+    /// The x-position including hardrock offsets.
+    pub x: f32,
+    /// The horizontal distance the catcher has to move from the previous
+    /// object to this one.
+    pub distance: f32,
+    /// Whether the movement to the next object requires a hyperdash.
+    pub hyper_dash: bool,
+    /// How much further the catcher could move towards the next object
+    /// before a hyperdash would be required.
+    ///
+    /// Only meaningful if [`hyper_dash`](Self::hyper_dash) is `false`.
+    pub dist_to_hyper_dash: f32,
+}
+
+/// Movement and object statistics of an osu!catch map.
+///
+/// The objects are converted the same way as for the difficulty calculation,
+/// i.e. with respect to hardrock offsets and reflection, and hyperdashes are
+/// initialized the same way as in osu!catch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CatchAnalysis {
+    /// All fruits and droplets sorted by start time.
+    pub objects: Vec<CatchAnalysisObject>,
+    /// The amount of objects from which the catcher hyperdashes to the next
+    /// object.
+    pub n_hyper_dashes: usize,
+    /// Indices into [`objects`](Self::objects) whose movement to the next
+    /// object is barely not a hyperdash, see
+    /// [`EDGE_DASH_THRESHOLD`](Self::EDGE_DASH_THRESHOLD).
+    pub edge_dash_candidates: Vec<usize>,
+    /// The amount of times the catcher reverses its horizontal direction.
+    pub direction_changes: usize,
+    /// Start times of all fruits, adjusted to the clock rate.
+    pub fruits: Vec<f64>,
+    /// Start times of all droplets, adjusted to the clock rate.
+    pub droplets: Vec<f64>,
+    /// Start times of all tiny droplets, adjusted to the clock rate.
+    pub tiny_droplets: Vec<f64>,
+}
+
+impl CatchAnalysis {
+    /// The maximum distance to a hyperdash in osu!pixels for a movement to
+    /// count as edge dash candidate.
+    pub const EDGE_DASH_THRESHOLD: f32 = 10.0;
+
+    /// Analyze the movement and objects of a map.
+    ///
+    /// Only the mods, clock rate, hardrock offsets, and passed objects of the
+    /// [`Difficulty`] are relevant. Passed objects only count fruits and
+    /// droplets, tiny droplets are included up to the last passed object.
+    pub fn new(difficulty: &Difficulty, map: &Beatmap) -> Result<Self, ConvertError> {
+        let map = map.convert_ref(GameMode::Catch, difficulty.get_mods())?;
+
+        let take = difficulty.get_passed_objects();
+        let clock_rate = difficulty.get_clock_rate();
+        let map_attrs = map.attributes().difficulty(difficulty).build();
+
+        let mut count = ObjectCountBuilder::new_timelines(take);
+
+        let palpable_objects = convert_objects(
+            &map,
+            &mut count,
+            difficulty.get_mods().reflection(),
+            difficulty.get_hardrock_offsets(),
+            map_attrs.cs as f32,
+            &Cancellation::NONE,
+        );
+
+        let n_objects = palpable_objects.len().min(take);
+        let mut objects = Vec::with_capacity(n_objects);
+        let mut n_hyper_dashes = 0;
+        let mut edge_dash_candidates = Vec::new();
+        let mut direction_changes = 0;
+
+        let mut last_x: Option<f32> = None;
+        let mut last_dir = 0.0_f32;
+
+        for (i, h) in palpable_objects.iter().take(n_objects).enumerate() {
+            let x = h.effective_x();
+            let movement = last_x.map_or(0.0, |last_x| x - last_x);
+
+            if movement.abs() > f32::EPSILON {
+                let dir = movement.signum();
+
+                if dir * last_dir < 0.0 {
+                    direction_changes += 1;
+                }
+
+                last_dir = dir;
+            }
+
+            // The last object has no next object to move to
+            let has_next = i + 1 < palpable_objects.len();
+
+            if h.hyper_dash {
+                n_hyper_dashes += 1;
+            } else if has_next && h.dist_to_hyper_dash <= Self::EDGE_DASH_THRESHOLD {
+                edge_dash_candidates.push(i);
+            }
+
+            objects.push(CatchAnalysisObject {
+                start_time: h.start_time / clock_rate,
+                x,
+                distance: movement.abs(),
+                hyper_dash: h.hyper_dash,
+                dist_to_hyper_dash: h.dist_to_hyper_dash,
+            });
+
+            last_x = Some(x);
+        }
+
+        let ObjectTimelines {
+            mut fruits,
+            mut droplets,
+            mut tiny_droplets,
+        } = count.into_timelines();
+
+        // Sorted by start time like the converted objects
+        for timeline in [&mut fruits, &mut droplets, &mut tiny_droplets] {
+            timeline.sort_by(f64::total_cmp);
+
+            for start_time in timeline.iter_mut() {
+                *start_time /= clock_rate;
+            }
+        }
+
+        Ok(Self {
+            objects,
+            n_hyper_dashes,
+            edge_dash_candidates,
+            direction_changes,
+            fruits,
+            droplets,
+            tiny_droplets,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rosu_map::util::Pos;
+
+    use crate::{catch::Catch, model::beatmap::BeatmapBuilder};
+
+    use super::*;
+
+    #[test]
+    fn hyper_dashes_and_directions() {
+        let map = BeatmapBuilder::new()
+            .mode(GameMode::Catch)
+            .cs(4.0)
+            .timing_point(0.0, 500.0)
+            .circle(Pos::new(0.0, 0.0), 1000.0)
+            // Too far to reach without a hyperdash
+            .circle(Pos::new(512.0, 0.0), 1100.0)
+            .circle(Pos::new(256.0, 0.0), 2100.0)
+            .circle(Pos::new(256.0, 0.0), 2200.0)
+            .circle(Pos::new(400.0, 0.0), 3200.0)
+            .build()
+            .unwrap();
+
+        let analysis = CatchAnalysis::new(&Difficulty::new(), &map).unwrap();
+
+        assert_eq!(analysis.objects.len(), 5);
+        assert_eq!(analysis.n_hyper_dashes, 1);
+        assert!(analysis.objects[0].hyper_dash);
+        assert_eq!(analysis.direction_changes, 2);
+        assert!((analysis.objects[1].distance - 512.0).abs() < f32::EPSILON);
+        assert!(analysis.objects[3].distance.abs() < f32::EPSILON);
+        assert_eq!(analysis.fruits.len(), 5);
+        assert!(analysis.droplets.is_empty());
+        assert!(analysis.tiny_droplets.is_empty());
+
+        let dt = CatchAnalysis::new(&Difficulty::new().mods(64), &map).unwrap();
+        assert!((dt.fruits[1] * 1.5 - 1100.0).abs() < 1e-9);
+
+        let passed = CatchAnalysis::new(&Difficulty::new().passed_objects(2), &map).unwrap();
+        assert_eq!(passed.objects.len(), 2);
+        assert_eq!(passed.fruits.len(), 2);
+    }
+
+    #[test]
+    fn real_map() {
+        let map = Beatmap::from_path("./resources/2118524.osu").unwrap();
+
+        let analysis = CatchAnalysis::new(&Difficulty::new(), &map).unwrap();
+        let attrs = Difficulty::new().calculate_for_mode::<Catch>(&map).unwrap();
+
+        assert_eq!(analysis.fruits.len(), attrs.n_fruits as usize);
+        assert_eq!(analysis.droplets.len(), attrs.n_droplets as usize);
+        assert_eq!(analysis.tiny_droplets.len(), attrs.n_tiny_droplets as usize);
+        assert_eq!(
+            analysis.objects.len(),
+            analysis.fruits.len() + analysis.droplets.len()
+        );
+        assert!(analysis.n_hyper_dashes > 0);
+        assert!(analysis.edge_dash_candidates.iter().all(|&i| {
+            let h = &analysis.objects[i];
+
+            !h.hyper_dash && h.dist_to_hyper_dash <= CatchAnalysis::EDGE_DASH_THRESHOLD
+        }));
+        assert!(analysis
+            .objects
+            .windows(2)
+            .all(|w| w[0].start_time <= w[1].start_time));
+        assert!(analysis.tiny_droplets.windows(2).all(|w| w[0] <= w[1]));
+
+        let difficulty = Difficulty::new().passed_objects(100);
+        let passed = CatchAnalysis::new(&difficulty, &map).unwrap();
+        let attrs = difficulty.calculate_for_mode::<Catch>(&map).unwrap();
+        let last_time = passed.objects.last().unwrap().start_time;
+
+        assert_eq!(passed.tiny_droplets.len(), attrs.n_tiny_droplets as usize);
+        assert!(passed.tiny_droplets.iter().all(|&time| time < last_time));
+    }
+}
@@ -113,6 +113,14 @@ pub struct GradualObjectCount {
     tiny_droplets: u32,
 }
 
+/// Start times of objects in order of conversion.
+#[derive(Clone, Default)]
+pub struct ObjectTimelines {
+    pub fruits: Vec<f64>,
+    pub droplets: Vec<f64>,
+    pub tiny_droplets: Vec<f64>,
+}
+
 pub enum ObjectCountBuilder {
     Regular {
         count: ObjectCount,
@@ -122,6 +130,10 @@ pub enum ObjectCountBuilder {
         count: GradualObjectCount,
         all: Vec<GradualObjectCount>,
     },
+    Timelines {
+        timelines: ObjectTimelines,
+        take: usize,
+    },
 }
 
 impl ObjectCountBuilder {
@@ -139,6 +151,13 @@ impl ObjectCountBuilder {
         }
     }
 
+    pub fn new_timelines(take: usize) -> Self {
+        Self::Timelines {
+            timelines: ObjectTimelines::default(),
+            take,
+        }
+    }
+
     pub fn into_regular(self) -> ObjectCount {
         if let Self::Regular { count, .. } = self {
             count
@@ -155,7 +174,15 @@ impl ObjectCountBuilder {
         }
     }
 
-    pub fn record_fruit(&mut self) {
+    pub fn into_timelines(self) -> ObjectTimelines {
+        if let Self::Timelines { timelines, .. } = self {
+            timelines
+        } else {
+            unreachable!()
+        }
+    }
+
+    pub fn record_fruit(&mut self, start_time: f64) {
         match self {
             Self::Regular { count, take } => {
                 if *take > 0 {
@@ -167,10 +194,16 @@ impl ObjectCountBuilder {
                 count.fruit = true;
                 all.push(mem::take(count));
             }
+            Self::Timelines { timelines, take } => {
+                if *take > 0 {
+                    *take -= 1;
+                    timelines.fruits.push(start_time);
+                }
+            }
         }
     }
 
-    pub fn record_droplet(&mut self) {
+    pub fn record_droplet(&mut self, start_time: f64) {
         match self {
             Self::Regular { count, take } => {
                 if *take > 0 {
@@ -179,17 +212,28 @@ impl ObjectCountBuilder {
                 }
             }
             Self::Gradual { count, all } => all.push(mem::take(count)),
+            Self::Timelines { timelines, take } => {
+                if *take > 0 {
+                    *take -= 1;
+                    timelines.droplets.push(start_time);
+                }
+            }
         }
     }
 
-    pub const fn record_tiny_droplets(&mut self, n: u32) {
+    pub fn record_tiny_droplet(&mut self, start_time: f64) {
         match self {
             Self::Regular { count, take } => {
                 if *take > 0 {
-                    count.tiny_droplets += n;
+                    count.tiny_droplets += 1;
+                }
+            }
+            Self::Gradual { count, .. } => count.tiny_droplets += 1,
+            Self::Timelines { timelines, take } => {
+                if *take > 0 {
+                    timelines.tiny_droplets.push(start_time);
                 }
             }
-            Self::Gradual { count, .. } => count.tiny_droplets += n,
         }
     }
 }
@@ -1,12 +1,13 @@
 use rosu_map::section::{general::GameMode, hit_objects::CurveBuffers};
 
 use crate::{
+    any::difficulty::cancellation::Cancellation,
     model::{
         beatmap::Beatmap,
         hit_object::{HitObject, HitObjectKind, HoldNote, Spinner},
         mods::Reflection,
     },
-    util::{float_ext::FloatExt, random::osu::Random},
+    util::{float_ext::FloatExt, hint::unlikely, random::osu::Random},
 };
 
 use super::{
@@ -34,6 +35,7 @@ pub fn convert_objects(
     reflection: Reflection,
     hr_offsets: bool,
     cs: f32,
+    cancellation: &Cancellation,
 ) -> Vec<PalpableObject> {
     // mean=686.54 | median=501
     let mut palpable_objects = Vec::with_capacity(512);
@@ -51,6 +53,10 @@ pub fn convert_objects(
     let mut last_start_time = 0.0;
 
     for h in map.hit_objects.iter() {
+        if unlikely(cancellation.is_cancelled()) {
+            break;
+        }
+
         let mut new_objects = convert_object(h, map, count, &mut bufs);
 
         apply_pos_offset(
@@ -84,7 +90,7 @@ fn convert_object<'a>(
     bufs: &'a mut JuiceStreamBufs,
 ) -> ObjectIter<'a> {
     let state = match h.kind {
-        HitObjectKind::Circle => ObjectIterState::Fruit(Some(Fruit::new(count))),
+        HitObjectKind::Circle => ObjectIterState::Fruit(Some(Fruit::new(count, h.start_time))),
         HitObjectKind::Slider(ref slider) => {
             let effective_x = h.pos.x.clamp(0.0, PLAYFIELD_WIDTH);
             let stream = JuiceStream::new(effective_x, h.start_time, slider, map, count, bufs);
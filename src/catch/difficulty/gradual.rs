@@ -3,7 +3,11 @@ use std::cmp;
 use rosu_map::section::general::GameMode;
 
 use crate::{
-    any::difficulty::skills::StrainSkill,
+    any::difficulty::{
+        cancellation::Cancellation,
+        checkpoints::{Checkpoints, ResumeError, StateHeader, StateReader, StateWriter},
+        skills::StrainSkill,
+    },
     catch::{
         attributes::{GradualObjectCount, ObjectCountBuilder},
         catcher::Catcher,
@@ -11,6 +15,7 @@ use crate::{
         CatchDifficultyAttributes,
     },
     model::mode::ConvertError,
+    util::judgement_times::JudgementTimes,
     Beatmap, Difficulty,
 };
 
@@ -58,11 +63,15 @@ use super::{
 pub struct CatchGradualDifficulty {
     pub(crate) idx: usize,
     pub(crate) difficulty: Difficulty,
+    /// See [`StateHeader::map_hash`].
+    map_hash: u64,
     attrs: CatchDifficultyAttributes,
     /// The delta of object counts after each palpable object
     count: Vec<GradualObjectCount>,
+    judgement_times: JudgementTimes,
     diff_objects: Box<[CatchDifficultyObject]>,
     movement: Movement,
+    checkpoints: Checkpoints<(CatchDifficultyAttributes, Movement)>,
 }
 
 impl CatchGradualDifficulty {
@@ -85,6 +94,7 @@ impl CatchGradualDifficulty {
             reflection,
             hr_offsets,
             map_attrs.cs as f32,
+            &Cancellation::NONE,
         );
 
         let mut half_catcher_width = Catcher::calculate_catch_width(map_attrs.cs as f32) * 0.5;
@@ -97,23 +107,123 @@ impl CatchGradualDifficulty {
         );
 
         let count = count.into_gradual();
+        let judgement_times = palpable_objects.iter().map(|h| h.start_time).collect();
         let movement = Movement::new(half_catcher_width, clock_rate);
+        let checkpoints = Checkpoints::new((attrs.clone(), movement.clone()));
 
         Ok(Self {
             idx: 0,
+            map_hash: StateHeader::map_hash(&map),
             difficulty,
             attrs,
             count,
+            judgement_times,
             diff_objects,
             movement,
+            checkpoints,
         })
     }
-}
 
-impl Iterator for CatchGradualDifficulty {
-    type Item = CatchDifficultyAttributes;
+    /// Take a snapshot of the calculator's state every `interval` processed
+    /// palpable objects so that [`CatchGradualDifficulty::seek`] can rewind
+    /// without starting over.
+    ///
+    /// Snapshots are disabled by default, i.e. an interval of `0`. Each
+    /// snapshot stores the skills' strain history so smaller intervals trade
+    /// memory for faster seeking.
+    #[must_use]
+    pub const fn checkpoint_interval(mut self, interval: usize) -> Self {
+        self.checkpoints.set_interval(interval);
+
+        self
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Move to the state after processing `idx` palpable objects so that the
+    /// next call of [`Iterator::next`] processes the palpable object at index
+    /// `idx`.
+    ///
+    /// Seeking backwards resumes from the latest snapshot at or before `idx`,
+    /// see [`CatchGradualDifficulty::checkpoint_interval`].
+    pub fn seek(&mut self, idx: usize) {
+        if let Some((n, (attrs, movement))) = self.checkpoints.restore(self.idx, idx) {
+            self.idx = n;
+            self.attrs = attrs;
+            self.movement = movement;
+        }
+
+        while self.idx < idx && self.process_next().is_some() {}
+    }
+
+    /// Process all palpable objects that are judged at or before `time`, i.e. the
+    /// current playback position in milliseconds, and return the attributes
+    /// at that moment.
+    ///
+    /// Tiny droplets are not considered.
+    /// Processing includes every palpable object up to the last one that is judged at
+    /// or before `time`. Already processed palpable objects are never rewound, see
+    /// [`CatchGradualDifficulty::seek`].
+    ///
+    /// Returns `None` if no palpable object has been processed yet.
+    pub fn advance_to_time(&mut self, time: f64) -> Option<CatchDifficultyAttributes> {
+        while self.judgement_times.is_judged(self.idx, time) {
+            self.process_next()?;
+        }
+
+        (self.idx > 0).then(|| self.eval())
+    }
+
+    /// Serialize the current state, i.e. the amount of processed palpable
+    /// objects, the partial attributes, and the skill's strain history.
+    ///
+    /// The state can be resumed through [`CatchGradualDifficulty::resume`],
+    /// e.g. in another process. Snapshots of
+    /// [`CatchGradualDifficulty::checkpoint_interval`] are not included.
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new(self.state_header(), self.idx);
+
+        writer
+            .write(&self.attrs.n_fruits)
+            .write(&self.attrs.n_droplets)
+            .write(&self.attrs.n_tiny_droplets)
+            .write(&self.movement);
+
+        writer.finish()
+    }
+
+    /// Resume a state that was serialized through
+    /// [`CatchGradualDifficulty::save_state`].
+    ///
+    /// The difficulty objects are rebuilt from the map so `difficulty` and
+    /// `map` must be the same as for the serialized calculator.
+    pub fn resume(
+        difficulty: Difficulty,
+        map: &Beatmap,
+        state: &[u8],
+    ) -> Result<Self, ResumeError> {
+        let mut this = Self::new(difficulty, map)?;
+        let mut reader = StateReader::new(state, this.state_header())?;
+
+        this.attrs.n_fruits = reader.read()?;
+        this.attrs.n_droplets = reader.read()?;
+        this.attrs.n_tiny_droplets = reader.read()?;
+        this.movement = reader.read()?;
+        this.idx = reader.idx();
+        reader.finish()?;
+
+        Ok(this)
+    }
+
+    fn state_header(&self) -> StateHeader {
+        StateHeader::new(
+            GameMode::Catch,
+            &self.difficulty,
+            self.idx + self.len(),
+            self.map_hash,
+        )
+    }
+
+    /// Process the next palpable object without evaluating the skills.
+    fn process_next(&mut self) -> Option<()> {
         // The first difficulty object belongs to the second palpable object
         // since each difficulty object requires the current and the last note.
         // Hence, if we're still on the first object, we don't have a difficulty
@@ -128,12 +238,29 @@ impl Iterator for CatchGradualDifficulty {
         self.attrs.add_object_count(self.count[self.idx]);
         self.idx += 1;
 
+        self.checkpoints
+            .save(self.idx, || (self.attrs.clone(), self.movement.clone()));
+
+        Some(())
+    }
+
+    fn eval(&self) -> CatchDifficultyAttributes {
         let mut attrs = self.attrs.clone();
 
         let movement = self.movement.cloned_difficulty_value();
         DifficultyValues::eval(&mut attrs, movement);
 
-        Some(attrs)
+        attrs
+    }
+}
+
+impl Iterator for CatchGradualDifficulty {
+    type Item = CatchDifficultyAttributes;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.process_next()?;
+
+        Some(self.eval())
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -143,22 +270,10 @@ impl Iterator for CatchGradualDifficulty {
     }
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
-        let skip_iter = self.diff_objects.iter().skip(self.idx.saturating_sub(1));
+        let take = cmp::min(n, self.len().saturating_sub(1));
 
-        let mut take = cmp::min(n, self.len().saturating_sub(1));
-
-        // The first palpable object has no difficulty object
-        if self.idx == 0 && take > 0 {
-            take -= 1;
-            self.attrs.add_object_count(self.count[self.idx]);
-            self.idx += 1;
-        }
-
-        for curr in skip_iter.take(take) {
-            self.movement.process(curr, &self.diff_objects);
-
-            self.attrs.add_object_count(self.count[self.idx]);
-            self.idx += 1;
+        for _ in 0..take {
+            self.process_next()?;
         }
 
         self.next()
@@ -221,4 +336,73 @@ mod tests {
             assert_eq!(next_gradual, expected);
         }
     }
+
+    #[test]
+    fn seek() {
+        let map = Beatmap::from_path("./resources/2118524.osu").unwrap();
+
+        let difficulty = Difficulty::new();
+
+        let expected: Vec<_> = CatchGradualDifficulty::new(difficulty.clone(), &map)
+            .unwrap()
+            .collect();
+
+        for interval in [0, 1, 50] {
+            let mut gradual = CatchGradualDifficulty::new(difficulty.clone(), &map)
+                .unwrap()
+                .checkpoint_interval(interval);
+
+            for idx in [100, 30, 0, 51, 50, expected.len() - 1, 2, 99] {
+                gradual.seek(idx);
+                assert_eq!(gradual.len(), expected.len() - idx);
+                assert_eq!(gradual.next().as_ref(), expected.get(idx), "{idx}");
+            }
+
+            gradual.seek(expected.len());
+            assert!(gradual.next().is_none());
+        }
+    }
+
+    #[test]
+    fn save_and_resume() {
+        let map = Beatmap::from_path("./resources/2118524.osu").unwrap();
+
+        let difficulty = Difficulty::new().mods(64); // DT
+
+        let mut gradual = CatchGradualDifficulty::new(difficulty.clone(), &map).unwrap();
+        let _ = gradual.nth(100);
+        let state = gradual.save_state();
+
+        let resumed = CatchGradualDifficulty::resume(difficulty, &map, &state).unwrap();
+        assert_eq!(resumed.len(), gradual.len());
+        assert!(resumed.eq(gradual));
+    }
+
+    #[test]
+    fn advance_to_time() {
+        let map = Beatmap::from_path("./resources/2118524.osu").unwrap();
+
+        let difficulty = Difficulty::new();
+
+        let expected: Vec<_> = CatchGradualDifficulty::new(difficulty.clone(), &map)
+            .unwrap()
+            .collect();
+
+        let mut gradual = CatchGradualDifficulty::new(difficulty, &map).unwrap();
+        assert!(gradual.advance_to_time(f64::MIN).is_none());
+
+        let times = gradual.judgement_times.clone();
+
+        for time in [times[10], times[10] + 1.0, times[100] - 1.0, times[200]] {
+            let attrs = gradual.advance_to_time(time);
+            let idx = gradual.idx;
+
+            assert!(times[idx - 1] <= time && times[idx] > time, "{time}");
+            assert_eq!(attrs.as_ref(), expected.get(idx - 1), "{time}");
+        }
+
+        let attrs = gradual.advance_to_time(f64::MAX);
+        assert_eq!(attrs.as_ref(), expected.last());
+        assert_eq!(gradual.len(), 0);
+    }
 }
@@ -6,6 +6,7 @@ use crate::{
         catcher::Catcher, convert::convert_objects, difficulty::object::CatchDifficultyObject,
     },
     model::{beatmap::BeatmapAttributes, mode::ConvertError},
+    util::hint::unlikely,
     Beatmap,
 };
 
@@ -14,6 +15,7 @@ use self::skills::movement::Movement;
 use super::{
     attributes::{CatchDifficultyAttributes, ObjectCountBuilder},
     object::palpable::PalpableObject,
+    CatchStrains,
 };
 
 pub mod gradual;
@@ -38,6 +40,27 @@ pub fn difficulty(
     Ok(attrs)
 }
 
+/// Calculate the difficulty attributes alongside the strains.
+pub fn difficulty_with_strains(
+    difficulty: &Difficulty,
+    map: &Beatmap,
+) -> Result<(CatchDifficultyAttributes, CatchStrains), ConvertError> {
+    let map = map.convert_ref(GameMode::Catch, difficulty.get_mods())?;
+
+    let DifficultyValues {
+        movement,
+        mut attrs,
+    } = DifficultyValues::calculate(difficulty, &map);
+
+    DifficultyValues::eval(&mut attrs, movement.cloned_difficulty_value());
+
+    let strains = CatchStrains {
+        movement: movement.into_current_strain_peaks().into_vec(),
+    };
+
+    Ok((attrs, strains))
+}
+
 pub struct CatchDifficultySetup {
     map_attrs: BeatmapAttributes,
     attrs: CatchDifficultyAttributes,
@@ -76,8 +99,16 @@ impl DifficultyValues {
         let reflection = difficulty.get_mods().reflection();
         let mut count = ObjectCountBuilder::new_regular(take);
 
-        let palpable_objects =
-            convert_objects(map, &mut count, reflection, hr_offsets, map_attrs.cs as f32);
+        let cancellation = difficulty.get_cancellation();
+
+        let palpable_objects = convert_objects(
+            map,
+            &mut count,
+            reflection,
+            hr_offsets,
+            map_attrs.cs as f32,
+            cancellation,
+        );
 
         let mut half_catcher_width = Catcher::calculate_catch_width(map_attrs.cs as f32) * 0.5;
         half_catcher_width *= 1.0 - ((map_attrs.cs as f32 - 5.5).max(0.0) * 0.0625);
@@ -91,6 +122,10 @@ impl DifficultyValues {
         let mut movement = Movement::new(half_catcher_width, clock_rate);
 
         for curr in diff_objects.iter() {
+            if unlikely(cancellation.is_cancelled()) {
+                break;
+            }
+
             movement.process(curr, &diff_objects);
         }
 
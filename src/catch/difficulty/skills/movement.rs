@@ -1,6 +1,7 @@
 use crate::{catch::difficulty::object::CatchDifficultyObject, util::float_ext::FloatExt};
 
 define_skill! {
+    #[derive(Clone)]
     pub struct Movement: StrainDecaySkill => [CatchDifficultyObject][CatchDifficultyObject] {
         half_catcher_width: f32,
         clock_rate: f64,
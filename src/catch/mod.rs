@@ -9,6 +9,7 @@ use crate::{
 };
 
 pub use self::{
+    analysis::{CatchAnalysis, CatchAnalysisObject},
     attributes::{CatchDifficultyAttributes, CatchPerformanceAttributes},
     difficulty::gradual::CatchGradualDifficulty,
     performance::{gradual::CatchGradualPerformance, CatchPerformance},
@@ -16,6 +17,7 @@ pub use self::{
     strains::CatchStrains,
 };
 
+mod analysis;
 mod attributes;
 mod catcher;
 mod convert;
@@ -37,6 +39,13 @@ impl Catch {
         debug_assert!(!map.is_convert && map.mode == GameMode::Osu);
         convert::convert(map);
     }
+
+    pub(crate) fn difficulty_with_strains(
+        difficulty: &Difficulty,
+        map: &Beatmap,
+    ) -> Result<(CatchDifficultyAttributes, CatchStrains), ConvertError> {
+        difficulty::difficulty_with_strains(difficulty, map)
+    }
 }
 
 impl IGameMode for Catch {
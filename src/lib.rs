@@ -127,6 +127,9 @@
 //! | Flag          | Description         | Dependencies
 //! | ------------- | ------------------- | ------------
 //! | `default`     | No features enabled |
+//! | `batch`       | Enables the `batch` module to calculate many jobs in parallel on a pool of threads. |
+//! | `capi`        | Enables the `capi` module which exports a C interface. The matching header is `include/refx_pp.h`. |
+//! | `cli`         | Builds the `refx-pp` binary to calculate attributes of a single map or a whole directory from the command line, or to serve calculations over HTTP. |
 //! | `raw_strains` | With this feature, internal strain values will be stored in a plain `Vec`. This introduces an out-of-memory risk on maliciously long maps (see [/b/3739922](https://osu.ppy.sh/b/3739922)), but comes with a ~5% gain in performance. |
 //! | `sync`        | Some gradual calculation types can only be shared across threads if this feature is enabled. This feature adds a small performance penalty. |
 //! | `tracing`     | Any error encountered during beatmap decoding will be logged through `tracing::error`. If this feature is **not** enabled, errors will be ignored. | [`tracing`]
@@ -136,6 +139,7 @@
 //! Using `refx-pp` from other languages than Rust:
 //! - JavaScript: [refx-pp-js]
 //! - Python: [refx-pp-py]
+//! - C and anything that can call C functions: enable the `capi` feature
 //!
 //! [osu!]: https://osu.ppy.sh/home
 //! [osu!lazer]: https://github.com/ppy/osu
@@ -161,7 +165,8 @@
     clippy::explicit_iter_loop,
     clippy::similar_names,
     clippy::cast_possible_wrap,
-    clippy::manual_midpoint
+    clippy::manual_midpoint,
+    clippy::manual_is_multiple_of
 )]
 
 #[doc(inline)]
@@ -190,3 +195,11 @@ pub mod mania;
 
 /// Types used in and around this crate.
 pub mod model;
+
+/// Parallel calculation of many difficulty and performance jobs.
+#[cfg(feature = "batch")]
+pub mod batch;
+
+/// C interface to use `refx-pp` from other languages.
+#[cfg(feature = "capi")]
+pub mod capi;
@@ -3,6 +3,7 @@ use std::cmp::Ordering;
 use rosu_map::{section::general::GameMode, util::Pos};
 
 use crate::{
+    any::difficulty::cancellation::Cancellation,
     mania::object::ManiaObject,
     model::{
         beatmap::Beatmap,
@@ -10,6 +11,7 @@ use crate::{
         hit_object::{HitObject, HitObjectKind, HoldNote, Spinner},
     },
     util::{
+        hint::unlikely,
         limited_queue::LimitedQueue,
         random::{csharp::Random as CsharpRandom, osu::Random as OsuRandom},
         sort,
@@ -32,7 +34,7 @@ mod pattern_type;
 
 const MAX_NOTES_FOR_DENSITY: usize = 7;
 
-pub fn convert(map: &mut Beatmap, mods: &GameMods) {
+pub fn convert(map: &mut Beatmap, mods: &GameMods, cancellation: &Cancellation) {
     let seed = (map.hp + map.cs).round_ties_even() as i32 * 20
         + (map.od * 41.2) as i32
         + map.ar.round_ties_even() as i32;
@@ -41,6 +43,10 @@ pub fn convert(map: &mut Beatmap, mods: &GameMods) {
 
     map.cs = target_columns(map, mods);
 
+    if mods.dual_stages() {
+        map.cs *= 2.0;
+    }
+
     let mut prev_note_times = LimitedQueue::<f64, MAX_NOTES_FOR_DENSITY>::new();
     let mut density = f64::from(i32::MAX);
 
@@ -61,6 +67,10 @@ pub fn convert(map: &mut Beatmap, mods: &GameMods) {
     let mut new_hit_objects = Vec::with_capacity(512);
 
     for (obj, sound) in map.hit_objects.iter().zip(map.hit_sounds.iter().copied()) {
+        if unlikely(cancellation.is_cancelled()) {
+            break;
+        }
+
         match obj.kind {
             HitObjectKind::Circle => {
                 compute_density(obj.start_time, &mut density);
@@ -318,12 +328,26 @@ pub(super) fn apply_random_to_beatmap(map: &mut Beatmap, seed: i32) {
     }
 }
 
+pub(super) fn apply_mirror_to_beatmap(map: &mut Beatmap) {
+    let total_columns = map.cs;
+    let available_columns = total_columns as usize;
+    let divisor = 512.0 / total_columns;
+
+    for h in map.hit_objects.iter_mut() {
+        let old_column = ManiaObject::column(h.pos.x, total_columns);
+        let new_column = available_columns.saturating_sub(old_column + 1);
+        h.pos.x = f32::ceil(new_column as f32 * divisor);
+    }
+}
+
 fn cmp_by_start_time(a: &HitObject, b: &HitObject) -> Ordering {
     a.start_time.total_cmp(&b.start_time)
 }
 
 #[cfg(test)]
 mod tests {
+    use rosu_mods::GameModsLegacy;
+
     use crate::util::float_ext::FloatExt;
 
     use super::*;
@@ -358,4 +382,56 @@ mod tests {
         assert!(map.stack_leniency.eq(0.5), "{} != 0.5", map.stack_leniency);
         assert_eq!(map.breaks.len(), 1);
     }
+
+    #[test]
+    fn convert_mania_dual_stages() {
+        let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+
+        let mods = GameMods::from(GameModsLegacy::KeyCoop);
+        let dual = map.convert_ref(GameMode::Mania, &mods).unwrap();
+        assert!(dual.cs.eq(14.0), "{} != 14.0", dual.cs);
+
+        let mods = GameMods::from(GameModsLegacy::KeyCoop | GameModsLegacy::Key4);
+        let dual = map.convert_ref(GameMode::Mania, &mods).unwrap();
+        assert!(dual.cs.eq(8.0), "{} != 8.0", dual.cs);
+
+        let max_column = dual
+            .hit_objects
+            .iter()
+            .map(|h| ManiaObject::column(h.pos.x, dual.cs))
+            .max();
+
+        assert_eq!(max_column, Some(7));
+
+        // Dual stages only apply to converts
+        let mania = Beatmap::from_path("./resources/1638954.osu").unwrap();
+        let converted = mania.convert_ref(GameMode::Mania, &mods).unwrap();
+        assert!(converted.cs.eq(mania.cs));
+    }
+
+    #[test]
+    fn apply_mirror() {
+        let map = Beatmap::from_path("./resources/1638954.osu").unwrap();
+        let mut mirrored = map.clone();
+        apply_mirror_to_beatmap(&mut mirrored);
+
+        let total_columns = map.cs;
+
+        for (h, mirrored) in map.hit_objects.iter().zip(mirrored.hit_objects.iter()) {
+            let column = ManiaObject::column(h.pos.x, total_columns);
+            let mirrored_column = ManiaObject::column(mirrored.pos.x, total_columns);
+
+            assert_eq!(column + mirrored_column, total_columns as usize - 1);
+            assert!(h.start_time.eq(mirrored.start_time));
+        }
+
+        apply_mirror_to_beatmap(&mut mirrored);
+
+        for (h, mirrored) in map.hit_objects.iter().zip(mirrored.hit_objects.iter()) {
+            assert_eq!(
+                ManiaObject::column(h.pos.x, total_columns),
+                ManiaObject::column(mirrored.pos.x, total_columns)
+            );
+        }
+    }
 }
@@ -3,9 +3,16 @@ use std::cmp;
 use rosu_map::section::general::GameMode;
 
 use crate::{
-    any::difficulty::skills::StrainSkill,
+    any::difficulty::{
+        checkpoints::{Checkpoints, ResumeError, StateHeader, StateReader, StateWriter},
+        skills::StrainSkill,
+    },
     mania::{convert, object::ObjectParams},
     model::{hit_object::HitObject, mode::ConvertError},
+    util::{
+        codec::{Decode, Encode},
+        judgement_times::JudgementTimes,
+    },
     Beatmap, Difficulty,
 };
 
@@ -49,19 +56,39 @@ use super::{
 pub struct ManiaGradualDifficulty {
     pub(crate) idx: usize,
     pub(crate) difficulty: Difficulty,
+    /// See [`StateHeader::map_hash`].
+    map_hash: u64,
     objects_is_circle: Box<[bool]>,
+    judgement_times: JudgementTimes,
     is_convert: bool,
     strain: Strain,
     diff_objects: Box<[ManiaDifficultyObject]>,
     note_state: NoteState,
+    checkpoints: Checkpoints<(Strain, NoteState)>,
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 struct NoteState {
     curr_combo: u32,
     n_hold_notes: u32,
 }
 
+impl Encode for NoteState {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.curr_combo.encode(buf);
+        self.n_hold_notes.encode(buf);
+    }
+}
+
+impl Decode for NoteState {
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        Some(Self {
+            curr_combo: u32::decode(bytes)?,
+            n_hold_notes: u32::decode(bytes)?,
+        })
+    }
+}
+
 impl ManiaGradualDifficulty {
     /// Create a new difficulty attributes iterator for osu!mania maps.
     pub fn new(difficulty: Difficulty, map: &Beatmap) -> Result<Self, ConvertError> {
@@ -79,15 +106,21 @@ impl ManiaGradualDifficulty {
             convert::apply_random_to_beatmap(map.to_mut(), seed);
         }
 
+        if difficulty.get_mods().mr() {
+            convert::apply_mirror_to_beatmap(map.to_mut());
+        }
+
         let take = difficulty.get_passed_objects();
         let total_columns = map.cs.round_ties_even().max(1.0);
         let clock_rate = difficulty.get_clock_rate();
         let mut params = ObjectParams::new(&map);
+        let mut end_times = Vec::with_capacity(map.hit_objects.len());
 
         let mania_objects = map
             .hit_objects
             .iter()
             .map(|h| ManiaObject::new(h, total_columns, &mut params))
+            .inspect(|h| end_times.push(h.end_time))
             .take(take);
 
         let diff_objects = DifficultyValues::create_difficulty_objects(clock_rate, mania_objects);
@@ -110,22 +143,122 @@ impl ManiaGradualDifficulty {
             );
         }
 
+        let checkpoints = Checkpoints::new((strain.clone(), note_state.clone()));
+
         Ok(Self {
             idx: 0,
+            map_hash: StateHeader::map_hash(&map),
             difficulty,
             objects_is_circle,
+            judgement_times: end_times.into_iter().collect(),
             is_convert: map.is_convert,
             strain,
             diff_objects,
             note_state,
+            checkpoints,
         })
     }
-}
 
-impl Iterator for ManiaGradualDifficulty {
-    type Item = ManiaDifficultyAttributes;
+    /// Take a snapshot of the calculator's state every `interval` processed
+    /// hit objects so that [`ManiaGradualDifficulty::seek`] can rewind
+    /// without starting over.
+    ///
+    /// Snapshots are disabled by default, i.e. an interval of `0`. Each
+    /// snapshot stores the skills' strain history so smaller intervals trade
+    /// memory for faster seeking.
+    #[must_use]
+    pub const fn checkpoint_interval(mut self, interval: usize) -> Self {
+        self.checkpoints.set_interval(interval);
+
+        self
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Move to the state after processing `idx` hit objects so that the
+    /// next call of [`Iterator::next`] processes the hit object at index `idx`.
+    ///
+    /// Seeking backwards resumes from the latest snapshot at or before `idx`,
+    /// see [`ManiaGradualDifficulty::checkpoint_interval`].
+    pub fn seek(&mut self, idx: usize) {
+        if let Some((n, (strain, note_state))) = self.checkpoints.restore(self.idx, idx) {
+            self.idx = n;
+            self.strain = strain;
+            self.note_state = note_state;
+        }
+
+        while self.idx < idx && self.process_next().is_some() {}
+    }
+
+    /// Process all hit objects that are judged at or before `time`, i.e. the
+    /// current playback position in milliseconds, and return the attributes
+    /// at that moment.
+    ///
+    /// Notes are judged at their start time, hold notes at their end time.
+    /// Objects are processed in order of their start time so if a later hit
+    /// object is judged while a hold note is still in progress, the
+    /// unjudged object is processed as well instead of blocking the judged one.
+    /// Already processed hit objects are never rewound, see
+    /// [`ManiaGradualDifficulty::seek`].
+    ///
+    /// Returns `None` if no hit object has been processed yet.
+    pub fn advance_to_time(&mut self, time: f64) -> Option<ManiaDifficultyAttributes> {
+        while self.judgement_times.is_judged(self.idx, time) {
+            self.process_next()?;
+        }
+
+        (self.idx > 0).then(|| self.eval())
+    }
+
+    /// Serialize the current state, i.e. the amount of processed hit objects,
+    /// the combo, and the skill's strain history.
+    ///
+    /// The state can be resumed through [`ManiaGradualDifficulty::resume`],
+    /// e.g. in another process. Snapshots of
+    /// [`ManiaGradualDifficulty::checkpoint_interval`] are not included.
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new(self.state_header(), self.idx);
+        writer.write(&self.note_state).write(&self.strain);
+
+        writer.finish()
+    }
+
+    /// Resume a state that was serialized through
+    /// [`ManiaGradualDifficulty::save_state`].
+    ///
+    /// The difficulty objects are rebuilt from the map so `difficulty` and
+    /// `map` must be the same as for the serialized calculator.
+    pub fn resume(
+        difficulty: Difficulty,
+        map: &Beatmap,
+        state: &[u8],
+    ) -> Result<Self, ResumeError> {
+        let mut this = Self::new(difficulty, map)?;
+        let mut reader = StateReader::new(state, this.state_header())?;
+
+        this.note_state = reader.read()?;
+        let strain: Strain = reader.read()?;
+
+        if strain.total_columns() != this.strain.total_columns() {
+            return Err(ResumeError::Mismatch);
+        }
+
+        this.strain = strain;
+        this.idx = reader.idx();
+        reader.finish()?;
+
+        Ok(this)
+    }
+
+    fn state_header(&self) -> StateHeader {
+        StateHeader::new(
+            GameMode::Mania,
+            &self.difficulty,
+            self.idx + self.len(),
+            self.map_hash,
+        )
+    }
+
+    /// Process the next hit object without evaluating the skills.
+    fn process_next(&mut self) -> Option<()> {
         // The first difficulty object belongs to the second note since each
         // difficulty object requires the current and the last note. Hence, if
         // we're still on the first object, we don't have a difficulty object
@@ -147,13 +280,36 @@ impl Iterator for ManiaGradualDifficulty {
 
         self.idx += 1;
 
-        Some(ManiaDifficultyAttributes {
+        self.checkpoints
+            .save(self.idx, || (self.strain.clone(), self.note_state.clone()));
+
+        Some(())
+    }
+
+    pub(crate) fn is_hold_note(&self, idx: usize) -> bool {
+        self.objects_is_circle
+            .get(idx)
+            .is_some_and(|is_circle| !is_circle)
+    }
+
+    fn eval(&self) -> ManiaDifficultyAttributes {
+        ManiaDifficultyAttributes {
             stars: self.strain.cloned_difficulty_value() * DIFFICULTY_MULTIPLIER,
             max_combo: self.note_state.curr_combo,
             n_objects: self.idx as u32,
             n_hold_notes: self.note_state.n_hold_notes,
             is_convert: self.is_convert,
-        })
+        }
+    }
+}
+
+impl Iterator for ManiaGradualDifficulty {
+    type Item = ManiaDifficultyAttributes;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.process_next()?;
+
+        Some(self.eval())
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -163,26 +319,10 @@ impl Iterator for ManiaGradualDifficulty {
     }
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
-        let skip_iter = self
-            .diff_objects
-            .iter()
-            .zip(self.objects_is_circle.iter().skip(1))
-            .skip(self.idx.saturating_sub(1));
-
-        let mut take = cmp::min(n, self.len().saturating_sub(1));
+        let take = cmp::min(n, self.len().saturating_sub(1));
 
-        // The first note has no difficulty object
-        if self.idx == 0 && take > 0 {
-            take -= 1;
-            self.idx += 1;
-        }
-
-        let clock_rate = self.difficulty.get_clock_rate();
-
-        for (curr, is_circle) in skip_iter.take(take) {
-            increment_combo(*is_circle, curr, &mut self.note_state, clock_rate);
-            self.strain.process(curr, &self.diff_objects);
-            self.idx += 1;
+        for _ in 0..take {
+            self.process_next()?;
         }
 
         self.next()
@@ -220,7 +360,9 @@ fn increment_combo_raw(is_circle: bool, start_time: f64, end_time: f64, state: &
 
 #[cfg(test)]
 mod tests {
-    use crate::{mania::Mania, Beatmap};
+    use rosu_map::util::Pos;
+
+    use crate::{mania::Mania, model::beatmap::BeatmapBuilder, Beatmap};
 
     use super::*;
 
@@ -270,4 +412,109 @@ mod tests {
             assert_eq!(next_gradual, expected);
         }
     }
+
+    #[test]
+    fn seek() {
+        let map = Beatmap::from_path("./resources/1638954.osu").unwrap();
+
+        let difficulty = Difficulty::new();
+
+        let expected: Vec<_> = ManiaGradualDifficulty::new(difficulty.clone(), &map)
+            .unwrap()
+            .collect();
+
+        for interval in [0, 1, 50] {
+            let mut gradual = ManiaGradualDifficulty::new(difficulty.clone(), &map)
+                .unwrap()
+                .checkpoint_interval(interval);
+
+            for idx in [100, 30, 0, 51, 50, expected.len() - 1, 2, 99] {
+                gradual.seek(idx);
+                assert_eq!(gradual.len(), expected.len() - idx);
+                assert_eq!(gradual.next().as_ref(), expected.get(idx), "{idx}");
+            }
+
+            gradual.seek(expected.len());
+            assert!(gradual.next().is_none());
+        }
+    }
+
+    #[test]
+    fn save_and_resume() {
+        let map = Beatmap::from_path("./resources/1638954.osu").unwrap();
+
+        let difficulty = Difficulty::new().mods(64); // DT
+
+        let mut gradual = ManiaGradualDifficulty::new(difficulty.clone(), &map).unwrap();
+        let _ = gradual.nth(100);
+        let state = gradual.save_state();
+
+        let resumed = ManiaGradualDifficulty::resume(difficulty, &map, &state).unwrap();
+        assert_eq!(resumed.len(), gradual.len());
+        assert!(resumed.eq(gradual));
+    }
+
+    #[test]
+    fn advance_to_time() {
+        let map = Beatmap::from_path("./resources/1638954.osu").unwrap();
+
+        let difficulty = Difficulty::new();
+
+        let expected: Vec<_> = ManiaGradualDifficulty::new(difficulty.clone(), &map)
+            .unwrap()
+            .collect();
+
+        let mut gradual = ManiaGradualDifficulty::new(difficulty, &map).unwrap();
+        assert!(gradual.advance_to_time(f64::MIN).is_none());
+
+        let times = gradual.judgement_times.clone();
+
+        for time in [times[10], times[10] + 1.0, times[100] - 1.0, times[200]] {
+            let attrs = gradual.advance_to_time(time);
+            let idx = gradual.idx;
+
+            assert!(times[idx - 1] <= time && times[idx] > time, "{time}");
+            assert_eq!(attrs.as_ref(), expected.get(idx - 1), "{time}");
+        }
+
+        let attrs = gradual.advance_to_time(f64::MAX);
+        assert_eq!(attrs.as_ref(), expected.last());
+        assert_eq!(gradual.len(), 0);
+    }
+
+    #[test]
+    fn advance_to_time_during_hold() {
+        let map = BeatmapBuilder::new()
+            .mode(GameMode::Mania)
+            .cs(4.0)
+            .timing_point(0.0, 500.0)
+            .circle(Pos::new(64.0, 192.0), 500.0)
+            .hold(192.0, 1000.0, 3000.0)
+            .circle(Pos::new(320.0, 192.0), 1500.0)
+            .circle(Pos::new(448.0, 192.0), 2000.0)
+            .circle(Pos::new(64.0, 192.0), 5000.0)
+            .build()
+            .unwrap();
+
+        let difficulty = Difficulty::new();
+
+        let expected: Vec<_> = ManiaGradualDifficulty::new(difficulty.clone(), &map)
+            .unwrap()
+            .collect();
+
+        let mut gradual = ManiaGradualDifficulty::new(difficulty, &map).unwrap();
+
+        // The hold note is still held but the notes after it are judged
+        let attrs = gradual.advance_to_time(2000.0);
+        assert_eq!(gradual.idx, 4);
+        assert_eq!(attrs.as_ref(), expected.get(3));
+
+        let attrs = gradual.advance_to_time(4999.0);
+        assert_eq!(gradual.idx, 4);
+        assert_eq!(attrs.as_ref(), expected.get(3));
+
+        let attrs = gradual.advance_to_time(5000.0);
+        assert_eq!(gradual.idx, 5);
+        assert_eq!(attrs.as_ref(), expected.last());
+    }
 }
@@ -1,4 +1,4 @@
-use std::cmp;
+use std::{borrow::Cow, cmp};
 
 use rosu_map::section::general::GameMode;
 
@@ -9,10 +9,11 @@ use crate::{
         object::{ManiaObject, ObjectParams},
     },
     model::mode::ConvertError,
+    util::hint::unlikely,
     Beatmap,
 };
 
-use super::{attributes::ManiaDifficultyAttributes, convert};
+use super::{attributes::ManiaDifficultyAttributes, convert, ManiaStrains};
 
 pub mod gradual;
 mod object;
@@ -24,7 +25,57 @@ pub fn difficulty(
     difficulty: &Difficulty,
     map: &Beatmap,
 ) -> Result<ManiaDifficultyAttributes, ConvertError> {
-    let mut map = map.convert_ref(GameMode::Mania, difficulty.get_mods())?;
+    let map = prepare_map(difficulty, map)?;
+
+    let n_objects = cmp::min(difficulty.get_passed_objects(), map.hit_objects.len()) as u32;
+
+    let values = DifficultyValues::calculate(difficulty, &map);
+
+    Ok(ManiaDifficultyAttributes {
+        stars: values.strain.into_difficulty_value() * DIFFICULTY_MULTIPLIER,
+        max_combo: values.max_combo,
+        n_objects,
+        n_hold_notes: values.n_hold_notes,
+        is_convert: map.is_convert,
+    })
+}
+
+/// Calculate the difficulty attributes alongside the strains.
+pub fn difficulty_with_strains(
+    difficulty: &Difficulty,
+    map: &Beatmap,
+) -> Result<(ManiaDifficultyAttributes, ManiaStrains), ConvertError> {
+    let map = prepare_map(difficulty, map)?;
+
+    let n_objects = cmp::min(difficulty.get_passed_objects(), map.hit_objects.len()) as u32;
+
+    let values = DifficultyValues::calculate(difficulty, &map);
+
+    let attrs = ManiaDifficultyAttributes {
+        stars: values.strain.cloned_difficulty_value() * DIFFICULTY_MULTIPLIER,
+        max_combo: values.max_combo,
+        n_objects,
+        n_hold_notes: values.n_hold_notes,
+        is_convert: map.is_convert,
+    };
+
+    let strains = ManiaStrains {
+        strains: values.strain.into_current_strain_peaks().into_vec(),
+    };
+
+    Ok((attrs, strains))
+}
+
+/// Convert the map to osu!mania and apply all mods that modify hit objects.
+pub fn prepare_map<'a>(
+    difficulty: &Difficulty,
+    map: &'a Beatmap,
+) -> Result<Cow<'a, Beatmap>, ConvertError> {
+    let mut map = map.convert_ref_cancellable(
+        GameMode::Mania,
+        difficulty.get_mods(),
+        difficulty.get_cancellation(),
+    )?;
 
     if difficulty.get_mods().ho() {
         convert::apply_hold_off_to_beatmap(map.to_mut());
@@ -38,17 +89,11 @@ pub fn difficulty(
         convert::apply_random_to_beatmap(map.to_mut(), seed);
     }
 
-    let n_objects = cmp::min(difficulty.get_passed_objects(), map.hit_objects.len()) as u32;
-
-    let values = DifficultyValues::calculate(difficulty, &map);
+    if difficulty.get_mods().mr() {
+        convert::apply_mirror_to_beatmap(map.to_mut());
+    }
 
-    Ok(ManiaDifficultyAttributes {
-        stars: values.strain.into_difficulty_value() * DIFFICULTY_MULTIPLIER,
-        max_combo: values.max_combo,
-        n_objects,
-        n_hold_notes: values.n_hold_notes,
-        is_convert: map.is_convert,
-    })
+    Ok(map)
 }
 
 pub struct DifficultyValues {
@@ -74,7 +119,13 @@ impl DifficultyValues {
 
         let mut strain = Strain::new(total_columns as usize);
 
+        let cancellation = difficulty.get_cancellation();
+
         for curr in diff_objects.iter() {
+            if unlikely(cancellation.is_cancelled()) {
+                break;
+            }
+
             strain.process(curr, &diff_objects);
         }
 
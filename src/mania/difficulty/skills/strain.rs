@@ -5,6 +5,7 @@ use crate::{
 };
 
 define_skill! {
+    #[derive(Clone)]
     #[allow(clippy::struct_field_names)]
     pub struct Strain: StrainDecaySkill => [ManiaDifficultyObject][ManiaDifficultyObject] {
         start_times: Box<[f64]>,
@@ -33,6 +34,15 @@ impl Strain {
     const SKILL_MULTIPLIER: f64 = 1.0;
     const STRAIN_DECAY_BASE: f64 = 1.0;
 
+    pub const fn total_columns(&self) -> usize {
+        self.start_times.len()
+    }
+
+    /// The strain of each processed difficulty object.
+    pub fn object_strains(&self) -> &[f64] {
+        &self.strain_skill_object_strains
+    }
+
     fn calculate_initial_strain(
         &self,
         offset: f64,
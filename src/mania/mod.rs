@@ -1,6 +1,7 @@
 use rosu_map::section::general::GameMode;
 
 use crate::{
+    any::difficulty::cancellation::Cancellation,
     model::{
         beatmap::Beatmap,
         mode::{ConvertError, IGameMode},
@@ -11,6 +12,7 @@ use crate::{
 pub use self::{
     attributes::{ManiaDifficultyAttributes, ManiaPerformanceAttributes},
     difficulty::gradual::ManiaGradualDifficulty,
+    patterns::{ManiaPattern, ManiaPatternStats, ManiaPatterns},
     performance::{gradual::ManiaGradualPerformance, ManiaPerformance},
     score_state::ManiaScoreState,
     strains::ManiaStrains,
@@ -20,6 +22,7 @@ mod attributes;
 mod convert;
 mod difficulty;
 mod object;
+mod patterns;
 mod performance;
 mod score_state;
 mod strains;
@@ -30,9 +33,16 @@ mod strains;
 pub struct Mania;
 
 impl Mania {
-    pub(crate) fn convert(map: &mut Beatmap, mods: &GameMods) {
+    pub(crate) fn convert(map: &mut Beatmap, mods: &GameMods, cancellation: &Cancellation) {
         debug_assert!(!map.is_convert && map.mode == GameMode::Osu);
-        convert::convert(map, mods);
+        convert::convert(map, mods, cancellation);
+    }
+
+    pub(crate) fn difficulty_with_strains(
+        difficulty: &Difficulty,
+        map: &Beatmap,
+    ) -> Result<(ManiaDifficultyAttributes, ManiaStrains), ConvertError> {
+        difficulty::difficulty_with_strains(difficulty, map)
     }
 }
 
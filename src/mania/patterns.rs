@@ -0,0 +1,406 @@
+use crate::{model::mode::ConvertError, Beatmap, Difficulty};
+
+use super::{
+    difficulty::{prepare_map, DifficultyValues},
+    object::{ManiaObject, ObjectParams},
+};
+
+/// A pattern that rows of an osu!mania map can be classified as.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ManiaPattern {
+    /// Fast single notes.
+    Stream,
+    /// Fast rows with at most two notes.
+    Jumpstream,
+    /// Fast rows with three or more notes.
+    Handstream,
+    /// Single notes repeated in the same column.
+    Jack,
+    /// Chords that repeat at least one column of the previous chord.
+    Chordjack,
+}
+
+impl ManiaPattern {
+    /// All patterns.
+    pub const ALL: [Self; 5] = [
+        Self::Stream,
+        Self::Jumpstream,
+        Self::Handstream,
+        Self::Jack,
+        Self::Chordjack,
+    ];
+
+    /// The name of the pattern in lowercase, e.g. to use it as tag.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Stream => "stream",
+            Self::Jumpstream => "jumpstream",
+            Self::Handstream => "handstream",
+            Self::Jack => "jack",
+            Self::Chordjack => "chordjack",
+        }
+    }
+}
+
+/// Statistics of a single [`ManiaPattern`].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ManiaPatternStats {
+    /// The amount of rows classified as the pattern.
+    pub rows: usize,
+    /// The amount of rows per second classified as the pattern.
+    pub density: f64,
+    /// The share of the map's total strain that comes from rows classified as
+    /// the pattern.
+    pub strain_share: f64,
+}
+
+/// Pattern analysis of an osu!mania map.
+///
+/// Notes that start at the same time form a row. Rows are classified based on
+/// the columns of the converted hit objects and weighted by the strain values of
+/// the strain skill. All times are adjusted to the clock rate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ManiaPatterns {
+    /// The amount of columns.
+    pub n_columns: usize,
+    /// The amount of hit objects.
+    pub n_objects: usize,
+    /// The amount of hold notes.
+    pub n_hold_notes: usize,
+    /// `n_hold_notes / n_objects`.
+    pub long_note_ratio: f64,
+    /// The amount of rows per size, i.e. the amount of rows with `i + 1`
+    /// notes is stored at index `i`.
+    pub chord_sizes: Vec<usize>,
+    /// The amount of jacks per column, i.e. notes that repeat the column of
+    /// a note in the previous row.
+    pub jacks: Vec<usize>,
+    /// The amount of notes played by the left hand.
+    ///
+    /// For an odd amount of columns, the middle column is played by neither
+    /// hand.
+    pub left_hand_notes: usize,
+    /// The amount of notes played by the right hand.
+    ///
+    /// For an odd amount of columns, the middle column is played by neither
+    /// hand.
+    pub right_hand_notes: usize,
+    patterns: [ManiaPatternStats; ManiaPattern::ALL.len()],
+}
+
+struct Row {
+    start_time: f64,
+    columns: Vec<usize>,
+    strain: f64,
+}
+
+impl Row {
+    fn shares_column(&self, other: &Self) -> bool {
+        self.columns
+            .iter()
+            .any(|column| other.columns.contains(column))
+    }
+}
+
+impl ManiaPatterns {
+    /// Maximum time between two rows of a stream, 120 BPM 1/4th.
+    const STREAM_DELTA_TIME: f64 = 125.0;
+    /// Maximum time between two notes of a jack, 150 BPM 1/2th.
+    const JACK_DELTA_TIME: f64 = 200.0;
+    /// Amount of rows before and after a stream row to consider when
+    /// classifying it.
+    const STREAM_WINDOW: usize = 3;
+
+    /// Analyze the patterns of a map.
+    ///
+    /// Only the mods, clock rate, and passed objects of the [`Difficulty`]
+    /// are relevant.
+    pub fn new(difficulty: &Difficulty, map: &Beatmap) -> Result<Self, ConvertError> {
+        let map = prepare_map(difficulty, map)?;
+
+        let total_columns = map.cs.round_ties_even().max(1.0);
+        let n_columns = total_columns as usize;
+        let clock_rate = difficulty.get_clock_rate();
+        let mut params = ObjectParams::new(&map);
+
+        let mania_objects: Vec<_> = map
+            .hit_objects
+            .iter()
+            .map(|h| ManiaObject::new(h, total_columns, &mut params))
+            .take(difficulty.get_passed_objects())
+            .collect();
+
+        let values = DifficultyValues::calculate(difficulty, &map);
+
+        // The first object has no difficulty object and thus no strain
+        let strains = std::iter::once(0.0).chain(values.strain.object_strains().iter().copied());
+
+        let mut rows: Vec<Row> = Vec::new();
+
+        for (h, strain) in mania_objects.iter().zip(strains) {
+            let start_time = h.start_time / clock_rate;
+
+            match rows.last_mut() {
+                Some(row) if (start_time - row.start_time).abs() < 1.0 => {
+                    row.columns.push(h.column);
+                    row.strain = row.strain.max(strain);
+                }
+                _ => rows.push(Row {
+                    start_time,
+                    columns: vec![h.column],
+                    strain,
+                }),
+            }
+        }
+
+        let n_objects = mania_objects.len();
+        let n_hold_notes = mania_objects
+            .iter()
+            .filter(|h| h.end_time > h.start_time)
+            .count();
+
+        let mut chord_sizes = vec![0; rows.iter().map(|row| row.columns.len()).max().unwrap_or(0)];
+        let mut jacks = vec![0; n_columns];
+
+        for row in rows.iter() {
+            chord_sizes[row.columns.len() - 1] += 1;
+        }
+
+        for (prev, curr) in rows.iter().zip(rows.iter().skip(1)) {
+            if curr.start_time - prev.start_time > Self::JACK_DELTA_TIME {
+                continue;
+            }
+
+            for column in curr.columns.iter() {
+                if prev.columns.contains(column) {
+                    jacks[*column] += 1;
+                }
+            }
+        }
+
+        let left_hand_notes = mania_objects
+            .iter()
+            .filter(|h| h.column < n_columns / 2)
+            .count();
+
+        let right_hand_notes = mania_objects
+            .iter()
+            .filter(|h| h.column >= n_columns.div_ceil(2))
+            .count();
+
+        let labels = classify(&rows);
+
+        let duration = match (rows.first(), rows.last()) {
+            (Some(first), Some(last)) => (last.start_time - first.start_time) / 1000.0,
+            _ => 0.0,
+        };
+
+        let total_strain: f64 = rows.iter().map(|row| row.strain).sum();
+        let mut patterns = [ManiaPatternStats::default(); ManiaPattern::ALL.len()];
+
+        for (row, label) in rows.iter().zip(labels) {
+            if let Some(pattern) = label {
+                let stats = &mut patterns[pattern as usize];
+                stats.rows += 1;
+                stats.strain_share += row.strain;
+            }
+        }
+
+        for stats in patterns.iter_mut() {
+            if duration > 0.0 {
+                stats.density = stats.rows as f64 / duration;
+            }
+
+            if total_strain > 0.0 {
+                stats.strain_share /= total_strain;
+            }
+        }
+
+        Ok(Self {
+            n_columns,
+            n_objects,
+            n_hold_notes,
+            long_note_ratio: if n_objects > 0 {
+                n_hold_notes as f64 / n_objects as f64
+            } else {
+                0.0
+            },
+            chord_sizes,
+            jacks,
+            left_hand_notes,
+            right_hand_notes,
+            patterns,
+        })
+    }
+
+    /// The statistics of the given pattern.
+    pub const fn pattern(&self, pattern: ManiaPattern) -> ManiaPatternStats {
+        self.patterns[pattern as usize]
+    }
+
+    /// The share of notes played by the left hand, i.e. `0.5` for a perfectly
+    /// balanced map.
+    pub fn hand_balance(&self) -> f64 {
+        let total = self.left_hand_notes + self.right_hand_notes;
+
+        if total == 0 {
+            0.5
+        } else {
+            self.left_hand_notes as f64 / total as f64
+        }
+    }
+}
+
+/// Classify each row.
+fn classify(rows: &[Row]) -> Vec<Option<ManiaPattern>> {
+    let delta_time = |i: usize| rows[i].start_time - rows[i - 1].start_time;
+
+    let is_jack = |i: usize| {
+        i > 0
+            && delta_time(i) <= ManiaPatterns::JACK_DELTA_TIME
+            && rows[i].shares_column(&rows[i - 1])
+    };
+
+    let is_stream = |i: usize| {
+        !is_jack(i)
+            && ((i > 0 && delta_time(i) <= ManiaPatterns::STREAM_DELTA_TIME)
+                || (i + 1 < rows.len() && delta_time(i + 1) <= ManiaPatterns::STREAM_DELTA_TIME))
+    };
+
+    let streams: Vec<_> = (0..rows.len()).map(is_stream).collect();
+
+    (0..rows.len())
+        .map(|i| {
+            if is_jack(i) {
+                let is_chord = rows[i].columns.len() > 1 && rows[i - 1].columns.len() > 1;
+
+                return Some(if is_chord {
+                    ManiaPattern::Chordjack
+                } else {
+                    ManiaPattern::Jack
+                });
+            }
+
+            if !streams[i] {
+                return None;
+            }
+
+            // Consider surrounding rows of the same stream
+            let start = (i.saturating_sub(ManiaPatterns::STREAM_WINDOW)..i)
+                .rev()
+                .take_while(|j| streams[*j])
+                .last()
+                .unwrap_or(i);
+
+            let end = (i + 1..rows.len().min(i + 1 + ManiaPatterns::STREAM_WINDOW))
+                .take_while(|j| streams[*j])
+                .last()
+                .unwrap_or(i);
+
+            let max_size = rows[start..=end]
+                .iter()
+                .map(|row| row.columns.len())
+                .max()
+                .unwrap_or(1);
+
+            Some(match max_size {
+                1 => ManiaPattern::Stream,
+                2 => ManiaPattern::Jumpstream,
+                _ => ManiaPattern::Handstream,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rosu_map::{section::general::GameMode, util::Pos};
+
+    use crate::model::beatmap::BeatmapBuilder;
+
+    use super::*;
+
+    /// Center x-coordinate of a column in 4K.
+    fn x(column: usize) -> f32 {
+        64.0 + 128.0 * column as f32
+    }
+
+    fn builder() -> BeatmapBuilder {
+        BeatmapBuilder::new()
+            .mode(GameMode::Mania)
+            .cs(4.0)
+            .timing_point(0.0, 400.0)
+    }
+
+    #[test]
+    fn jumpstream_and_jacks() {
+        let mut builder = builder();
+
+        // Jumpstream: alternating single notes and jumps
+        for i in 0..16 {
+            let time = 1000.0 + 100.0 * f64::from(i);
+
+            builder = if i % 2 == 0 {
+                builder
+                    .circle(Pos::new(x(0), 0.0), time)
+                    .circle(Pos::new(x(2), 0.0), time)
+            } else {
+                builder.circle(Pos::new(x(1 + 2 * (i % 4 / 3) as usize), 0.0), time)
+            };
+        }
+
+        // Jacks in the last column
+        for i in 0..8 {
+            builder = builder.circle(Pos::new(x(3), 0.0), 5000.0 + 150.0 * f64::from(i));
+        }
+
+        let map = builder.hold(x(1), 7000.0, 500.0).build().unwrap();
+        let patterns = ManiaPatterns::new(&Difficulty::new(), &map).unwrap();
+
+        assert_eq!(patterns.n_columns, 4);
+        assert_eq!(patterns.n_objects, 33);
+        assert_eq!(patterns.n_hold_notes, 1);
+        assert_eq!(patterns.chord_sizes, [17, 8]);
+        assert_eq!(patterns.jacks, [0, 0, 0, 7]);
+        assert_eq!(patterns.pattern(ManiaPattern::Jumpstream).rows, 16);
+        assert_eq!(patterns.pattern(ManiaPattern::Jack).rows, 7);
+        assert_eq!(patterns.pattern(ManiaPattern::Handstream).rows, 0);
+        assert!(patterns.hand_balance() < 0.5);
+
+        let total: f64 = ManiaPattern::ALL
+            .into_iter()
+            .map(|pattern| patterns.pattern(pattern).strain_share)
+            .sum();
+
+        assert!(total > 0.0 && total <= 1.0 + f64::EPSILON);
+    }
+
+    #[test]
+    fn clock_rate() {
+        let map = Beatmap::from_path("./resources/1638954.osu").unwrap();
+
+        let nomod = ManiaPatterns::new(&Difficulty::new(), &map).unwrap();
+        let ht = ManiaPatterns::new(&Difficulty::new().mods(256), &map).unwrap();
+
+        assert_eq!(nomod.n_objects, map.hit_objects.len());
+        assert_eq!(nomod.chord_sizes, ht.chord_sizes);
+        assert_eq!(
+            nomod
+                .chord_sizes
+                .iter()
+                .enumerate()
+                .map(|(i, n)| (i + 1) * n)
+                .sum::<usize>(),
+            nomod.n_objects
+        );
+
+        let classified_rows = |patterns: &ManiaPatterns| {
+            ManiaPattern::ALL
+                .into_iter()
+                .map(|pattern| patterns.pattern(pattern).rows)
+                .sum::<usize>()
+        };
+
+        assert!(classified_rows(&ht) < classified_rows(&nomod));
+    }
+}
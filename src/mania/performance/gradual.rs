@@ -1,4 +1,6 @@
-use crate::{mania::ManiaGradualDifficulty, model::mode::ConvertError, Beatmap, Difficulty};
+use crate::{
+    any::ResumeError, mania::ManiaGradualDifficulty, model::mode::ConvertError, Beatmap, Difficulty,
+};
 
 use super::{ManiaPerformanceAttributes, ManiaScoreState};
 
@@ -108,6 +110,72 @@ impl ManiaGradualPerformance {
         Some(performance)
     }
 
+    /// Process all objects that are judged at or before `time`, i.e. the
+    /// current playback position in milliseconds, and calculate the
+    /// performance attributes for the resulting score state.
+    ///
+    /// See [`ManiaGradualDifficulty::advance_to_time`].
+    #[allow(clippy::missing_panics_doc)]
+    pub fn advance_to_time(
+        &mut self,
+        state: ManiaScoreState,
+        time: f64,
+    ) -> Option<ManiaPerformanceAttributes> {
+        let performance = self
+            .difficulty
+            .advance_to_time(time)?
+            .performance()
+            .state(state)
+            .difficulty(self.difficulty.difficulty.clone())
+            .passed_objects(self.difficulty.idx as u32)
+            .calculate()
+            .expect("no conversion required");
+
+        Some(performance)
+    }
+
+    /// Take a snapshot of the calculator's state every `interval` processed
+    /// objects so that [`ManiaGradualPerformance::seek`] can rewind without
+    /// starting over.
+    ///
+    /// See [`ManiaGradualDifficulty::checkpoint_interval`].
+    #[must_use]
+    pub fn checkpoint_interval(mut self, interval: usize) -> Self {
+        self.difficulty = self.difficulty.checkpoint_interval(interval);
+
+        self
+    }
+
+    /// Move to the state after processing `idx` objects so that the next call
+    /// of [`ManiaGradualPerformance::next`] processes the object at index `idx`.
+    ///
+    /// See [`ManiaGradualDifficulty::seek`].
+    pub fn seek(&mut self, idx: usize) {
+        self.difficulty.seek(idx);
+    }
+
+    /// Serialize the current state so that it can be resumed through
+    /// [`ManiaGradualPerformance::resume`], e.g. in another process.
+    ///
+    /// See [`ManiaGradualDifficulty::save_state`].
+    pub fn save_state(&self) -> Vec<u8> {
+        self.difficulty.save_state()
+    }
+
+    /// Resume a state that was serialized through
+    /// [`ManiaGradualPerformance::save_state`].
+    ///
+    /// See [`ManiaGradualDifficulty::resume`].
+    pub fn resume(
+        difficulty: Difficulty,
+        map: &Beatmap,
+        state: &[u8],
+    ) -> Result<Self, ResumeError> {
+        let difficulty = ManiaGradualDifficulty::resume(difficulty, map, state)?;
+
+        Ok(Self { difficulty })
+    }
+
     /// Returns the amount of remaining objects.
     #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
@@ -804,7 +804,7 @@ mod tests {
         prelude::*
     };
     use rosu_map::section::general::GameMode;
-    use rosu_mods::GameMod;
+    use rosu_mods::{generated_mods::ClassicMania, GameMod};
 
     use crate::{
         any::{DifficultyAttributes, PerformanceAttributes},
@@ -846,7 +846,7 @@ mod tests {
     fn mods(classic: bool) -> rosu_mods::GameMods {
         if classic {
             let mut mods = rosu_mods::GameMods::new();
-            mods.insert(GameMod::ClassicMania(Default::default()));
+            mods.insert(GameMod::ClassicMania(ClassicMania::default()));
 
             mods
         } else {
@@ -859,7 +859,7 @@ mod tests {
     ///
     /// Very slow but accurate. Only slight optimizations have been applied so
     /// that it doesn't run unreasonably long.
-    #[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+    #[allow(clippy::too_many_arguments, clippy::too_many_lines, clippy::float_cmp)]
     fn brute_force_best(
         classic: bool,
         acc: f64,
@@ -1213,6 +1213,7 @@ mod tests {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn exec_mania_hitresults(
         classic: bool,
         acc: f64,
@@ -22,7 +22,11 @@ impl ManiaStrains {
 }
 
 pub fn strains(difficulty: &Difficulty, map: &Beatmap) -> Result<ManiaStrains, ConvertError> {
-    let map = map.convert_ref(GameMode::Mania, difficulty.get_mods())?;
+    let map = map.convert_ref_cancellable(
+        GameMode::Mania,
+        difficulty.get_mods(),
+        difficulty.get_cancellation(),
+    )?;
     let values = DifficultyValues::calculate(difficulty, &map);
 
     Ok(ManiaStrains {
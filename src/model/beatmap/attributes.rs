@@ -52,6 +52,7 @@ pub struct BeatmapAttributesBuilder {
     clock_rate: Option<f64>,
 }
 
+#[derive(Copy, Clone)]
 struct GameModeHitWindows {
     min: f64,
     avg: f64,
@@ -380,6 +381,29 @@ impl BeatmapAttributesBuilder {
     pub(crate) const fn osu_great_hit_window_to_od(hit_window: f64) -> f64 {
         (79.5 - hit_window) / 6.0
     }
+
+    /// The approach rate and overall difficulty that produce the same hit
+    /// windows at a clock rate of 1.0 as the given values at `clock_rate`.
+    pub(crate) fn rate_adjusted_ar_od(
+        mode: GameMode,
+        ar: f32,
+        od: f32,
+        clock_rate: f64,
+    ) -> (f32, f32) {
+        let adjust = |value: f32, windows: GameModeHitWindows| {
+            let hit_window = difficulty_range(f64::from(value), windows) / clock_rate;
+
+            inverse_difficulty_range(hit_window, windows) as f32
+        };
+
+        match mode {
+            GameMode::Osu => (adjust(ar, AR_WINDOWS), adjust(od, OSU_GREAT)),
+            GameMode::Taiko => (adjust(ar, AR_WINDOWS), adjust(od, TAIKO_GREAT)),
+            // osu!catch does not use the OD hit window for its attributes
+            GameMode::Catch => (adjust(ar, AR_WINDOWS), od),
+            GameMode::Mania => (ar, od),
+        }
+    }
 }
 
 impl From<&Beatmap> for BeatmapAttributesBuilder {
@@ -402,6 +426,17 @@ fn difficulty_range(difficulty: f64, windows: GameModeHitWindows) -> f64 {
     }
 }
 
+#[allow(clippy::needless_pass_by_value)]
+fn inverse_difficulty_range(hit_window: f64, windows: GameModeHitWindows) -> f64 {
+    let GameModeHitWindows { min, avg: mid, max } = windows;
+
+    if (hit_window - mid) * (max - mid) > 0.0 {
+        5.0 + 5.0 * (hit_window - mid) / (max - mid)
+    } else {
+        5.0 - 5.0 * (mid - hit_window) / (mid - min)
+    }
+}
+
 impl Default for BeatmapAttributesBuilder {
     fn default() -> Self {
         Self::new()
@@ -1,14 +1,99 @@
 use std::collections::HashMap;
 
-use crate::model::{control_point::TimingPoint, hit_object::HitObject};
+use crate::model::control_point::TimingPoint;
 
-pub fn bpm(last_hit_object: Option<&HitObject>, timing_points: &[TimingPoint]) -> f64 {
-    // This is incorrect if the last object is a slider since there
-    // is no reasonable way to get the slider end time at this point.
-    let last_time = last_hit_object
-        .map(HitObject::end_time)
-        .or_else(|| timing_points.last().map(|t| t.time))
-        .unwrap_or(0.0);
+/// BPM statistics of a [`Beatmap`].
+///
+/// All values are adjusted to the clock rate of [`BeatmapStats`].
+///
+/// [`Beatmap`]: super::Beatmap
+/// [`BeatmapStats`]: super::BeatmapStats
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BeatmapBpm {
+    /// The lowest BPM of all sections.
+    pub min: f64,
+    /// The highest BPM of all sections.
+    pub max: f64,
+    /// The BPM that is active for the longest time.
+    ///
+    /// This is the same value as [`Beatmap::bpm`] with the clock rate applied.
+    ///
+    /// [`Beatmap::bpm`]: super::Beatmap::bpm
+    pub most_common: f64,
+    /// Sections of constant BPM in chronological order.
+    ///
+    /// Only timing points until the end of the last hit object are
+    /// considered.
+    pub timeline: Vec<BpmSection>,
+}
+
+/// A section of a [`Beatmap`] with constant BPM.
+///
+/// [`Beatmap`]: super::Beatmap
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct BpmSection {
+    /// The start time of the section in milliseconds.
+    pub start_time: f64,
+    /// The end time of the section in milliseconds.
+    pub end_time: f64,
+    /// The BPM during the section.
+    pub bpm: f64,
+}
+
+pub fn bpm_stats(
+    last_object_time: Option<f64>,
+    timing_points: &[TimingPoint],
+    clock_rate: f64,
+) -> BeatmapBpm {
+    let last_time = last_time(last_object_time, timing_points);
+
+    let timeline: Vec<_> = timing_points
+        .iter()
+        .enumerate()
+        // * osu-stable forced the first control point to start at 0.
+        .map(|(i, curr)| {
+            if i == 0 {
+                (0.0, curr)
+            } else {
+                (curr.time, curr)
+            }
+        })
+        .take_while(|(start_time, _)| *start_time <= last_time)
+        .zip(
+            timing_points
+                .iter()
+                .skip(1)
+                .map(|t| t.time.min(last_time))
+                .chain(Some(last_time)),
+        )
+        .map(|((start_time, curr), end_time)| BpmSection {
+            start_time: start_time / clock_rate,
+            end_time: end_time.max(start_time) / clock_rate,
+            bpm: 60_000.0 / curr.beat_len * clock_rate,
+        })
+        .collect();
+
+    let most_common = bpm(last_object_time, timing_points) * clock_rate;
+
+    let (min, max) = timeline
+        .iter()
+        .map(|section| section.bpm)
+        .fold(None, |range, bpm| match range {
+            Some((min, max)) => Some((f64::min(min, bpm), f64::max(max, bpm))),
+            None => Some((bpm, bpm)),
+        })
+        .unwrap_or((most_common, most_common));
+
+    BeatmapBpm {
+        min,
+        max,
+        most_common,
+        timeline,
+    }
+}
+
+pub fn bpm(last_object_time: Option<f64>, timing_points: &[TimingPoint]) -> f64 {
+    let last_time = last_time(last_object_time, timing_points);
 
     let mut bpm_points = BeatLenDuration::new(last_time);
 
@@ -41,6 +126,12 @@ pub fn bpm(last_hit_object: Option<&HitObject>, timing_points: &[TimingPoint]) -
     60_000.0 / most_common_beat_len
 }
 
+fn last_time(last_object_time: Option<f64>, timing_points: &[TimingPoint]) -> f64 {
+    last_object_time
+        .or_else(|| timing_points.last().map(|t| t.time))
+        .unwrap_or(0.0)
+}
+
 /// Maps `beat_len` to a cumulative duration
 struct BeatLenDuration {
     last_time: f64,
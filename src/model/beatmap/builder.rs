@@ -0,0 +1,577 @@
+use std::{error, fmt};
+
+use rosu_map::section::general::GameMode;
+
+use crate::model::{
+    control_point::{DifficultyPoint, TimingPoint},
+    hit_object::{
+        HitObject, HitObjectKind, HitSoundType, HoldNote, PathControlPoint, Pos, Slider, Spinner,
+    },
+};
+
+use super::{Beatmap, BreakPeriod};
+
+/// A builder for synthetic [`Beatmap`]s.
+///
+/// Instead of decoding a `.osu` file, hit objects and control points can be
+/// added one by one. All times are in milliseconds and must be added in
+/// chronological order; [`BeatmapBuilder::build`] validates this.
+///
+/// Note that, unlike in `.osu` files, timing points do not reset the slider
+/// velocity. Use [`BeatmapBuilder::slider_velocity`] to add such changes.
+///
+/// # Example
+///
+/// ```
+/// use refx_pp::{Beatmap, Difficulty};
+/// use refx_pp::model::beatmap::BeatmapBuilder;
+/// use refx_pp::model::hit_object::{PathControlPoint, PathType, Pos};
+///
+/// let mut start = PathControlPoint::new(Pos::new(0.0, 0.0));
+/// start.path_type = Some(PathType::LINEAR);
+/// let end = PathControlPoint::new(Pos::new(100.0, 0.0));
+///
+/// let map: Beatmap = BeatmapBuilder::new()
+///     .od(8.0)
+///     .timing_point(0.0, 500.0)
+///     .circle(Pos::new(100.0, 100.0), 1000.0)
+///     .slider(Pos::new(200.0, 100.0), 1500.0, vec![start, end], 1, None)
+///     .slider_velocity(2000.0, 1.5)
+///     .spinner(3000.0, 1000.0)
+///     .build()
+///     .unwrap();
+///
+/// let attrs = Difficulty::new().calculate(&map);
+/// assert!(attrs.stars() > 0.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[must_use]
+pub struct BeatmapBuilder {
+    map: Beatmap,
+}
+
+impl BeatmapBuilder {
+    /// Create a new [`BeatmapBuilder`].
+    ///
+    /// The mode will be `GameMode::Osu` and all other values are the same as
+    /// for [`Beatmap::default`].
+    pub fn new() -> Self {
+        Self {
+            map: Beatmap::default(),
+        }
+    }
+
+    /// Specify the mode.
+    ///
+    /// Hit objects are used as is, i.e. the map will not be a convert.
+    pub const fn mode(mut self, mode: GameMode) -> Self {
+        self.map.mode = mode;
+
+        self
+    }
+
+    /// Specify the approach rate.
+    pub const fn ar(mut self, ar: f32) -> Self {
+        self.map.ar = ar;
+
+        self
+    }
+
+    /// Specify the circle size.
+    ///
+    /// For osu!mania this is the amount of keys.
+    pub const fn cs(mut self, cs: f32) -> Self {
+        self.map.cs = cs;
+
+        self
+    }
+
+    /// Specify the drain rate.
+    pub const fn hp(mut self, hp: f32) -> Self {
+        self.map.hp = hp;
+
+        self
+    }
+
+    /// Specify the overall difficulty.
+    pub const fn od(mut self, od: f32) -> Self {
+        self.map.od = od;
+
+        self
+    }
+
+    /// Specify the base slider velocity multiplier.
+    pub const fn slider_multiplier(mut self, slider_multiplier: f64) -> Self {
+        self.map.slider_multiplier = slider_multiplier;
+
+        self
+    }
+
+    /// Specify the slider tick rate.
+    pub const fn slider_tick_rate(mut self, slider_tick_rate: f64) -> Self {
+        self.map.slider_tick_rate = slider_tick_rate;
+
+        self
+    }
+
+    /// Specify the stack leniency.
+    pub const fn stack_leniency(mut self, stack_leniency: f32) -> Self {
+        self.map.stack_leniency = stack_leniency;
+
+        self
+    }
+
+    /// Add a timing point i.e. a BPM change.
+    ///
+    /// `beat_len` is the duration of a beat in milliseconds.
+    pub fn timing_point(mut self, time: f64, beat_len: f64) -> Self {
+        self.map
+            .timing_points
+            .push(TimingPoint::new(time, beat_len));
+
+        self
+    }
+
+    /// Add a slider velocity change.
+    ///
+    /// `slider_velocity` is the multiplier as displayed in the editor, e.g.
+    /// `1.5` for 1.5x.
+    pub fn slider_velocity(mut self, time: f64, slider_velocity: f64) -> Self {
+        let point = DifficultyPoint::new(time, -100.0 / slider_velocity, slider_velocity);
+        self.map.difficulty_points.push(point);
+
+        self
+    }
+
+    /// Add a break.
+    pub fn break_period(mut self, start_time: f64, end_time: f64) -> Self {
+        self.map.breaks.push(BreakPeriod {
+            start_time,
+            end_time,
+        });
+
+        self
+    }
+
+    /// Add a circle.
+    ///
+    /// For osu!taiko, circles are hits and for osu!mania they're notes.
+    pub fn circle(self, pos: Pos, start_time: f64) -> Self {
+        self.hit_object(
+            HitObject {
+                pos,
+                start_time,
+                kind: HitObjectKind::Circle,
+            },
+            HitSoundType::default(),
+        )
+    }
+
+    /// Add a slider.
+    ///
+    /// The positions of `control_points` are relative to `pos` so the first
+    /// one should generally be at `(0, 0)` and carry a path type.
+    /// `expected_dist` is the pixel length of the slider; if `None`, the
+    /// length of the path will be used.
+    pub fn slider(
+        self,
+        pos: Pos,
+        start_time: f64,
+        control_points: impl Into<Box<[PathControlPoint]>>,
+        repeats: usize,
+        expected_dist: Option<f64>,
+    ) -> Self {
+        let slider = Slider {
+            expected_dist,
+            repeats,
+            control_points: control_points.into(),
+            node_sounds: vec![HitSoundType::default(); repeats + 2].into_boxed_slice(),
+        };
+
+        self.hit_object(
+            HitObject {
+                pos,
+                start_time,
+                kind: HitObjectKind::Slider(slider),
+            },
+            HitSoundType::default(),
+        )
+    }
+
+    /// Add a spinner.
+    ///
+    /// For osu!taiko, spinners are swells.
+    pub fn spinner(self, start_time: f64, duration: f64) -> Self {
+        self.hit_object(
+            HitObject {
+                pos: Pos::new(256.0, 192.0),
+                start_time,
+                kind: HitObjectKind::Spinner(Spinner { duration }),
+            },
+            HitSoundType::default(),
+        )
+    }
+
+    /// Add an osu!mania hold note.
+    ///
+    /// The column is determined by `x` in the same way as for `.osu` files.
+    pub fn hold(self, x: f32, start_time: f64, duration: f64) -> Self {
+        self.hit_object(
+            HitObject {
+                pos: Pos::new(x, 192.0),
+                start_time,
+                kind: HitObjectKind::Hold(HoldNote { duration }),
+            },
+            HitSoundType::default(),
+        )
+    }
+
+    /// Add an arbitrary [`HitObject`] with the given [`HitSoundType`].
+    ///
+    /// The hit sound is relevant for osu!taiko where clap and whistle
+    /// indicate a rim hit and finish indicates a strong hit.
+    pub fn hit_object(mut self, h: HitObject, sound: HitSoundType) -> Self {
+        self.map.hit_objects.push(h);
+        self.map.hit_sounds.push(sound);
+
+        self
+    }
+
+    /// Validate all values and build the [`Beatmap`].
+    ///
+    /// Difficulty attributes are clamped to the same ranges as they are when
+    /// decoding a `.osu` file.
+    pub fn build(self) -> Result<Beatmap, BuildBeatmapError> {
+        let Self { mut map } = self;
+
+        Self::validate_timing(&map)?;
+        Self::validate_hit_objects(&map)?;
+
+        map.hp = map.hp.clamp(0.0, 10.0);
+
+        map.cs = if map.mode == GameMode::Mania {
+            map.cs.clamp(1.0, 18.0)
+        } else {
+            map.cs.clamp(0.0, 10.0)
+        };
+
+        map.od = map.od.clamp(0.0, 10.0);
+        map.ar = map.ar.clamp(0.0, 10.0);
+        map.slider_multiplier = map.slider_multiplier.clamp(0.4, 3.6);
+        map.slider_tick_rate = map.slider_tick_rate.clamp(0.5, 8.0);
+
+        Ok(map)
+    }
+
+    fn validate_timing(map: &Beatmap) -> Result<(), BuildBeatmapError> {
+        for (i, point) in map.timing_points.iter().enumerate() {
+            if !point.time.is_finite() || !point.beat_len.is_finite() {
+                return Err(BuildBeatmapError::NonFinite);
+            }
+
+            if i > 0 && point.time <= map.timing_points[i - 1].time {
+                return Err(BuildBeatmapError::UnsortedTimingPoints { idx: i });
+            }
+        }
+
+        for (i, point) in map.difficulty_points.iter().enumerate() {
+            if !point.time.is_finite() || !point.slider_velocity.is_finite() {
+                return Err(BuildBeatmapError::NonFinite);
+            }
+
+            if i > 0 && point.time <= map.difficulty_points[i - 1].time {
+                return Err(BuildBeatmapError::UnsortedDifficultyPoints { idx: i });
+            }
+        }
+
+        for (i, period) in map.breaks.iter().enumerate() {
+            if !period.start_time.is_finite() || !period.end_time.is_finite() {
+                return Err(BuildBeatmapError::NonFinite);
+            }
+
+            if period.end_time < period.start_time
+                || (i > 0 && period.start_time < map.breaks[i - 1].end_time)
+            {
+                return Err(BuildBeatmapError::InvalidBreak { idx: i });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_hit_objects(map: &Beatmap) -> Result<(), BuildBeatmapError> {
+        for (i, h) in map.hit_objects.iter().enumerate() {
+            if !h.start_time.is_finite() || !h.pos.x.is_finite() || !h.pos.y.is_finite() {
+                return Err(BuildBeatmapError::NonFinite);
+            }
+
+            if i > 0 && h.start_time < map.hit_objects[i - 1].start_time {
+                return Err(BuildBeatmapError::UnsortedHitObjects { idx: i });
+            }
+
+            match h.kind {
+                HitObjectKind::Circle => {}
+                HitObjectKind::Slider(ref slider) => {
+                    if slider.control_points.is_empty() {
+                        return Err(BuildBeatmapError::EmptySliderPath { idx: i });
+                    }
+
+                    if slider.expected_dist.is_some_and(|dist| !dist.is_finite()) {
+                        return Err(BuildBeatmapError::NonFinite);
+                    }
+                }
+                HitObjectKind::Spinner(Spinner { duration }) => {
+                    Self::validate_duration(duration, i)?;
+                }
+                HitObjectKind::Hold(HoldNote { duration }) => {
+                    if map.mode != GameMode::Mania {
+                        return Err(BuildBeatmapError::HoldNoteOutsideMania { idx: i });
+                    }
+
+                    Self::validate_duration(duration, i)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_duration(duration: f64, idx: usize) -> Result<(), BuildBeatmapError> {
+        if !duration.is_finite() {
+            Err(BuildBeatmapError::NonFinite)
+        } else if duration < 0.0 {
+            Err(BuildBeatmapError::NegativeDuration { idx })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Default for BeatmapBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Error type of [`BeatmapBuilder::build`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuildBeatmapError {
+    /// A time, duration, position, or value was NaN or infinite.
+    NonFinite,
+    /// The hit object at the given index starts before its predecessor.
+    UnsortedHitObjects { idx: usize },
+    /// The timing point at the given index is not after its predecessor.
+    UnsortedTimingPoints { idx: usize },
+    /// The slider velocity change at the given index is not after its
+    /// predecessor.
+    UnsortedDifficultyPoints { idx: usize },
+    /// The break at the given index ends before it starts or overlaps with
+    /// its predecessor.
+    InvalidBreak { idx: usize },
+    /// The spinner or hold note at the given index has a negative duration.
+    NegativeDuration { idx: usize },
+    /// The slider at the given index has no control points.
+    EmptySliderPath { idx: usize },
+    /// The hit object at the given index is a hold note but the mode is not
+    /// osu!mania.
+    HoldNoteOutsideMania { idx: usize },
+}
+
+impl error::Error for BuildBeatmapError {}
+
+impl fmt::Display for BuildBeatmapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonFinite => f.write_str("values must be finite"),
+            Self::UnsortedHitObjects { idx } => {
+                write!(f, "hit object {idx} starts before its predecessor")
+            }
+            Self::UnsortedTimingPoints { idx } => {
+                write!(f, "timing point {idx} is not after its predecessor")
+            }
+            Self::UnsortedDifficultyPoints { idx } => {
+                write!(
+                    f,
+                    "slider velocity change {idx} is not after its predecessor"
+                )
+            }
+            Self::InvalidBreak { idx } => write!(f, "break {idx} is invalid"),
+            Self::NegativeDuration { idx } => {
+                write!(f, "hit object {idx} has a negative duration")
+            }
+            Self::EmptySliderPath { idx } => write!(f, "slider {idx} has no control points"),
+            Self::HoldNoteOutsideMania { idx } => {
+                write!(f, "hit object {idx} is a hold note outside of osu!mania")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mania::Mania, model::hit_object::PathType, osu::Osu, taiko::Taiko, Difficulty};
+
+    use super::*;
+
+    const OSU_FILE: &str = "osu file format v14
+
+[General]
+Mode: 0
+
+[Difficulty]
+HPDrainRate:5
+CircleSize:4
+OverallDifficulty:8
+ApproachRate:9
+SliderMultiplier:1.4
+SliderTickRate:1
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+2000,-50,4,2,0,100,0,0
+
+[HitObjects]
+100,100,1000,1,0,0:0:0:0:
+200,100,1250,1,0,0:0:0:0:
+300,200,1500,2,0,L|400:200,2,100
+256,192,3000,12,0,4000,0:0:0:0:
+";
+
+    fn linear(end: Pos) -> Vec<PathControlPoint> {
+        let start = PathControlPoint {
+            pos: Pos::default(),
+            path_type: Some(PathType::LINEAR),
+        };
+
+        vec![start, PathControlPoint::new(end)]
+    }
+
+    #[test]
+    fn matches_decoded() {
+        let decoded: Beatmap = OSU_FILE.parse().unwrap();
+
+        let built = BeatmapBuilder::new()
+            .hp(5.0)
+            .cs(4.0)
+            .od(8.0)
+            .ar(9.0)
+            .timing_point(0.0, 500.0)
+            .slider_velocity(2000.0, 2.0)
+            .circle(Pos::new(100.0, 100.0), 1000.0)
+            .circle(Pos::new(200.0, 100.0), 1250.0)
+            .slider(
+                Pos::new(300.0, 200.0),
+                1500.0,
+                linear(Pos::new(100.0, 0.0)),
+                1,
+                Some(100.0),
+            )
+            .spinner(3000.0, 1000.0)
+            .build()
+            .unwrap();
+
+        assert_eq!(built.hit_objects, decoded.hit_objects);
+        assert_eq!(built.timing_points, decoded.timing_points);
+        assert_eq!(built.difficulty_points, decoded.difficulty_points);
+
+        let difficulty = Difficulty::new().mods(64);
+
+        assert_eq!(
+            difficulty.calculate_for_mode::<Osu>(&built).unwrap(),
+            difficulty.calculate_for_mode::<Osu>(&decoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn taiko_and_mania() {
+        let taiko = BeatmapBuilder::new()
+            .mode(GameMode::Taiko)
+            .timing_point(0.0, 300.0)
+            .circle(Pos::default(), 0.0)
+            .hit_object(
+                HitObject {
+                    pos: Pos::default(),
+                    start_time: 150.0,
+                    kind: HitObjectKind::Circle,
+                },
+                HitSoundType::from(HitSoundType::CLAP),
+            )
+            .circle(Pos::default(), 300.0)
+            .circle(Pos::default(), 450.0)
+            .build()
+            .unwrap();
+
+        let attrs = Difficulty::new()
+            .calculate_for_mode::<Taiko>(&taiko)
+            .unwrap();
+
+        assert_eq!(attrs.max_combo, 4);
+
+        let mania = BeatmapBuilder::new()
+            .mode(GameMode::Mania)
+            .cs(4.0)
+            .timing_point(0.0, 300.0)
+            .circle(Pos::new(64.0, 192.0), 0.0)
+            .hold(192.0, 100.0, 500.0)
+            .circle(Pos::new(320.0, 192.0), 200.0)
+            .circle(Pos::new(448.0, 192.0), 300.0)
+            .build()
+            .unwrap();
+
+        let attrs = Difficulty::new()
+            .calculate_for_mode::<Mania>(&mania)
+            .unwrap();
+
+        assert_eq!(attrs.n_objects, 4);
+        assert_eq!(attrs.n_hold_notes, 1);
+        assert!(attrs.stars > 0.0);
+    }
+
+    #[test]
+    fn invalid() {
+        let err = BeatmapBuilder::new()
+            .circle(Pos::default(), 100.0)
+            .circle(Pos::default(), 50.0)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(err, BuildBeatmapError::UnsortedHitObjects { idx: 1 });
+
+        let err = BeatmapBuilder::new()
+            .timing_point(100.0, 500.0)
+            .timing_point(100.0, 400.0)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(err, BuildBeatmapError::UnsortedTimingPoints { idx: 1 });
+
+        let err = BeatmapBuilder::new()
+            .slider(Pos::default(), 0.0, Vec::new(), 0, None)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(err, BuildBeatmapError::EmptySliderPath { idx: 0 });
+
+        let err = BeatmapBuilder::new()
+            .hold(0.0, 0.0, 100.0)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(err, BuildBeatmapError::HoldNoteOutsideMania { idx: 0 });
+
+        let err = BeatmapBuilder::new()
+            .spinner(0.0, -1.0)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(err, BuildBeatmapError::NegativeDuration { idx: 0 });
+
+        let err = BeatmapBuilder::new()
+            .circle(Pos::default(), f64::NAN)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(err, BuildBeatmapError::NonFinite);
+    }
+}
@@ -1,21 +1,30 @@
 use std::{borrow::Cow, io, path::Path, str::FromStr};
 
 use rosu_map::{
-    section::{general::GameMode, hit_objects::hit_samples::HitSoundType},
+    section::{
+        general::GameMode,
+        hit_objects::{hit_samples::HitSoundType, CurveBuffers},
+    },
     LATEST_FORMAT_VERSION,
 };
 
 pub use rosu_map::section::events::BreakPeriod;
 
 use crate::{
-    catch::Catch, mania::Mania, taiko::Taiko, Difficulty, GameMods, GradualDifficulty,
-    GradualPerformance, Performance,
+    any::difficulty::cancellation::Cancellation, catch::Catch, mania::Mania, taiko::Taiko,
+    Difficulty, GameMods, GradualDifficulty, GradualPerformance, Performance,
 };
 
 pub use self::{
     attributes::{BeatmapAttributes, BeatmapAttributesBuilder, HitWindows},
+    bpm::{BeatmapBpm, BpmSection},
+    builder::{BeatmapBuilder, BuildBeatmapError},
     decode::{BeatmapState, ParseBeatmapError},
-    suspicious::TooSuspicious,
+    stats::{BeatmapStats, BeatmapStatsBuilder},
+    suspicion_report::{
+        DenseSection, DensityFinding, SliderFinding, SuspicionFinding, SuspicionReport,
+    },
+    suspicious::{SuspicionConfig, TooSuspicious},
 };
 
 use super::{
@@ -23,13 +32,16 @@ use super::{
         difficulty_point_at, effect_point_at, timing_point_at, DifficultyPoint, EffectPoint,
         TimingPoint,
     },
-    hit_object::HitObject,
+    hit_object::{HitObject, HitObjectKind, HoldNote, Spinner},
     mode::ConvertError,
 };
 
 mod attributes;
 mod bpm;
+mod builder;
 mod decode;
+mod stats;
+mod suspicion_report;
 mod suspicious;
 
 /// All beatmap data that is relevant for difficulty and performance
@@ -76,6 +88,11 @@ impl Beatmap {
         rosu_map::from_bytes(bytes)
     }
 
+    /// Returns a [`BeatmapBuilder`] to create a synthetic [`Beatmap`].
+    pub fn builder() -> BeatmapBuilder {
+        BeatmapBuilder::new()
+    }
+
     /// Returns a [`BeatmapAttributesBuilder`] to calculate modified beatmap
     /// attributes.
     pub fn attributes(&self) -> BeatmapAttributesBuilder {
@@ -83,8 +100,22 @@ impl Beatmap {
     }
 
     /// The beats per minute of the map.
+    ///
+    /// This is the most common BPM, see [`BeatmapStats::bpm`] for the BPM
+    /// range and timeline.
     pub fn bpm(&self) -> f64 {
-        bpm::bpm(self.hit_objects.last(), &self.timing_points)
+        let last_time = self
+            .hit_objects
+            .last()
+            .map(|h| stats::end_time(self, h, &mut CurveBuffers::default()));
+
+        bpm::bpm(last_time, &self.timing_points)
+    }
+
+    /// Returns a builder for the [`BeatmapStats`] of this [`Beatmap`] such as
+    /// its drain time or notes per second.
+    pub fn stats(&self) -> BeatmapStatsBuilder<'_> {
+        BeatmapStatsBuilder::new(self)
     }
 
     /// Create a performance calculator for this [`Beatmap`].
@@ -122,6 +153,128 @@ impl Beatmap {
         self.breaks.iter().map(BreakPeriod::duration).sum()
     }
 
+    /// Create a rate-changed copy of this [`Beatmap`].
+    ///
+    /// All times, durations, beat lengths, and breaks are divided by `rate`
+    /// and AR and OD are adjusted so that the resulting map played at 1.0x
+    /// is equivalent to this map played at the given clock rate, e.g. `1.5`
+    /// for a DT map.
+    ///
+    /// Note that AR and OD are not clamped so they may exceed 10. Since
+    /// [`BeatmapAttributesBuilder`] clamps AR and OD to `0..=10`, such values
+    /// must be specified through [`Difficulty::ar`] and [`Difficulty::od`],
+    /// e.g. `.ar(map.ar, false)`, otherwise they are clamped again and the
+    /// results no longer correspond to the clock rate.
+    ///
+    /// For osu!taiko and osu!mania, difficulty attributes of the resulting
+    /// map are equal to those of [`Difficulty::clock_rate`] on the original
+    /// up to floating point precision. The other modes are not covered by
+    /// this guarantee:
+    ///   - osu!standard: slider tail leniency and similar constants are not
+    ///     rate-adjusted. The star rating typically stays within 0.5%.
+    ///   - osu!catch: hyperdashes and tiny droplets are generated from
+    ///     unscaled times and the movement skill uses the clock rate itself.
+    ///     Only AR and the amount of fruits and droplets match, the star
+    ///     rating may differ by 10% or more.
+    ///
+    /// ```
+    /// use refx_pp::{Beatmap, Difficulty};
+    ///
+    /// let map = Beatmap::from_path("./resources/1028484.osu").unwrap();
+    /// let rate_map = map.with_rate(1.5);
+    ///
+    /// let expected = Difficulty::new().clock_rate(1.5).calculate(&map);
+    /// let actual = Difficulty::new()
+    ///     .ar(rate_map.ar, false)
+    ///     .od(rate_map.od, false)
+    ///     .calculate(&rate_map);
+    ///
+    /// assert!((actual.stars() - expected.stars()).abs() < 1e-6);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is not positive.
+    #[must_use]
+    pub fn with_rate(&self, rate: f64) -> Self {
+        assert!(rate > 0.0, "rate must be positive");
+
+        let (ar, od) =
+            BeatmapAttributesBuilder::rate_adjusted_ar_od(self.mode, self.ar, self.od, rate);
+
+        let breaks = self
+            .breaks
+            .iter()
+            .map(|b| BreakPeriod {
+                start_time: b.start_time / rate,
+                end_time: b.end_time / rate,
+            })
+            .collect();
+
+        let timing_points = self
+            .timing_points
+            .iter()
+            .map(|point| TimingPoint {
+                time: point.time / rate,
+                beat_len: point.beat_len / rate,
+            })
+            .collect();
+
+        let difficulty_points = self
+            .difficulty_points
+            .iter()
+            .map(|point| DifficultyPoint {
+                time: point.time / rate,
+                ..point.clone()
+            })
+            .collect();
+
+        let effect_points = self
+            .effect_points
+            .iter()
+            .map(|point| EffectPoint {
+                time: point.time / rate,
+                ..*point
+            })
+            .collect();
+
+        let hit_objects = self
+            .hit_objects
+            .iter()
+            .map(|h| {
+                let kind = match h.kind {
+                    HitObjectKind::Spinner(Spinner { duration }) => {
+                        HitObjectKind::Spinner(Spinner {
+                            duration: duration / rate,
+                        })
+                    }
+                    HitObjectKind::Hold(HoldNote { duration }) => HitObjectKind::Hold(HoldNote {
+                        duration: duration / rate,
+                    }),
+                    ref kind => kind.clone(),
+                };
+
+                HitObject {
+                    pos: h.pos,
+                    start_time: h.start_time / rate,
+                    kind,
+                }
+            })
+            .collect();
+
+        Self {
+            ar,
+            od,
+            breaks,
+            timing_points,
+            difficulty_points,
+            effect_points,
+            hit_objects,
+            hit_sounds: self.hit_sounds.clone(),
+            ..*self
+        }
+    }
+
     /// Attempt to convert a [`Beatmap`] to the specified mode.
     pub fn convert(mut self, mode: GameMode, mods: &GameMods) -> Result<Self, ConvertError> {
         self.convert_mut(mode, mods)?;
@@ -136,6 +289,17 @@ impl Beatmap {
         &self,
         mode: GameMode,
         mods: &GameMods,
+    ) -> Result<Cow<'_, Self>, ConvertError> {
+        self.convert_ref_cancellable(mode, mods, &Cancellation::NONE)
+    }
+
+    /// Same as [`Beatmap::convert_ref`] but stops converting early if the
+    /// calculation is cancelled.
+    pub(crate) fn convert_ref_cancellable(
+        &self,
+        mode: GameMode,
+        mods: &GameMods,
+        cancellation: &Cancellation,
     ) -> Result<Cow<'_, Self>, ConvertError> {
         if self.mode == mode {
             return Ok(Cow::Borrowed(self));
@@ -151,9 +315,9 @@ impl Beatmap {
         let mut map = self.to_owned();
 
         match mode {
-            GameMode::Taiko => Taiko::convert(&mut map),
+            GameMode::Taiko => Taiko::convert_cancellable(&mut map, cancellation),
             GameMode::Catch => Catch::convert(&mut map),
-            GameMode::Mania => Mania::convert(&mut map, mods),
+            GameMode::Mania => Mania::convert(&mut map, mods, cancellation),
             GameMode::Osu => unreachable!(),
         }
 
@@ -178,7 +342,7 @@ impl Beatmap {
         match mode {
             GameMode::Taiko => Taiko::convert(self),
             GameMode::Catch => Catch::convert(self),
-            GameMode::Mania => Mania::convert(self, mods),
+            GameMode::Mania => Mania::convert(self, mods, &Cancellation::NONE),
             GameMode::Osu => unreachable!(),
         }
 
@@ -192,11 +356,34 @@ impl Beatmap {
     /// should likely be avoided on these maps due to potential performance
     /// issues.
     pub fn check_suspicion(&self) -> Result<(), TooSuspicious> {
-        match TooSuspicious::new(self) {
+        self.check_suspicion_with(&SuspicionConfig::DEFAULT)
+    }
+
+    /// Check whether hitobjects appear too suspicious for further calculation
+    /// based on custom thresholds.
+    ///
+    /// See [`Beatmap::check_suspicion`].
+    pub fn check_suspicion_with(&self, config: &SuspicionConfig) -> Result<(), TooSuspicious> {
+        match TooSuspicious::new(self, config) {
             None => Ok(()),
             Some(err) => Err(err),
         }
     }
+
+    /// Run all suspicion checks and collect every finding.
+    ///
+    /// See [`Beatmap::check_suspicion`].
+    pub fn suspicion_report(&self) -> SuspicionReport {
+        self.suspicion_report_with(&SuspicionConfig::DEFAULT)
+    }
+
+    /// Run all suspicion checks based on custom thresholds and collect every
+    /// finding.
+    ///
+    /// See [`Beatmap::check_suspicion`].
+    pub fn suspicion_report_with(&self, config: &SuspicionConfig) -> SuspicionReport {
+        SuspicionReport::new(self, config)
+    }
 }
 
 impl FromStr for Beatmap {
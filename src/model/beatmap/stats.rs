@@ -0,0 +1,285 @@
+use rosu_map::section::hit_objects::CurveBuffers;
+
+use crate::{
+    model::{
+        control_point::{DifficultyPoint, TimingPoint},
+        hit_object::{HitObject, HitObjectKind, HoldNote, Spinner},
+    },
+    util::get_precision_adjusted_beat_len,
+    Difficulty, GameMods,
+};
+
+use super::{
+    bpm::{self, BeatmapBpm},
+    Beatmap, BeatmapAttributesBuilder,
+};
+
+/// Length, timing, and density statistics of a [`Beatmap`].
+///
+/// All times are in milliseconds and already divided by the clock rate so
+/// they correspond to the time the map takes to play with the given mods.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BeatmapStats {
+    /// The clock rate with respect to mods.
+    pub clock_rate: f64,
+    /// The amount of hit objects.
+    pub n_objects: usize,
+    /// The start time of the first hit object.
+    pub first_object_time: f64,
+    /// The latest end time of all hit objects, including slider ends.
+    pub last_object_time: f64,
+    /// Time between the start of the first and the end of the last hit
+    /// object.
+    pub total_length: f64,
+    /// Duration of all breaks within [`total_length`](Self::total_length).
+    pub break_time: f64,
+    /// Time that is not spent in breaks i.e. `total_length - break_time`.
+    pub drain_time: f64,
+    /// The average amount of hit objects per second of
+    /// [`total_length`](Self::total_length).
+    pub average_nps: f64,
+    /// The highest amount of hit objects starting within one second.
+    pub peak_nps: u32,
+    /// The amount of hit objects starting in each second of
+    /// [`total_length`](Self::total_length), beginning with the first hit
+    /// object.
+    pub nps: Vec<u32>,
+    /// BPM range and timeline.
+    pub bpm: BeatmapBpm,
+}
+
+/// A builder for [`BeatmapStats`].
+///
+/// The clock rate is resolved the same way as for [`BeatmapAttributes`],
+/// i.e. a custom clock rate takes precedence over the mods' clock rate.
+///
+/// [`BeatmapAttributes`]: super::BeatmapAttributes
+#[derive(Clone, Debug, PartialEq)]
+#[must_use]
+pub struct BeatmapStatsBuilder<'map> {
+    map: &'map Beatmap,
+    attrs: BeatmapAttributesBuilder,
+}
+
+impl<'map> BeatmapStatsBuilder<'map> {
+    /// Create a new [`BeatmapStatsBuilder`] for the given [`Beatmap`].
+    pub fn new(map: &'map Beatmap) -> Self {
+        Self {
+            map,
+            attrs: BeatmapAttributesBuilder::new().map(map),
+        }
+    }
+
+    /// Specify mods.
+    ///
+    /// See [`BeatmapAttributesBuilder::mods`].
+    pub fn mods(mut self, mods: impl Into<GameMods>) -> Self {
+        self.attrs = self.attrs.mods(mods);
+
+        self
+    }
+
+    /// Specify a custom clock rate.
+    ///
+    /// | Minimum | Maximum |
+    /// | :-----: | :-----: |
+    /// | 0.01    | 100     |
+    pub fn clock_rate(mut self, clock_rate: f64) -> Self {
+        self.attrs = self.attrs.clock_rate(clock_rate.clamp(0.01, 100.0));
+
+        self
+    }
+
+    /// Use the mods and clock rate of a [`Difficulty`].
+    pub fn difficulty(mut self, difficulty: &Difficulty) -> Self {
+        self.attrs = self.attrs.difficulty(difficulty);
+
+        self
+    }
+
+    /// Calculate the [`BeatmapStats`].
+    pub fn build(&self) -> BeatmapStats {
+        let map = self.map;
+        let clock_rate = self.attrs.build().clock_rate;
+
+        let mut bufs = CurveBuffers::default();
+
+        let last_object_time = map.hit_objects.last().map(|h| end_time(map, h, &mut bufs));
+        let bpm = bpm::bpm_stats(last_object_time, &map.timing_points, clock_rate);
+
+        let Some(first) = map.hit_objects.first() else {
+            return BeatmapStats {
+                clock_rate,
+                bpm,
+                ..Default::default()
+            };
+        };
+
+        let first_time = first.start_time;
+        let last_time = map
+            .hit_objects
+            .iter()
+            .map(|h| end_time(map, h, &mut bufs))
+            .fold(first_time, f64::max);
+
+        let break_time: f64 = map
+            .breaks
+            .iter()
+            .map(|b| (b.end_time.min(last_time) - b.start_time.max(first_time)).max(0.0))
+            .sum();
+
+        let total_length = (last_time - first_time) / clock_rate;
+        let break_time = break_time / clock_rate;
+
+        // One second of real time in map time
+        let window = 1000.0 * clock_rate;
+
+        let mut nps = vec![0; ((last_time - first_time) / window) as usize + 1];
+        let mut peak_nps = 0;
+        let mut window_start = 0;
+
+        for (i, h) in map.hit_objects.iter().enumerate() {
+            nps[((h.start_time - first_time) / window) as usize] += 1;
+
+            while h.start_time - map.hit_objects[window_start].start_time >= window {
+                window_start += 1;
+            }
+
+            peak_nps = peak_nps.max((i + 1 - window_start) as u32);
+        }
+
+        let n_objects = map.hit_objects.len();
+
+        let average_nps = if total_length > 0.0 {
+            n_objects as f64 / (total_length / 1000.0)
+        } else {
+            0.0
+        };
+
+        BeatmapStats {
+            clock_rate,
+            n_objects,
+            first_object_time: first_time / clock_rate,
+            last_object_time: last_time / clock_rate,
+            total_length,
+            break_time,
+            drain_time: total_length - break_time,
+            average_nps,
+            peak_nps,
+            nps,
+            bpm,
+        }
+    }
+}
+
+/// The end time of a hit object.
+///
+/// Unlike [`HitObject::end_time`], this calculates the slider path to get the
+/// correct end time for sliders.
+pub(super) fn end_time(map: &Beatmap, h: &HitObject, bufs: &mut CurveBuffers) -> f64 {
+    const BASE_SCORING_DIST: f64 = 100.0;
+
+    match h.kind {
+        HitObjectKind::Circle => h.start_time,
+        HitObjectKind::Slider(ref slider) => {
+            let beat_len = map
+                .timing_point_at(h.start_time)
+                .map_or(TimingPoint::DEFAULT_BEAT_LEN, |point| point.beat_len);
+
+            let slider_velocity = map
+                .difficulty_point_at(h.start_time)
+                .map_or(DifficultyPoint::DEFAULT_SLIDER_VELOCITY, |point| {
+                    point.slider_velocity
+                });
+
+            let velocity = BASE_SCORING_DIST * map.slider_multiplier
+                / get_precision_adjusted_beat_len(slider_velocity, beat_len);
+
+            let dist = slider.curve(map.mode, bufs).dist();
+
+            h.start_time + slider.span_count() as f64 * dist / velocity
+        }
+        HitObjectKind::Spinner(Spinner { duration })
+        | HitObjectKind::Hold(HoldNote { duration }) => h.start_time + duration,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Beatmap;
+
+    #[test]
+    fn clock_rate() {
+        let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+
+        let nomod = map.stats().build();
+        let dt = map.stats().mods(64).build();
+
+        assert!((dt.clock_rate - 1.5).abs() < f64::EPSILON);
+        assert!((dt.total_length * 1.5 - nomod.total_length).abs() < 1e-6);
+        assert!((dt.drain_time * 1.5 - nomod.drain_time).abs() < 1e-6);
+        assert!(dt.peak_nps >= nomod.peak_nps);
+        assert_eq!(dt.nps.iter().sum::<u32>() as usize, map.hit_objects.len());
+    }
+
+    #[test]
+    fn slider_end() {
+        let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+        let stats = map.stats().build();
+        let last = map.hit_objects.last().unwrap();
+
+        assert!(last.is_slider());
+        assert!(stats.last_object_time > last.start_time);
+        assert!((stats.drain_time - (stats.total_length - stats.break_time)).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn empty_map() {
+        let stats = Beatmap::default().stats().clock_rate(2.0).build();
+
+        assert!((stats.clock_rate - 2.0).abs() < f64::EPSILON);
+        assert_eq!(stats.n_objects, 0);
+        assert!(stats.nps.is_empty());
+        assert!(stats.bpm.timeline.is_empty());
+    }
+
+    #[test]
+    fn clamped_clock_rate() {
+        let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+
+        let zero = map.stats().clock_rate(0.0).build();
+        assert!((zero.clock_rate - 0.01).abs() < f64::EPSILON);
+        assert_eq!(zero.nps.iter().sum::<u32>() as usize, map.hit_objects.len());
+
+        let negative = map.stats().clock_rate(-1.0).build();
+        assert!((negative.clock_rate - 0.01).abs() < f64::EPSILON);
+        assert!(negative.total_length > 0.0);
+
+        let huge = map.stats().clock_rate(1000.0).build();
+        assert!((huge.clock_rate - 100.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn bpm() {
+        let map = Beatmap::from_path("./resources/5229879.osu").unwrap();
+
+        let nomod = map.stats().build().bpm;
+        let dt = map.stats().mods(64).build().bpm;
+
+        assert!((nomod.most_common - map.bpm()).abs() < f64::EPSILON);
+        assert!((dt.most_common - 1.5 * map.bpm()).abs() < 1e-9);
+        assert!(nomod.timeline.len() > 1);
+        assert!(nomod.min < nomod.max);
+        assert!((dt.max - 1.5 * nomod.max).abs() < 1e-9);
+
+        assert!(nomod.timeline[0].start_time.abs() < f64::EPSILON);
+        assert!(nomod
+            .timeline
+            .windows(2)
+            .all(|w| (w[0].end_time - w[1].start_time).abs() < f64::EPSILON));
+        assert!(nomod
+            .timeline
+            .iter()
+            .all(|s| (nomod.min..=nomod.max).contains(&s.bpm)));
+    }
+}
@@ -0,0 +1,345 @@
+use rosu_map::section::general::GameMode;
+
+use crate::model::hit_object::{HitObject, HitObjectKind, Pos, Slider};
+
+use super::{Beatmap, SuspicionConfig, TooSuspicious};
+
+/// Detailed result of [`Beatmap::suspicion_report`].
+///
+/// Contrary to [`Beatmap::check_suspicion`], which stops at the first
+/// suspicious finding, the report contains every check that was triggered
+/// alongside the offending hit objects.
+///
+/// [`Beatmap::suspicion_report`]: crate::model::beatmap::Beatmap::suspicion_report
+/// [`Beatmap::check_suspicion`]: crate::model::beatmap::Beatmap::check_suspicion
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SuspicionReport {
+    /// All triggered checks.
+    pub findings: Vec<SuspicionFinding>,
+    /// The amount of hit objects.
+    pub n_objects: usize,
+    /// The estimated amount of nested slider objects i.e. heads, ticks,
+    /// repeats, and tails.
+    pub n_slider_nested_objects: u64,
+}
+
+impl SuspicionReport {
+    /// Whether any check was triggered.
+    ///
+    /// This is `true` if and only if [`Beatmap::check_suspicion`] with the
+    /// same [`SuspicionConfig`] returns an error.
+    ///
+    /// [`Beatmap::check_suspicion`]: crate::model::beatmap::Beatmap::check_suspicion
+    pub const fn is_suspicious(&self) -> bool {
+        !self.findings.is_empty()
+    }
+
+    /// The reasons of all findings.
+    pub fn reasons(&self) -> impl Iterator<Item = TooSuspicious> + '_ {
+        self.findings.iter().map(SuspicionFinding::reason)
+    }
+
+    /// A rough estimate of the calculation cost, measured as the amount of
+    /// hit objects plus the amount of their nested slider objects.
+    pub const fn estimated_cost(&self) -> u64 {
+        self.n_objects as u64 + self.n_slider_nested_objects
+    }
+
+    pub(crate) fn new(map: &Beatmap, config: &SuspicionConfig) -> Self {
+        let mut findings = Vec::new();
+        let n_objects = map.hit_objects.len();
+
+        let max_objects = match map.mode {
+            GameMode::Taiko => config.max_objects_taiko,
+            _ => config.max_objects,
+        };
+
+        if n_objects > max_objects {
+            findings.push(SuspicionFinding::ObjectCount {
+                count: n_objects,
+                max: max_objects,
+            });
+        }
+
+        if let [first, .., last] = map.hit_objects.as_slice() {
+            if last.start_time - first.start_time > config.max_length {
+                findings.push(SuspicionFinding::Length {
+                    first_time: first.start_time,
+                    last_time: last.start_time,
+                    max: config.max_length,
+                });
+            }
+        }
+
+        if let Some(max_notes) = TooSuspicious::density_thresholds(map, config) {
+            for (window, max_notes) in [(1000.0, max_notes.0), (10_000.0, max_notes.1)] {
+                if let Some(finding) = DensityFinding::new(&map.hit_objects, window, max_notes) {
+                    findings.push(SuspicionFinding::Density(finding));
+                }
+            }
+        }
+
+        if matches!(map.mode, GameMode::Osu | GameMode::Catch) {
+            Self::check_sliders(map, config, &mut findings);
+        }
+
+        let n_slider_nested_objects = map
+            .hit_objects
+            .iter()
+            .map(|h| match h.kind {
+                HitObjectKind::Slider(ref slider) => {
+                    estimate_nested_objects(map, slider, h.start_time)
+                }
+                _ => 0,
+            })
+            .sum();
+
+        Self {
+            findings,
+            n_objects,
+            n_slider_nested_objects,
+        }
+    }
+
+    fn check_sliders(
+        map: &Beatmap,
+        config: &SuspicionConfig,
+        findings: &mut Vec<SuspicionFinding>,
+    ) {
+        let mut red_flags = Vec::new();
+        let mut positions = Vec::new();
+        let mut repeats = Vec::new();
+
+        let check_pos = |pos: Pos| {
+            f32::abs(pos.x) > config.slider_position || f32::abs(pos.y) > config.slider_position
+        };
+
+        for (i, h) in map.hit_objects.iter().enumerate() {
+            let HitObjectKind::Slider(ref slider) = h.kind else {
+                continue;
+            };
+
+            let finding = SliderFinding {
+                idx: i,
+                start_time: h.start_time,
+                pos: h.pos,
+                repeats: slider.repeats,
+            };
+
+            if slider.repeats > config.slider_repeats {
+                if check_pos(h.pos) {
+                    red_flags.push(finding);
+                } else {
+                    repeats.push(finding);
+                }
+            } else if check_pos(h.pos) {
+                positions.push(finding);
+            }
+        }
+
+        if !red_flags.is_empty() {
+            findings.push(SuspicionFinding::RedFlag { sliders: red_flags });
+        }
+
+        if positions.len() > config.slider_cutoff {
+            findings.push(SuspicionFinding::SliderPositions {
+                sliders: positions,
+                max: config.slider_cutoff,
+            });
+        }
+
+        if repeats.len() > config.slider_cutoff {
+            findings.push(SuspicionFinding::SliderRepeats {
+                sliders: repeats,
+                max: config.slider_cutoff,
+            });
+        }
+    }
+}
+
+/// A triggered check of a [`SuspicionReport`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum SuspicionFinding {
+    /// Too many objects.
+    ObjectCount {
+        /// The amount of hit objects.
+        count: usize,
+        /// The maximum amount of hit objects for the map's mode.
+        max: usize,
+    },
+    /// The map seems too long.
+    Length {
+        /// The start time of the first hit object.
+        first_time: f64,
+        /// The start time of the last hit object.
+        last_time: f64,
+        /// The maximum length in milliseconds.
+        max: f64,
+    },
+    /// Notes are too dense time-wise.
+    Density(DensityFinding),
+    /// Sliders with both a suspicious position and a very high amount of
+    /// repeats.
+    RedFlag {
+        /// The offending sliders.
+        sliders: Vec<SliderFinding>,
+    },
+    /// Too many sliders' positions were suspicious.
+    SliderPositions {
+        /// The offending sliders.
+        sliders: Vec<SliderFinding>,
+        /// The amount of offending sliders that is tolerated.
+        max: usize,
+    },
+    /// Too many sliders had a very high amount of repeats.
+    SliderRepeats {
+        /// The offending sliders.
+        sliders: Vec<SliderFinding>,
+        /// The amount of offending sliders that is tolerated.
+        max: usize,
+    },
+}
+
+impl SuspicionFinding {
+    /// The corresponding [`TooSuspicious`] reason.
+    pub const fn reason(&self) -> TooSuspicious {
+        match self {
+            Self::ObjectCount { .. } => TooSuspicious::ObjectCount,
+            Self::Length { .. } => TooSuspicious::Length,
+            Self::Density(_) => TooSuspicious::Density,
+            Self::RedFlag { .. } => TooSuspicious::RedFlag,
+            Self::SliderPositions { .. } => TooSuspicious::SliderPositions,
+            Self::SliderRepeats { .. } => TooSuspicious::SliderRepeats,
+        }
+    }
+}
+
+/// Note density within a time window.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DensityFinding {
+    /// The length of the time window in milliseconds.
+    pub window: f64,
+    /// The maximum amount of notes within the window for the map's mode.
+    pub max_notes: usize,
+    /// The highest amount of notes within any window.
+    pub peak_notes: usize,
+    /// The start time of the window with the most notes.
+    pub peak_time: f64,
+    /// All sections that are too dense.
+    pub sections: Vec<DenseSection>,
+}
+
+impl DensityFinding {
+    fn new(hit_objects: &[HitObject], window: f64, max_notes: usize) -> Option<Self> {
+        let mut peak_notes = 0;
+        let mut peak_time = 0.0;
+        let mut sections: Vec<DenseSection> = Vec::new();
+
+        let mut end = 0;
+
+        for (i, h) in hit_objects.iter().enumerate() {
+            end = end.max(i);
+
+            while end + 1 < hit_objects.len()
+                && hit_objects[end + 1].start_time - h.start_time < window
+            {
+                end += 1;
+            }
+
+            let notes = end - i + 1;
+
+            if notes > peak_notes {
+                peak_notes = notes;
+                peak_time = h.start_time;
+            }
+
+            if notes <= max_notes {
+                continue;
+            }
+
+            // Same condition as for `Beatmap::check_suspicion`
+            let last_idx = i + max_notes;
+
+            match sections.last_mut() {
+                Some(section) if section.end_idx >= i => {
+                    section.end_idx = last_idx;
+                    section.end_time = hit_objects[last_idx].start_time;
+                }
+                _ => sections.push(DenseSection {
+                    start_idx: i,
+                    end_idx: last_idx,
+                    start_time: h.start_time,
+                    end_time: hit_objects[last_idx].start_time,
+                }),
+            }
+        }
+
+        (!sections.is_empty()).then_some(Self {
+            window,
+            max_notes,
+            peak_notes,
+            peak_time,
+            sections,
+        })
+    }
+
+    /// The highest amount of notes per second.
+    pub fn peak_notes_per_second(&self) -> f64 {
+        self.peak_notes as f64 * 1000.0 / self.window
+    }
+}
+
+/// A section of hit objects that is too dense.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DenseSection {
+    /// The index of the first hit object.
+    pub start_idx: usize,
+    /// The index of the last hit object.
+    pub end_idx: usize,
+    /// The start time of the first hit object.
+    pub start_time: f64,
+    /// The start time of the last hit object.
+    pub end_time: f64,
+}
+
+/// A suspicious slider.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SliderFinding {
+    /// The index of the slider.
+    pub idx: usize,
+    /// The start time of the slider.
+    pub start_time: f64,
+    /// The position of the slider.
+    pub pos: Pos,
+    /// The amount of repeats of the slider.
+    pub repeats: usize,
+}
+
+/// Estimates the amount of nested objects without calculating the curve.
+fn estimate_nested_objects(map: &Beatmap, slider: &Slider, start_time: f64) -> u64 {
+    let len = slider.expected_dist.unwrap_or_else(|| {
+        slider
+            .control_points
+            .windows(2)
+            .map(|w| f64::from(w[0].pos.distance(w[1].pos)))
+            .sum()
+    });
+
+    let slider_velocity = map
+        .difficulty_point_at(start_time)
+        .map_or(1.0, |point| point.slider_velocity);
+
+    let tick_dist = 100.0 * map.slider_multiplier * slider_velocity / map.slider_tick_rate;
+
+    let ticks_per_span = if tick_dist > 0.0 && len.is_finite() {
+        ((len / tick_dist).ceil() - 1.0).max(0.0)
+    } else {
+        0.0
+    };
+
+    let span_count = slider.span_count() as f64;
+
+    // head + tail + repeats + ticks
+    (2.0 + slider.repeats as f64 + ticks_per_span * span_count).min(u64::MAX as f64) as u64
+}
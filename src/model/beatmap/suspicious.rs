@@ -17,7 +17,7 @@ use super::Beatmap;
 ///
 /// [`Beatmap::check_suspicion`]: crate::model::beatmap::Beatmap::check_suspicion
 /// [`Beatmap`]: crate::model::beatmap::Beatmap
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum TooSuspicious {
     /// Notes are too dense time-wise.
@@ -35,11 +35,9 @@ pub enum TooSuspicious {
 }
 
 impl TooSuspicious {
-    pub(crate) fn new(map: &Beatmap) -> Option<Self> {
+    pub(crate) fn new(map: &Beatmap, config: &SuspicionConfig) -> Option<Self> {
         #[inline]
-        const fn too_long(hit_objects: &[HitObject]) -> bool {
-            const DAY_MS: u32 = 60 * 60 * 24 * 1000;
-
+        const fn too_long(hit_objects: &[HitObject], max_length: f64) -> bool {
             if unlikely(hit_objects.len() < 2) {
                 return false;
             }
@@ -48,55 +46,68 @@ impl TooSuspicious {
                 unreachable!()
             };
 
-            (last.start_time - first.start_time) > DAY_MS as f64
+            (last.start_time - first.start_time) > max_length
         }
 
         #[inline]
-        const fn too_many_objects(map: &Beatmap) -> bool {
-            const THRESHOLD: usize = 500_000;
-            /// Taiko calculation is especially expensive for high object counts
-            const THRESHOLD_TAIKO: usize = 20_000;
-
+        const fn too_many_objects(map: &Beatmap, config: &SuspicionConfig) -> bool {
             match map.mode {
-                GameMode::Taiko => map.hit_objects.len() > THRESHOLD_TAIKO,
-                _ => map.hit_objects.len() > THRESHOLD,
+                GameMode::Taiko => map.hit_objects.len() > config.max_objects_taiko,
+                _ => map.hit_objects.len() > config.max_objects,
             }
         }
 
-        if unlikely(too_many_objects(map)) {
+        if unlikely(too_many_objects(map, config)) {
             return Some(Self::ObjectCount);
-        } else if unlikely(too_long(&map.hit_objects)) {
+        } else if unlikely(too_long(&map.hit_objects, config.max_length)) {
             return Some(Self::Length);
         }
 
         match map.mode {
-            GameMode::Osu => Self::check_osu(map),
-            GameMode::Taiko => Self::check_taiko(map),
-            GameMode::Catch => Self::check_catch(map),
-            GameMode::Mania => Self::check_mania(map),
+            GameMode::Osu => Self::check_osu(map, config),
+            GameMode::Taiko => Self::check_taiko(map, config),
+            GameMode::Catch => Self::check_catch(map, config),
+            GameMode::Mania => Self::check_mania(map, config),
         }
     }
 
-    fn check_osu(map: &Beatmap) -> Option<Self> {
+    /// The maximum amount of notes within 1s and within 10s or `None` if the
+    /// mode's note density is not checked.
+    pub(super) fn density_thresholds(
+        map: &Beatmap,
+        config: &SuspicionConfig,
+    ) -> Option<(usize, usize)> {
+        let factor = match map.mode {
+            GameMode::Osu => 1,
+            GameMode::Taiko => 2,
+            GameMode::Catch => return None,
+            GameMode::Mania => cmp::max(1, map.cs as usize / 2),
+        };
+
+        Some((
+            config.notes_per_1s.saturating_mul(factor),
+            config.notes_per_10s.saturating_mul(factor),
+        ))
+    }
+
+    fn check_osu(map: &Beatmap, config: &SuspicionConfig) -> Option<Self> {
         let mut state = SliderState::new();
-        let per_1s = THRESHOLD_1S;
-        let per_10s = THRESHOLD_10S;
+        let (per_1s, per_10s) = Self::density_thresholds(map, config)?;
 
         // Checking both note density and sliders
         for (i, h) in map.hit_objects.iter().enumerate() {
             if unlikely(Self::too_dense(&map.hit_objects, i, per_1s, per_10s)) {
                 return Some(Self::Density);
-            } else if unlikely(Self::suspicious_slider(h, &mut state).is_break()) {
+            } else if unlikely(Self::suspicious_slider(h, &mut state, config).is_break()) {
                 return Some(Self::RedFlag);
             }
         }
 
-        state.eval()
+        state.eval(config)
     }
 
-    fn check_taiko(map: &Beatmap) -> Option<Self> {
-        let per_1s = THRESHOLD_1S * 2;
-        let per_10s = THRESHOLD_10S * 2;
+    fn check_taiko(map: &Beatmap, config: &SuspicionConfig) -> Option<Self> {
+        let (per_1s, per_10s) = Self::density_thresholds(map, config)?;
 
         // Only checking note density
         for i in 0..map.hit_objects.len() {
@@ -108,23 +119,21 @@ impl TooSuspicious {
         None
     }
 
-    fn check_catch(map: &Beatmap) -> Option<Self> {
+    fn check_catch(map: &Beatmap, config: &SuspicionConfig) -> Option<Self> {
         let mut state = SliderState::new();
 
         // Only checking sliders
         for h in map.hit_objects.iter() {
-            if unlikely(Self::suspicious_slider(h, &mut state).is_break()) {
+            if unlikely(Self::suspicious_slider(h, &mut state, config).is_break()) {
                 return Some(Self::RedFlag);
             }
         }
 
-        state.eval()
+        state.eval(config)
     }
 
-    fn check_mania(map: &Beatmap) -> Option<Self> {
-        let keys_per_hand = cmp::max(1, map.cs as usize / 2);
-        let per_1s = THRESHOLD_1S * keys_per_hand;
-        let per_10s = THRESHOLD_10S * keys_per_hand;
+    fn check_mania(map: &Beatmap, config: &SuspicionConfig) -> Option<Self> {
+        let (per_1s, per_10s) = Self::density_thresholds(map, config)?;
 
         // Only checking note density
         for i in 0..map.hit_objects.len() {
@@ -138,6 +147,9 @@ impl TooSuspicious {
 
     #[inline]
     fn too_dense(hit_objects: &[HitObject], i: usize, per_1s: usize, per_10s: usize) -> bool {
+        let per_1s = per_1s.min(hit_objects.len());
+        let per_10s = per_10s.min(hit_objects.len());
+
         (hit_objects.len() > i + per_1s
             && hit_objects[i + per_1s].start_time - hit_objects[i].start_time < 1000.0)
             || (hit_objects.len() > i + per_10s
@@ -145,31 +157,24 @@ impl TooSuspicious {
     }
 
     #[inline]
-    const fn suspicious_slider(h: &HitObject, state: &mut SliderState) -> ControlFlow<()> {
-        #[inline]
-        const fn check_pos(pos: Pos) -> bool {
-            /// osu!'s max value is `131_072` and the playfield is `512x384`
-            const THRESHOLD: f32 = 10_000.0;
-
-            f32::abs(pos.x) > THRESHOLD || f32::abs(pos.y) > THRESHOLD
-        }
-
+    const fn suspicious_slider(
+        h: &HitObject,
+        state: &mut SliderState,
+        config: &SuspicionConfig,
+    ) -> ControlFlow<()> {
         #[inline]
-        const fn check_repeats(repeats: usize) -> bool {
-            /// osu!'s max value is `9000`
-            const THRESHOLD: usize = 1000;
-
-            repeats > THRESHOLD
+        const fn check_pos(pos: Pos, threshold: f32) -> bool {
+            f32::abs(pos.x) > threshold || f32::abs(pos.y) > threshold
         }
 
         if let HitObjectKind::Slider(ref slider) = h.kind {
-            if unlikely(check_repeats(slider.repeats)) {
-                if unlikely(check_pos(h.pos)) {
+            if unlikely(slider.repeats > config.slider_repeats) {
+                if unlikely(check_pos(h.pos, config.slider_position)) {
                     return ControlFlow::Break(());
                 }
 
                 state.repeats_beyond_threshold += 1;
-            } else if unlikely(check_pos(h.pos)) {
+            } else if unlikely(check_pos(h.pos, config.slider_position)) {
                 state.pos_beyond_threshold += 1;
             }
         }
@@ -178,11 +183,71 @@ impl TooSuspicious {
     }
 }
 
-/// 200 notes per 1s = 12000 BPM
-const THRESHOLD_1S: usize = 200;
+/// Thresholds for [`Beatmap::check_suspicion_with`].
+///
+/// The default values are the ones used by [`Beatmap::check_suspicion`].
+///
+/// [`Beatmap::check_suspicion_with`]: crate::model::beatmap::Beatmap::check_suspicion_with
+/// [`Beatmap::check_suspicion`]: crate::model::beatmap::Beatmap::check_suspicion
+#[derive(Clone, Debug, PartialEq)]
+pub struct SuspicionConfig {
+    /// The maximum amount of notes within one second.
+    ///
+    /// Doubled for osu!taiko and multiplied by the amount of keys per hand
+    /// for osu!mania. Defaults to `200` i.e. 12000 BPM.
+    pub notes_per_1s: usize,
+    /// The maximum amount of notes within ten seconds.
+    ///
+    /// Doubled for osu!taiko and multiplied by the amount of keys per hand
+    /// for osu!mania. Defaults to `500` i.e. 3000 BPM.
+    pub notes_per_10s: usize,
+    /// The maximum amount of hit objects. Defaults to `500_000`.
+    pub max_objects: usize,
+    /// The maximum amount of hit objects for osu!taiko since its calculation
+    /// is especially expensive for high object counts. Defaults to `20_000`.
+    pub max_objects_taiko: usize,
+    /// The maximum time between the first and last hit object in
+    /// milliseconds. Defaults to one day.
+    pub max_length: f64,
+    /// The maximum absolute x- or y-coordinate of a slider before it is
+    /// considered suspicious.
+    ///
+    /// osu!'s max value is `131_072` and the playfield is `512x384`.
+    /// Defaults to `10_000.0`.
+    pub slider_position: f32,
+    /// The maximum amount of repeats of a slider before it is considered
+    /// suspicious.
+    ///
+    /// osu!'s max value is `9000`. Defaults to `1000`.
+    pub slider_repeats: usize,
+    /// The amount of suspicious sliders that is tolerated. Defaults to `128`.
+    pub slider_cutoff: usize,
+}
 
-/// 500 notes per 10s = 3000 BPM
-const THRESHOLD_10S: usize = 500;
+impl SuspicionConfig {
+    /// The default thresholds.
+    pub const DEFAULT: Self = Self {
+        notes_per_1s: 200,
+        notes_per_10s: 500,
+        max_objects: 500_000,
+        max_objects_taiko: 20_000,
+        max_length: 86_400_000.0,
+        slider_position: 10_000.0,
+        slider_repeats: 1000,
+        slider_cutoff: 128,
+    };
+
+    /// Create a new [`SuspicionConfig`] with default thresholds.
+    pub const fn new() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl Default for SuspicionConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 struct SliderState {
     repeats_beyond_threshold: usize,
@@ -197,12 +262,10 @@ impl SliderState {
         }
     }
 
-    const fn eval(self) -> Option<TooSuspicious> {
-        const CUTOFF: usize = 128;
-
-        if unlikely(self.pos_beyond_threshold > CUTOFF) {
+    const fn eval(self, config: &SuspicionConfig) -> Option<TooSuspicious> {
+        if unlikely(self.pos_beyond_threshold > config.slider_cutoff) {
             Some(TooSuspicious::SliderPositions)
-        } else if unlikely(self.repeats_beyond_threshold > CUTOFF) {
+        } else if unlikely(self.repeats_beyond_threshold > config.slider_cutoff) {
             Some(TooSuspicious::SliderRepeats)
         } else {
             None
@@ -226,3 +289,121 @@ impl fmt::Display for TooSuspicious {
     [1175457, 1277504, 1594580, 1904970, 2140631, 2440314, 2573161, 2571051,
     2573164, 2619200, 2923535, 3824509]
 */
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{
+        beatmap::{BeatmapBuilder, SuspicionFinding},
+        hit_object::Pos,
+    };
+
+    use super::*;
+
+    fn dense_map() -> Beatmap {
+        (0..100)
+            .fold(BeatmapBuilder::new(), |builder, i| {
+                builder.circle(Pos::default(), f64::from(i) * 5.0)
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn custom_density() {
+        let map = dense_map();
+        assert!(map.check_suspicion().is_ok());
+
+        let config = SuspicionConfig {
+            notes_per_1s: 50,
+            ..SuspicionConfig::default()
+        };
+
+        assert!(matches!(
+            map.check_suspicion_with(&config),
+            Err(TooSuspicious::Density)
+        ));
+    }
+
+    #[test]
+    fn custom_object_count_and_length() {
+        let map = dense_map();
+
+        let config = SuspicionConfig {
+            max_objects: 99,
+            ..SuspicionConfig::default()
+        };
+
+        assert!(matches!(
+            map.check_suspicion_with(&config),
+            Err(TooSuspicious::ObjectCount)
+        ));
+
+        let config = SuspicionConfig {
+            max_length: 100.0,
+            ..SuspicionConfig::default()
+        };
+
+        assert!(matches!(
+            map.check_suspicion_with(&config),
+            Err(TooSuspicious::Length)
+        ));
+    }
+
+    #[test]
+    fn report() {
+        let map = dense_map();
+
+        let config = SuspicionConfig {
+            notes_per_1s: 50,
+            max_objects: 99,
+            ..SuspicionConfig::default()
+        };
+
+        let report = map.suspicion_report_with(&config);
+
+        assert!(report.is_suspicious());
+        assert_eq!(
+            report.reasons().collect::<Vec<_>>(),
+            [TooSuspicious::ObjectCount, TooSuspicious::Density]
+        );
+
+        let SuspicionFinding::Density(ref density) = report.findings[1] else {
+            panic!("expected density finding");
+        };
+
+        assert_eq!(density.peak_notes, 100);
+        assert_eq!(density.sections.len(), 1);
+        assert_eq!(density.sections[0].start_idx, 0);
+        assert_eq!(density.sections[0].end_idx, 99);
+        assert_eq!(report.estimated_cost(), 100);
+    }
+
+    #[test]
+    fn report_matches_check() {
+        for path in [
+            "./resources/2785319.osu",
+            "./resources/1028484.osu",
+            "./resources/2118524.osu",
+            "./resources/1638954.osu",
+        ] {
+            let map = Beatmap::from_path(path).unwrap();
+
+            for notes_per_1s in [5, 10, 20, 200] {
+                let config = SuspicionConfig {
+                    notes_per_1s,
+                    notes_per_10s: notes_per_1s * 5,
+                    ..SuspicionConfig::default()
+                };
+
+                let report = map.suspicion_report_with(&config);
+                let check = map.check_suspicion_with(&config);
+
+                assert_eq!(report.is_suspicious(), check.is_err(), "{path}");
+
+                if let Err(reason) = check {
+                    assert!(report.reasons().any(|r| r == reason), "{path}");
+                }
+            }
+        }
+    }
+}
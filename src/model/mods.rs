@@ -53,6 +53,15 @@ impl Debug for GameMods {
 impl GameMods {
     pub(crate) const DEFAULT: Self = Self::Legacy(GameModsLegacy::NoMod);
 
+    /// Returns the bitflags of all legacy mods.
+    pub(crate) fn bits(&self) -> u32 {
+        match self {
+            Self::Lazer(ref mods) => mods.bits(),
+            Self::Intermode(ref mods) => mods.bits(),
+            Self::Legacy(ref mods) => mods.bits(),
+        }
+    }
+
     /// Returns the mods' clock rate.
     ///
     /// In case of variable clock rates like for `WindUp`, this will return
@@ -232,6 +241,14 @@ impl GameMods {
         }
     }
 
+    pub(crate) fn dual_stages(&self) -> bool {
+        match self {
+            Self::Lazer(ref mods) => mods.contains_intermode(GameModIntermode::DualStages),
+            Self::Intermode(ref mods) => mods.contains(GameModIntermode::DualStages),
+            Self::Legacy(mods) => mods.contains(GameModsLegacy::KeyCoop),
+        }
+    }
+
     pub(crate) fn scroll_speed(&self) -> Option<f64> {
         let Self::Lazer(mods) = self else { return None };
 
@@ -277,6 +294,36 @@ impl GameMods {
             })
     }
 
+    pub(crate) fn wiggle_strength(&self) -> Option<f64> {
+        match self {
+            Self::Lazer(ref mods) => mods.iter().find_map(|m| match m {
+                GameMod::WiggleOsu(m) => Some(m.strength.unwrap_or(1.0)),
+                _ => None,
+            }),
+            Self::Intermode(ref mods) => mods.contains(GameModIntermode::Wiggle).then_some(1.0),
+            Self::Legacy(_) => None,
+        }
+    }
+
+    /// Rotations per minute; negative for counterclockwise rotations.
+    pub(crate) fn barrel_roll_speed(&self) -> Option<f64> {
+        match self {
+            Self::Lazer(ref mods) => mods.iter().find_map(|m| match m {
+                GameMod::BarrelRollOsu(m) => {
+                    let speed = m.spin_speed.unwrap_or(0.5);
+
+                    match m.direction.as_deref() {
+                        Some("1") => Some(-speed),
+                        _ => Some(speed),
+                    }
+                }
+                _ => None,
+            }),
+            Self::Intermode(ref mods) => mods.contains(GameModIntermode::BarrelRoll).then_some(0.5),
+            Self::Legacy(_) => None,
+        }
+    }
+
     pub(crate) fn start_scale(&self) -> Option<f64> {
         let Self::Lazer(mods) = self else { return None };
 
@@ -369,6 +416,7 @@ impl_has_mod! {
     ht: + HalfTime ["HalfTime"],
     dt: + DoubleTime ["DoubleTime"],
     score_v2: + ScoreV2 ["ScoreV2"],
+    mr: + Mirror ["Mirror"],
     bl: - Blinds ["Blinds"],
     cl: - Classic ["Classic"],
     invert: - Invert ["Invert"],
@@ -1,20 +1,28 @@
 use rosu_map::section::hit_objects::CurveBuffers;
 
-use crate::model::{beatmap::Beatmap, mods::Reflection};
+use crate::{
+    any::difficulty::cancellation::Cancellation,
+    model::{beatmap::Beatmap, mods::Reflection},
+    util::hint::unlikely,
+};
 
 use super::{
     attributes::OsuDifficultyAttributes,
     difficulty::scaling_factor::ScalingFactor,
+    geometry::ModGeometry,
     object::{NestedSliderObjectKind, OsuObject, OsuObjectKind},
 };
 
+#[allow(clippy::too_many_arguments)]
 pub fn convert_objects(
     map: &Beatmap,
     scaling_factor: &ScalingFactor,
     reflection: Reflection,
+    geometry: Option<ModGeometry>,
     time_preempt: f64,
     mut take: usize,
     attrs: &mut OsuDifficultyAttributes,
+    cancellation: &Cancellation,
 ) -> Box<[OsuObject]> {
     let mut curve_bufs = CurveBuffers::default();
     // mean=5.16 | median=4
@@ -23,6 +31,7 @@ pub fn convert_objects(
     let mut osu_objects: Box<[_]> = map
         .hit_objects
         .iter()
+        .take_while(|_| !unlikely(cancellation.is_cancelled()))
         .map(|h| OsuObject::new(h, map, &mut curve_bufs, &mut ticks_buf))
         .inspect(|h| {
             if take == 0 {
@@ -60,9 +69,9 @@ pub fn convert_objects(
     let stack_threshold = time_preempt * f64::from(map.stack_leniency);
 
     if map.version >= 6 {
-        stacking(&mut osu_objects, stack_threshold);
+        stacking(&mut osu_objects, stack_threshold, cancellation);
     } else {
-        old_stacking(&mut osu_objects, stack_threshold);
+        old_stacking(&mut osu_objects, stack_threshold, cancellation);
     }
 
     for h in osu_objects.iter_mut() {
@@ -73,12 +82,18 @@ pub fn convert_objects(
         }
     }
 
+    if let Some(geometry) = geometry {
+        for h in osu_objects.iter_mut() {
+            geometry.apply(h, time_preempt);
+        }
+    }
+
     osu_objects
 }
 
 const STACK_DISTANCE: f32 = 3.0;
 
-fn stacking(hit_objects: &mut [OsuObject], stack_threshold: f64) {
+fn stacking(hit_objects: &mut [OsuObject], stack_threshold: f64, cancellation: &Cancellation) {
     let mut extended_start_idx = 0;
 
     let Some(extended_end_idx) = hit_objects.len().checked_sub(1) else {
@@ -88,6 +103,10 @@ fn stacking(hit_objects: &mut [OsuObject], stack_threshold: f64) {
     // First big `if` in osu!lazer's function can be skipped
 
     for i in (1..=extended_end_idx).rev() {
+        if unlikely(cancellation.is_cancelled()) {
+            return;
+        }
+
         let mut n = i;
         let mut obj_i_idx = i;
         // * We should check every note which has not yet got a stack.
@@ -195,8 +214,12 @@ fn stacking(hit_objects: &mut [OsuObject], stack_threshold: f64) {
     }
 }
 
-fn old_stacking(hit_objects: &mut [OsuObject], stack_threshold: f64) {
+fn old_stacking(hit_objects: &mut [OsuObject], stack_threshold: f64, cancellation: &Cancellation) {
     for i in 0..hit_objects.len() {
+        if unlikely(cancellation.is_cancelled()) {
+            return;
+        }
+
         if hit_objects[i].stack_height != 0 && !hit_objects[i].is_slider() {
             continue;
         }
@@ -3,12 +3,17 @@ use std::{cmp, mem};
 use rosu_map::section::general::GameMode;
 
 use crate::{
-    any::difficulty::skills::StrainSkill,
+    any::difficulty::{
+        cancellation::Cancellation,
+        checkpoints::{Checkpoints, ResumeError, StateHeader, StateReader, StateWriter},
+    },
     model::{mode::ConvertError},
     osu::{
         convert::convert_objects,
+        geometry::ModGeometry,
         object::{OsuObject, OsuObjectKind},
     },
+    util::judgement_times::JudgementTimes,
     Beatmap, Difficulty,
 };
 
@@ -55,7 +60,9 @@ use super::{
 pub struct OsuGradualDifficulty {
     pub(crate) idx: usize,
     pub(crate) difficulty: Difficulty,
-    map: Beatmap,
+    /// See [`StateHeader::map_hash`].
+    map_hash: u64,
+    pub(crate) map: Beatmap,
     attrs: OsuDifficultyAttributes,
     skills: OsuSkills,
     // Lifetimes actually depend on `osu_objects` so this type is
@@ -63,6 +70,8 @@ pub struct OsuGradualDifficulty {
     // `osu_objects` will immediately invalidate `diff_objects`.
     diff_objects: Box<[OsuDifficultyObject<'static>]>,
     osu_objects: OsuObjects,
+    judgement_times: JudgementTimes,
+    checkpoints: Checkpoints<(OsuDifficultyAttributes, OsuSkills)>,
     // Additional safety measure that this type can't be cloned as it would
     // invalidate `diff_objects`.
     _not_clonable: NotClonable,
@@ -87,9 +96,11 @@ impl OsuGradualDifficulty {
             &map,
             &scaling_factor,
             mods.reflection(),
+            ModGeometry::new(&difficulty),
             time_preempt,
             map.hit_objects.len(),
             &mut attrs,
+            &Cancellation::NONE,
         );
 
         attrs.n_circles = 0;
@@ -102,29 +113,170 @@ impl OsuGradualDifficulty {
             Self::increment_combo(h, &mut attrs);
         }
 
+        let judgement_times = osu_objects.iter().map(OsuObject::end_time).collect();
         let mut osu_objects = OsuObjects::new(osu_objects);
 
         let diff_objects = DifficultyValues::create_difficulty_objects(
             &difficulty,
             &scaling_factor,
             osu_objects.iter_mut(),
+            &Cancellation::NONE,
         );
 
         let skills = OsuSkills::new(mods, &scaling_factor, &map_attrs, time_preempt);
         let diff_objects = extend_lifetime(diff_objects.into_boxed_slice());
+        let checkpoints = Checkpoints::new((attrs.clone(), skills.clone()));
 
         Ok(Self {
             idx: 0,
+            map_hash: StateHeader::map_hash(&map),
             map: map.as_ref().to_owned(),
             difficulty,
             attrs,
             skills,
             diff_objects,
             osu_objects,
+            judgement_times,
+            checkpoints,
             _not_clonable: NotClonable,
         })
     }
 
+    /// Take a snapshot of the calculator's state every `interval` processed
+    /// hit objects so that [`OsuGradualDifficulty::seek`] can rewind without
+    /// starting over.
+    ///
+    /// Snapshots are disabled by default, i.e. an interval of `0`. Each
+    /// snapshot stores the skills' strain history so smaller intervals trade
+    /// memory for faster seeking.
+    #[must_use]
+    pub const fn checkpoint_interval(mut self, interval: usize) -> Self {
+        self.checkpoints.set_interval(interval);
+
+        self
+    }
+
+    /// Move to the state after processing `idx` hit objects so that the next
+    /// call of [`Iterator::next`] processes the hit object at index `idx`.
+    ///
+    /// Seeking backwards resumes from the latest snapshot at or before `idx`,
+    /// see [`OsuGradualDifficulty::checkpoint_interval`].
+    pub fn seek(&mut self, idx: usize) {
+        if let Some((n, (attrs, skills))) = self.checkpoints.restore(self.idx, idx) {
+            self.idx = n;
+            self.attrs = attrs;
+            self.skills = skills;
+        }
+
+        while self.idx < idx && self.process_next().is_some() {}
+    }
+
+    /// Process all hit objects that are judged at or before `time`, i.e. the
+    /// current playback position in milliseconds, and return the attributes
+    /// at that moment.
+    ///
+    /// Circles are judged at their start time, sliders and spinners at their
+    /// end time.
+    /// Objects are processed in order of their start time so if a later hit
+    /// object is judged while a slider or spinner is still in progress, the
+    /// unjudged object is processed as well instead of blocking the judged one.
+    /// Already processed hit objects are never rewound, see
+    /// [`OsuGradualDifficulty::seek`].
+    ///
+    /// Returns `None` if no hit object has been processed yet.
+    pub fn advance_to_time(&mut self, time: f64) -> Option<OsuDifficultyAttributes> {
+        while self.judgement_times.is_judged(self.idx, time) {
+            self.process_next()?;
+        }
+
+        (self.idx > 0).then(|| self.eval())
+    }
+
+    /// Serialize the current state, i.e. the amount of processed hit objects,
+    /// the partial attributes, and the skills' strain history.
+    ///
+    /// The state can be resumed through [`OsuGradualDifficulty::resume`],
+    /// e.g. in another process. Snapshots of
+    /// [`OsuGradualDifficulty::checkpoint_interval`] are not included.
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new(self.state_header(), self.idx);
+
+        writer
+            .write(&self.attrs.n_circles)
+            .write(&self.attrs.n_sliders)
+            .write(&self.attrs.n_large_ticks)
+            .write(&self.attrs.n_spinners)
+            .write(&self.attrs.max_combo)
+            .write(&self.skills);
+
+        writer.finish()
+    }
+
+    /// Resume a state that was serialized through
+    /// [`OsuGradualDifficulty::save_state`].
+    ///
+    /// The difficulty objects are rebuilt from the map so `difficulty` and
+    /// `map` must be the same as for the serialized calculator.
+    pub fn resume(
+        difficulty: Difficulty,
+        map: &Beatmap,
+        state: &[u8],
+    ) -> Result<Self, ResumeError> {
+        let mut this = Self::new(difficulty, map)?;
+        let mut reader = StateReader::new(state, this.state_header())?;
+
+        this.attrs.n_circles = reader.read()?;
+        this.attrs.n_sliders = reader.read()?;
+        this.attrs.n_large_ticks = reader.read()?;
+        this.attrs.n_spinners = reader.read()?;
+        this.attrs.max_combo = reader.read()?;
+        this.skills = reader.read()?;
+        this.idx = reader.idx();
+        reader.finish()?;
+
+        Ok(this)
+    }
+
+    fn state_header(&self) -> StateHeader {
+        StateHeader::new(
+            GameMode::Osu,
+            &self.difficulty,
+            self.idx + self.len(),
+            self.map_hash,
+        )
+    }
+
+    /// Process the next hit object without evaluating the skills.
+    fn process_next(&mut self) -> Option<()> {
+        // The first difficulty object belongs to the second note since each
+        // difficulty object requires the current and the last note. Hence, if
+        // we're still on the first object, we don't have a difficulty object
+        // yet and just skip processing.
+        if self.idx > 0 {
+            let curr = self.diff_objects.get(self.idx - 1)?;
+
+            self.skills.process(curr, &self.diff_objects);
+            Self::increment_combo(curr.base, &mut self.attrs);
+        } else if self.osu_objects.is_empty() {
+            return None;
+        }
+
+        self.idx += 1;
+
+        self.checkpoints
+            .save(self.idx, || (self.attrs.clone(), self.skills.clone()));
+
+        Some(())
+    }
+
+    fn eval(&self) -> OsuDifficultyAttributes {
+        let mut attrs = self.attrs.clone();
+
+        DifficultyValues::eval(&self.map, &mut attrs, self.difficulty.get_mods(), &self.skills);
+
+        attrs
+    }
+
     fn increment_combo(h: &OsuObject, attrs: &mut OsuDifficultyAttributes) {
         attrs.max_combo += 1;
 
@@ -153,30 +305,9 @@ impl Iterator for OsuGradualDifficulty {
     type Item = OsuDifficultyAttributes;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // The first difficulty object belongs to the second note since each
-        // difficulty object requires the current and the last note. Hence, if
-        // we're still on the first object, we don't have a difficulty object
-        // yet and just skip processing.
-        if self.idx > 0 {
-            let curr = self.diff_objects.get(self.idx - 1)?;
-
-            self.skills.aim.process(curr, &self.diff_objects);
-            self.skills.aim_no_sliders.process(curr, &self.diff_objects);
-            self.skills.speed.process(curr, &self.diff_objects);
-            self.skills.flashlight.process(curr, &self.diff_objects);
-
-            Self::increment_combo(curr.base, &mut self.attrs);
-        } else if self.osu_objects.is_empty() {
-            return None;
-        }
-
-        self.idx += 1;
-
-        let mut attrs = self.attrs.clone();
+        self.process_next()?;
 
-        DifficultyValues::eval(&self.map, &mut attrs, self.difficulty.get_mods(), &self.skills);
-
-        Some(attrs)
+        Some(self.eval())
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -186,20 +317,10 @@ impl Iterator for OsuGradualDifficulty {
     }
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
-        let skip_iter = self.diff_objects.iter().skip(self.idx.saturating_sub(1));
+        let take = cmp::min(n, self.len().saturating_sub(1));
 
-        let mut take = cmp::min(n, self.len().saturating_sub(1));
-
-        // The first note has no difficulty object
-        if self.idx == 0 && take > 0 {
-            take -= 1;
-            self.idx += 1;
-        }
-
-        for curr in skip_iter.take(take) {
-            self.skills.process(curr, &self.diff_objects);
-            Self::increment_combo(curr.base, &mut self.attrs);
-            self.idx += 1;
+        for _ in 0..take {
+            self.process_next()?;
         }
 
         self.next()
@@ -239,7 +360,13 @@ mod osu_objects {
 
 #[cfg(test)]
 mod tests {
-    use crate::{osu::Osu, Beatmap};
+    use crate::{
+        model::mods::rosu_mods::{
+            self, generated_mods::DifficultyAdjustOsu, GameMod, GameModsIntermode,
+        },
+        osu::Osu,
+        Beatmap,
+    };
 
     use super::*;
 
@@ -289,4 +416,127 @@ mod tests {
             assert_eq!(next_gradual, expected);
         }
     }
+
+    #[test]
+    fn seek() {
+        let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+
+        let difficulty = Difficulty::new();
+
+        let expected: Vec<_> = OsuGradualDifficulty::new(difficulty.clone(), &map)
+            .unwrap()
+            .collect();
+
+        for interval in [0, 1, 50] {
+            let mut gradual = OsuGradualDifficulty::new(difficulty.clone(), &map)
+                .unwrap()
+                .checkpoint_interval(interval);
+
+            for idx in [100, 30, 0, 51, 50, expected.len() - 1, 2, 99] {
+                gradual.seek(idx);
+                assert_eq!(gradual.len(), expected.len() - idx);
+                assert_eq!(gradual.next().as_ref(), expected.get(idx), "{idx}");
+            }
+
+            gradual.seek(expected.len());
+            assert!(gradual.next().is_none());
+        }
+    }
+
+    #[test]
+    fn save_and_resume() {
+        let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+
+        let difficulty = Difficulty::new().mods(88); // HDHRDT
+
+        let mut gradual = OsuGradualDifficulty::new(difficulty.clone(), &map).unwrap();
+        let _ = gradual.nth(100);
+        let state = gradual.save_state();
+
+        let resumed = OsuGradualDifficulty::resume(difficulty.clone(), &map, &state).unwrap();
+        assert_eq!(resumed.len(), gradual.len());
+        assert!(resumed.eq(gradual));
+
+        let truncated = &state[..state.len() - 1];
+        let res = OsuGradualDifficulty::resume(difficulty.clone(), &map, truncated);
+        assert!(matches!(res, Err(ResumeError::Invalid)));
+
+        let res = OsuGradualDifficulty::resume(difficulty.clone().clock_rate(1.2), &map, &state);
+        assert!(matches!(res, Err(ResumeError::Mismatch)));
+
+        let res = OsuGradualDifficulty::resume(difficulty.clone().mods(24), &map, &state);
+        assert!(matches!(res, Err(ResumeError::Mismatch)));
+
+        let res = OsuGradualDifficulty::resume(difficulty.clone().lazer(false), &map, &state);
+        assert!(matches!(res, Err(ResumeError::Mismatch)));
+
+        let res = OsuGradualDifficulty::resume(difficulty.clone().cs(4.0, false), &map, &state);
+        assert!(matches!(res, Err(ResumeError::Mismatch)));
+
+        let hr_offsets = difficulty.clone().hardrock_offsets(false);
+        let res = OsuGradualDifficulty::resume(hr_offsets, &map, &state);
+        assert!(matches!(res, Err(ResumeError::Mismatch)));
+
+        // Equivalent mods in a different representation
+        let intermode = GameModsIntermode::try_from_acronyms("HDHRDT").unwrap();
+        let equivalent = difficulty.clone().mods(intermode.clone());
+        assert!(OsuGradualDifficulty::resume(equivalent, &map, &state).is_ok());
+
+        // Same mods but with custom settings
+        let mut lazer = intermode.with_mode(rosu_mods::GameMode::Osu);
+        lazer.insert(GameMod::DifficultyAdjustOsu(DifficultyAdjustOsu {
+            circle_size: Some(5.0),
+            ..DifficultyAdjustOsu::default()
+        }));
+        let res = OsuGradualDifficulty::resume(difficulty.clone().mods(lazer), &map, &state);
+        assert!(matches!(res, Err(ResumeError::Mismatch)));
+
+        // Same timings but different positions
+        let mut moved = map.clone();
+
+        for h in moved.hit_objects.iter_mut() {
+            h.pos.x += 1.0;
+        }
+
+        let res = OsuGradualDifficulty::resume(difficulty.clone(), &moved, &state);
+        assert!(matches!(res, Err(ResumeError::Mismatch)));
+
+        // Same amount of objects but different timings
+        let mut shifted = map.clone();
+
+        for h in shifted.hit_objects.iter_mut() {
+            h.start_time += 1.0;
+        }
+
+        let res = OsuGradualDifficulty::resume(difficulty, &shifted, &state);
+        assert!(matches!(res, Err(ResumeError::Mismatch)));
+    }
+
+    #[test]
+    fn advance_to_time() {
+        let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+
+        let difficulty = Difficulty::new();
+
+        let expected: Vec<_> = OsuGradualDifficulty::new(difficulty.clone(), &map)
+            .unwrap()
+            .collect();
+
+        let mut gradual = OsuGradualDifficulty::new(difficulty, &map).unwrap();
+        assert!(gradual.advance_to_time(f64::MIN).is_none());
+
+        let times = gradual.judgement_times.clone();
+
+        for time in [times[10], times[10] + 1.0, times[100] - 1.0, times[200]] {
+            let attrs = gradual.advance_to_time(time);
+            let idx = gradual.idx;
+
+            assert!(times[idx - 1] <= time && times[idx] > time, "{time}");
+            assert_eq!(attrs.as_ref(), expected.get(idx - 1), "{time}");
+        }
+
+        let attrs = gradual.advance_to_time(f64::MAX);
+        assert_eq!(attrs.as_ref(), expected.last());
+        assert_eq!(gradual.len(), 0);
+    }
 }
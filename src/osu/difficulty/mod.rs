@@ -7,22 +7,22 @@ use self::calculator::OsuRatingCalculator;
 use skills::{aim::Aim, flashlight::Flashlight, speed::Speed, strain::OsuStrainSkill};
 
 use crate::{
-    Beatmap, any::difficulty::{Difficulty, skills::StrainSkill}, model::{
+    Beatmap, any::difficulty::{Difficulty, cancellation::Cancellation, skills::StrainSkill}, model::{
         beatmap::BeatmapAttributes, 
         mode::ConvertError, 
-        mods::GameMods
+        mods::{GameMods, Reflection}
     }, osu::{
-        convert::convert_objects, difficulty::{object::OsuDifficultyObject, scaling_factor::ScalingFactor, skills::strain::difficulty_to_performance}, legacy::{
+        convert::convert_objects, geometry::ModGeometry, difficulty::{object::OsuDifficultyObject, scaling_factor::ScalingFactor, skills::strain::difficulty_to_performance}, legacy::{
             OsuLegacyScoreSimulator, utils::{calculate_difficulty_peppy_stars, calculate_nested_score_per_object}
         }, object::OsuObject, performance::calculator::PERFORMANCE_BASE_MULTIPLIER
-    }
+    }, util::hint::unlikely
 };
 
 use self::skills::OsuSkills;
 
 use super::attributes::OsuDifficultyAttributes;
 
-mod object;
+pub mod object;
 pub mod calculator;
 pub mod gradual;
 pub mod scaling_factor;
@@ -41,12 +41,140 @@ pub fn difficulty(
 
     let DifficultyValues { skills, mut attrs } = DifficultyValues::calculate(difficulty, &map);
 
+    // The attributes are incomplete anyway so skip the costly evaluation
+    if unlikely(difficulty.get_cancellation().is_cancelled()) {
+        return Ok(attrs);
+    }
+
     let mods = difficulty.get_mods();
     DifficultyValues::eval(&map, &mut attrs, mods, &skills);
 
     Ok(attrs)
 }
 
+/// Calculate the difficulty attributes and skills for multiple
+/// [`Difficulty`] settings on the same map.
+///
+/// Settings that result in the same object geometry, e.g. NM and HD or
+/// different clock rates at the same AR, share their [`OsuObject`]s.
+/// Settings that additionally share the clock rate also share their
+/// difficulty objects so only skill processing is repeated for them.
+pub fn difficulty_many(
+    difficulties: &[Difficulty],
+    map: &Beatmap,
+) -> Result<Vec<DifficultyValues>, ConvertError> {
+    let Some(first) = difficulties.first() else {
+        return Ok(Vec::new());
+    };
+
+    let map = map.convert_ref(GameMode::Osu, first.get_mods())?;
+
+    let mut results: Vec<_> = difficulties.iter().map(|_| None).collect();
+
+    let groups = group_indices(0..difficulties.len(), |i| {
+        GeometryKey::new(&difficulties[i], &map)
+    });
+
+    for indices in groups {
+        let converted = ConvertedObjects::new(&difficulties[indices[0]], &map);
+
+        let OsuDifficultySetup {
+            scaling_factor,
+            attrs,
+            time_preempt,
+            ..
+        } = &converted.setup;
+
+        let rate_groups = group_indices(indices.into_iter(), |i| {
+            difficulties[i].get_clock_rate().to_bits()
+        });
+
+        for indices in rate_groups {
+            let diff_objects = converted.difficulty_objects(&difficulties[indices[0]]);
+
+            for i in indices {
+                let difficulty = &difficulties[i];
+                let setup = OsuDifficultySetup::new(difficulty, &map);
+
+                let skills = DifficultyValues::process_skills(
+                    difficulty,
+                    &map,
+                    scaling_factor,
+                    &setup.map_attrs,
+                    *time_preempt,
+                    &diff_objects,
+                );
+
+                let mut member_attrs = OsuDifficultyAttributes {
+                    n_circles: attrs.n_circles,
+                    n_sliders: attrs.n_sliders,
+                    n_large_ticks: attrs.n_large_ticks,
+                    n_spinners: attrs.n_spinners,
+                    max_combo: attrs.max_combo,
+                    ..setup.attrs
+                };
+
+                if !unlikely(difficulty.get_cancellation().is_cancelled()) {
+                    DifficultyValues::eval(&map, &mut member_attrs, difficulty.get_mods(), &skills);
+                }
+
+                results[i] = Some(DifficultyValues {
+                    skills,
+                    attrs: member_attrs,
+                });
+            }
+        }
+    }
+
+    Ok(results.into_iter().flatten().collect())
+}
+
+/// Split indices into groups of equal keys while preserving their order.
+fn group_indices<K: PartialEq>(
+    indices: impl Iterator<Item = usize>,
+    key: impl Fn(usize) -> K,
+) -> Vec<Vec<usize>> {
+    let mut groups: Vec<(K, Vec<usize>)> = Vec::new();
+
+    for i in indices {
+        let curr = key(i);
+
+        match groups.iter_mut().find(|(group_key, _)| *group_key == curr) {
+            Some((_, group)) => group.push(i),
+            None => groups.push((curr, vec![i])),
+        }
+    }
+
+    groups.into_iter().map(|(_, group)| group).collect()
+}
+
+/// Everything that affects [`OsuObject`]s.
+///
+/// The clock rate is not included because the time preempt is already
+/// adjusted for it.
+#[derive(PartialEq)]
+struct GeometryKey {
+    cs: f64,
+    time_preempt: f64,
+    reflection: Reflection,
+    mod_geometry: Option<ModGeometry>,
+    passed_objects: usize,
+}
+
+impl GeometryKey {
+    fn new(difficulty: &Difficulty, map: &Beatmap) -> Self {
+        let setup = OsuDifficultySetup::new(difficulty, map);
+
+        Self {
+            cs: setup.map_attrs.cs,
+            time_preempt: setup.time_preempt,
+            reflection: difficulty.get_mods().reflection(),
+            mod_geometry: ModGeometry::new(difficulty),
+            passed_objects: difficulty.get_passed_objects(),
+        }
+    }
+}
+
 pub struct OsuDifficultySetup {
     scaling_factor: ScalingFactor,
     map_attrs: BeatmapAttributes,
@@ -80,6 +208,48 @@ impl OsuDifficultySetup {
     }
 }
 
+/// The [`OsuDifficultySetup`] of a [`Difficulty`] and the converted
+/// [`OsuObject`]s of a map with their slider cursor positions computed.
+struct ConvertedObjects {
+    setup: OsuDifficultySetup,
+    osu_objects: Box<[OsuObject]>,
+}
+
+impl ConvertedObjects {
+    fn new(difficulty: &Difficulty, map: &Beatmap) -> Self {
+        let mut setup = OsuDifficultySetup::new(difficulty, map);
+
+        let mut osu_objects = convert_objects(
+            map,
+            &setup.scaling_factor,
+            difficulty.get_mods().reflection(),
+            ModGeometry::new(difficulty),
+            setup.time_preempt,
+            difficulty.get_passed_objects(),
+            &mut setup.attrs,
+            difficulty.get_cancellation(),
+        );
+
+        let radius = setup.scaling_factor.radius;
+
+        for h in osu_objects.iter_mut() {
+            OsuDifficultyObject::compute_slider_cursor_pos(Pin::new(h), radius);
+        }
+
+        Self { setup, osu_objects }
+    }
+
+    /// Create the difficulty objects for the clock rate of a [`Difficulty`].
+    fn difficulty_objects(&self, difficulty: &Difficulty) -> Vec<OsuDifficultyObject<'_>> {
+        DifficultyValues::create_difficulty_objects_from(
+            difficulty,
+            &self.setup.scaling_factor,
+            self.osu_objects.iter(),
+            difficulty.get_cancellation(),
+        )
+    }
+}
+
 pub struct DifficultyValues {
     pub skills: OsuSkills,
     pub attrs: OsuDifficultyAttributes,
@@ -87,40 +257,71 @@ pub struct DifficultyValues {
 
 impl DifficultyValues {
     pub fn calculate(difficulty: &Difficulty, map: &Beatmap) -> Self {
-        let mods = difficulty.get_mods();
-        let take = difficulty.get_passed_objects();
-
-        let OsuDifficultySetup {
-            scaling_factor,
-            map_attrs,
-            mut attrs,
-            time_preempt,
-        } = OsuDifficultySetup::new(difficulty, map);
+        let converted = ConvertedObjects::new(difficulty, map);
+        let diff_objects = converted.difficulty_objects(difficulty);
+        let setup = &converted.setup;
 
-        let mut osu_objects = convert_objects(
+        let skills = Self::process_skills(
+            difficulty,
             map,
-            &scaling_factor,
-            mods.reflection(),
-            time_preempt,
-            take,
-            &mut attrs,
+            &setup.scaling_factor,
+            &setup.map_attrs,
+            setup.time_preempt,
+            &diff_objects,
         );
 
-        let osu_object_iter = osu_objects.iter_mut().map(Pin::new);
+        Self {
+            skills,
+            attrs: converted.setup.attrs,
+        }
+    }
 
-        let diff_objects =
-            Self::create_difficulty_objects(difficulty, &scaling_factor, osu_object_iter);
+    /// Create the difficulty objects of all passed objects without
+    /// processing any skills and hand them to `f`.
+    pub fn with_difficulty_objects<T>(
+        difficulty: &Difficulty,
+        map: &Beatmap,
+        f: impl FnOnce(&[OsuDifficultyObject<'_>]) -> T,
+    ) -> T {
+        let converted = ConvertedObjects::new(difficulty, map);
+        let diff_objects = converted.difficulty_objects(difficulty);
 
-        let mut skills = OsuSkills::new(mods, &scaling_factor, &map_attrs, time_preempt);
+        // The first hit object has no difficulty object
+        let take_diff_objects = cmp::min(map.hit_objects.len(), difficulty.get_passed_objects())
+            .saturating_sub(1)
+            .min(diff_objects.len());
+
+        f(&diff_objects[..take_diff_objects])
+    }
+
+    /// Process all difficulty objects with fresh skills.
+    fn process_skills(
+        difficulty: &Difficulty,
+        map: &Beatmap,
+        scaling_factor: &ScalingFactor,
+        map_attrs: &BeatmapAttributes,
+        time_preempt: f64,
+        diff_objects: &[OsuDifficultyObject<'_>],
+    ) -> OsuSkills {
+        let mods = difficulty.get_mods();
+        let take = difficulty.get_passed_objects();
+
+        let mut skills = OsuSkills::new(mods, scaling_factor, map_attrs, time_preempt);
 
         // The first hit object has no difficulty object
         let take_diff_objects = cmp::min(map.hit_objects.len(), take).saturating_sub(1);
 
+        let cancellation = difficulty.get_cancellation();
+
         for hit_object in diff_objects.iter().take(take_diff_objects) {
-            skills.process(hit_object, &diff_objects);
+            if unlikely(cancellation.is_cancelled()) {
+                break;
+            }
+
+            skills.process(hit_object, diff_objects);
         }
 
-        Self { skills, attrs }
+        skills
     }
 
     /// Process the difficulty values and store the results in `attrs`.
@@ -250,27 +451,40 @@ impl DifficultyValues {
         difficulty: &Difficulty,
         scaling_factor: &ScalingFactor,
         osu_objects: impl ExactSizeIterator<Item = Pin<&'a mut OsuObject>>,
+        cancellation: &Cancellation,
+    ) -> Vec<OsuDifficultyObject<'a>> {
+        let osu_objects = osu_objects
+            .map(|h| OsuDifficultyObject::compute_slider_cursor_pos(h, scaling_factor.radius))
+            .map(|h| Pin::into_ref(h).get_ref());
+
+        Self::create_difficulty_objects_from(difficulty, scaling_factor, osu_objects, cancellation)
+    }
+
+    /// Same as [`DifficultyValues::create_difficulty_objects`] but the slider
+    /// cursor positions must have been computed already.
+    pub fn create_difficulty_objects_from<'a>(
+        difficulty: &Difficulty,
+        scaling_factor: &ScalingFactor,
+        mut osu_objects: impl Iterator<Item = &'a OsuObject>,
+        cancellation: &Cancellation,
     ) -> Vec<OsuDifficultyObject<'a>> {
         let take = difficulty.get_passed_objects();
         let clock_rate = difficulty.get_clock_rate();
 
-        let mut osu_objects_iter = osu_objects
-            .map(|h| OsuDifficultyObject::compute_slider_cursor_pos(h, scaling_factor.radius))
-            .map(Pin::into_ref);
-
-        let Some(mut last) = osu_objects_iter.next().filter(|_| take > 0) else {
+        let Some(mut last) = osu_objects.next().filter(|_| take > 0) else {
             return Vec::new();
         };
 
         let mut last_last = None;
 
-        osu_objects_iter
+        osu_objects
+            .take_while(|_| !unlikely(cancellation.is_cancelled()))
             .enumerate()
             .map(|(idx, h)| {
                 let diff_object = OsuDifficultyObject::new(
-                    h.get_ref(),
-                    last.get_ref(),
-                    last_last.as_deref(),
+                    h,
+                    last,
+                    last_last,
                     clock_rate,
                     idx,
                     scaling_factor,
@@ -284,3 +498,18 @@ impl DifficultyValues {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geometry_key_ignores_clock_rate() {
+        let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+        let key = |difficulty: Difficulty| GeometryKey::new(&difficulty, &map);
+
+        assert!(key(Difficulty::new()) == key(Difficulty::new().clock_rate(1.5)));
+        assert!(key(Difficulty::new()) == key(Difficulty::new().mods(8)));
+        assert!(key(Difficulty::new()) != key(Difficulty::new().mods(16)));
+    }
+}
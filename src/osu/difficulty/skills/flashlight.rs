@@ -6,11 +6,15 @@ use crate::{
         skills::strain_decay,
     },
     osu::{difficulty::object::OsuDifficultyObject, object::OsuObjectKind},
-    util::strains_vec::StrainsVec,
+    util::{
+        codec::{Decode, Encode},
+        strains_vec::StrainsVec,
+    },
     GameMods,
 };
 
 define_skill! {
+    #[derive(Clone)]
     pub struct Flashlight: StrainSkill => [OsuDifficultyObject<'a>][OsuDifficultyObject<'a>] {
         current_strain: f64,
         has_hidden_mod: bool,
@@ -74,12 +78,31 @@ impl Flashlight {
     }
 }
 
+#[derive(Clone)]
 struct FlashlightEvaluator {
     scaling_factor: f64,
     time_preempt: f64,
     time_fade_in: f64,
 }
 
+impl Encode for FlashlightEvaluator {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.scaling_factor.encode(buf);
+        self.time_preempt.encode(buf);
+        self.time_fade_in.encode(buf);
+    }
+}
+
+impl Decode for FlashlightEvaluator {
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        Some(Self {
+            scaling_factor: f64::decode(bytes)?,
+            time_preempt: f64::decode(bytes)?,
+            time_fade_in: f64::decode(bytes)?,
+        })
+    }
+}
+
 impl FlashlightEvaluator {
     const MAX_OPACITY_BONUS: f64 = 0.4;
     const HIDDEN_BONUS: f64 = 0.2;
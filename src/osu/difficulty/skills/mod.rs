@@ -2,6 +2,7 @@ use crate::{
     any::difficulty::skills::StrainSkill,
     model::{beatmap::BeatmapAttributes, mods::GameMods},
     osu::object::OsuObject,
+    util::codec::{Decode, Encode},
 };
 
 use self::{aim::Aim, flashlight::Flashlight, speed::Speed};
@@ -15,6 +16,7 @@ pub mod flashlight;
 pub mod speed;
 pub mod strain;
 
+#[derive(Clone)]
 pub struct OsuSkills {
     pub aim: Aim,
     pub aim_no_sliders: Aim,
@@ -65,3 +67,23 @@ impl OsuSkills {
         self.flashlight.process(curr, objects);
     }
 }
+
+impl Encode for OsuSkills {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.aim.encode(buf);
+        self.aim_no_sliders.encode(buf);
+        self.speed.encode(buf);
+        self.flashlight.encode(buf);
+    }
+}
+
+impl Decode for OsuSkills {
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        Some(Self {
+            aim: Aim::decode(bytes)?,
+            aim_no_sliders: Aim::decode(bytes)?,
+            speed: Speed::decode(bytes)?,
+            flashlight: Flashlight::decode(bytes)?,
+        })
+    }
+}
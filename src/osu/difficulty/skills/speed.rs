@@ -214,11 +214,10 @@ impl RhythmEvaluator {
 
         while curr
             .previous(rhythm_start, diff_objects)
-            .filter(|prev| {
+            .is_some_and(|prev| {
                 rhythm_start + 2 < historical_note_count
                     && curr.start_time - prev.start_time < f64::from(Self::HISTORY_TIME_MAX)
             })
-            .is_some()
         {
             rhythm_start += 1;
         }
@@ -0,0 +1,222 @@
+use rosu_map::util::Pos;
+
+use crate::{util::random::csharp::Random as CsharpRandom, Difficulty};
+
+use super::{
+    object::{OsuObject, OsuObjectKind},
+    PLAYFIELD_BASE_SIZE,
+};
+
+/// Object transforms of visual mods that change where objects are located
+/// at the time they have to be hit.
+///
+/// Most visual mods only change how objects appear before they have to be
+/// hit, e.g. Transform, Spin In, Grow, or Deflate, so their objects end up
+/// at their original position and are not simulated. Magnetised depends on
+/// the cursor position and is thus not deterministic.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ModGeometry {
+    wiggle_strength: Option<f64>,
+    /// Rotations per minute; negative for counterclockwise rotations.
+    barrel_roll_speed: Option<f64>,
+}
+
+impl ModGeometry {
+    const WIGGLE_DURATION: f64 = 100.0;
+    const WIGGLE_DIST: f64 = 7.0;
+
+    pub fn new(difficulty: &Difficulty) -> Option<Self> {
+        if !difficulty.get_simulate_mod_geometry() {
+            return None;
+        }
+
+        let mods = difficulty.get_mods();
+
+        let this = Self {
+            wiggle_strength: mods.wiggle_strength(),
+            barrel_roll_speed: mods.barrel_roll_speed(),
+        };
+
+        (this.wiggle_strength.is_some() || this.barrel_roll_speed.is_some()).then_some(this)
+    }
+
+    /// Moves the object and its nested objects.
+    ///
+    /// Must be called after stacking was applied and the slider's lazy end
+    /// position was finalized.
+    pub fn apply(self, h: &mut OsuObject, time_preempt: f64) {
+        let wiggle = self
+            .wiggle_strength
+            .filter(|_| !h.is_spinner())
+            .map(|strength| Wiggle::new(h, strength, time_preempt));
+
+        let transform = |pos: Pos, time: f64| {
+            let mut pos = pos;
+
+            if let Some(ref wiggle) = wiggle {
+                pos += wiggle.offset_at(time);
+            }
+
+            if let Some(speed) = self.barrel_roll_speed {
+                pos = barrel_roll(pos, time, speed);
+            }
+
+            pos
+        };
+
+        let stack_offset = h.stack_offset;
+        h.pos = transform(h.pos + stack_offset, h.start_time) - stack_offset;
+
+        if let OsuObjectKind::Slider(ref mut slider) = h.kind {
+            for nested in slider.nested_objects.iter_mut() {
+                nested.pos = transform(nested.pos + stack_offset, nested.start_time) - stack_offset;
+            }
+
+            // Already contains the stack offset
+            slider.lazy_end_pos =
+                transform(slider.lazy_end_pos, h.start_time + slider.lazy_travel_time);
+        }
+    }
+}
+
+/// Rotates the position around the playfield's center.
+fn barrel_roll(pos: Pos, time: f64, speed: f64) -> Pos {
+    let center = PLAYFIELD_BASE_SIZE / 2.0;
+    let degrees = 360.0 * (time / 60_000.0 * speed) as f32;
+    let (sin, cos) = degrees.to_radians().sin_cos();
+
+    let Pos { x, y } = pos - center;
+
+    Pos::new(x * cos - y * sin, x * sin + y * cos) + center
+}
+
+/// The wiggle movements of an object.
+struct Wiggle {
+    /// Start time and target offset of each movement.
+    moves: Vec<(f64, Pos)>,
+}
+
+impl Wiggle {
+    fn new(h: &OsuObject, strength: f64, time_preempt: f64) -> Self {
+        let mut rng = CsharpRandom::new(h.start_time as i32);
+
+        let mut next_offset = || {
+            let angle = (rng.next_double() * 2.0 * std::f64::consts::PI) as f32;
+            let dist = (rng.next_double() * strength * ModGeometry::WIGGLE_DIST) as f32;
+
+            Pos::new(dist * angle.cos(), dist * angle.sin())
+        };
+
+        // * Wiggle all objects during TimePreempt
+        let preempt_wiggles = (time_preempt as i32) / ModGeometry::WIGGLE_DURATION as i32;
+        let preempt_start = h.start_time - time_preempt;
+
+        let mut moves: Vec<_> = (0..preempt_wiggles)
+            .map(|i| {
+                let start = preempt_start + f64::from(i) * ModGeometry::WIGGLE_DURATION;
+
+                (start, next_offset())
+            })
+            .collect();
+
+        // * Keep wiggling sliders and spinners for their duration
+        if let OsuObjectKind::Slider(ref slider) = h.kind {
+            let duration = slider.end_time - h.start_time;
+            let duration_wiggles = (duration / ModGeometry::WIGGLE_DURATION) as i32;
+
+            moves.extend((0..duration_wiggles).map(|i| {
+                let start = h.start_time + f64::from(i) * ModGeometry::WIGGLE_DURATION;
+
+                (start, next_offset())
+            }));
+        }
+
+        Self { moves }
+    }
+
+    fn offset_at(&self, time: f64) -> Pos {
+        let mut curr = Pos::default();
+
+        for &(start, target) in self.moves.iter() {
+            if time >= start + ModGeometry::WIGGLE_DURATION {
+                curr = target;
+            } else {
+                if time > start {
+                    let progress = ((time - start) / ModGeometry::WIGGLE_DURATION) as f32;
+                    curr += (target - curr) * progress;
+                }
+
+                break;
+            }
+        }
+
+        curr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rosu_mods::{
+        generated_mods::{BarrelRollOsu, WiggleOsu},
+        GameMod, GameMods as GameModsLazer,
+    };
+
+    use crate::{osu::Osu, Beatmap};
+
+    use super::*;
+
+    #[test]
+    fn barrel_roll_rotation() {
+        let pos = Pos::new(356.0, 192.0);
+
+        let same = barrel_roll(pos, 0.0, 0.5);
+        assert!((same - pos).length() < 1e-3);
+
+        // A quarter rotation clockwise moves right of the center to below it
+        let rotated = barrel_roll(pos, 30_000.0, 0.5);
+        assert!((rotated - Pos::new(256.0, 292.0)).length() < 1e-3);
+
+        let rotated = barrel_roll(pos, 30_000.0, -0.5);
+        assert!((rotated - Pos::new(256.0, 92.0)).length() < 1e-3);
+    }
+
+    #[test]
+    fn opt_in() {
+        let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+
+        for gamemod in [
+            GameMod::WiggleOsu(WiggleOsu { strength: None }),
+            GameMod::BarrelRollOsu(BarrelRollOsu {
+                spin_speed: Some(2.0),
+                direction: None,
+            }),
+        ] {
+            let mods: GameModsLazer = [gamemod].into_iter().collect();
+            let difficulty = Difficulty::new().mods(mods);
+
+            let raw = difficulty.calculate_for_mode::<Osu>(&map).unwrap();
+
+            let simulated = difficulty
+                .clone()
+                .simulate_mod_geometry(true)
+                .calculate_for_mode::<Osu>(&map)
+                .unwrap();
+
+            assert!((raw.aim - simulated.aim).abs() > f64::EPSILON);
+            assert_eq!(raw.max_combo, simulated.max_combo);
+
+            let disabled = difficulty
+                .simulate_mod_geometry(false)
+                .calculate_for_mode::<Osu>(&map)
+                .unwrap();
+
+            assert_eq!(raw, disabled);
+        }
+    }
+
+    #[test]
+    fn no_geometry_mods() {
+        let difficulty = Difficulty::new().simulate_mod_geometry(true);
+        assert!(ModGeometry::new(&difficulty).is_none());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
     Beatmap,
+    any::difficulty::cancellation::Cancellation,
     model::{
         mods::GameMods,
         hit_object::Spinner
@@ -52,9 +53,11 @@ impl OsuLegacyScoreSimulator {
             beatmap,
             &scaling_factor,
             mods.reflection(),
+            None,
             time_preempt,
             beatmap.hit_objects.len(),
             &mut attrs,
+            &Cancellation::NONE,
         );
 
         let mut attributes = OsuLegacyScoreAttributes::default();
@@ -4,6 +4,7 @@ use rust_decimal_macros::dec;
 
 use crate::{
     Beatmap,
+    any::difficulty::cancellation::Cancellation,
     model::mods::GameMods,
     osu::{
         object::{OsuObjectKind, NestedSliderObjectKind},
@@ -38,9 +39,11 @@ pub fn calculate_nested_score_per_object(beatmap: &Beatmap, mods: &GameMods) ->
         beatmap,
         &scaling_factor,
         mods.reflection(),
+        None,
         time_preempt,
         beatmap.hit_objects.len(),
         &mut attrs,
+        &Cancellation::NONE,
     );
 
     let mut amount_of_big_ticks = 0;
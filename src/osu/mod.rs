@@ -11,17 +11,23 @@ use crate::{
 pub use self::{
     attributes::{OsuDifficultyAttributes, OsuPerformanceAttributes},
     difficulty::gradual::OsuGradualDifficulty,
-    performance::{gradual::OsuGradualPerformance, OsuPerformance},
+    performance::{
+        gradual::{OsuGradualPerformance, OsuGradualStep},
+        OsuPerformance,
+    },
     score_state::{OsuScoreOrigin, OsuScoreState},
+    skillsets::{OsuSkillset, OsuSkillsetSection, OsuSkillsets},
     strains::OsuStrains,
 };
 
 mod attributes;
 mod convert;
 mod difficulty;
+mod geometry;
 mod object;
 mod performance;
 mod score_state;
+mod skillsets;
 mod strains;
 mod legacy;
 
@@ -32,6 +38,31 @@ const PLAYFIELD_BASE_SIZE: Pos = Pos::new(512.0, 384.0);
 /// [`GameMode::Osu`]: rosu_map::section::general::GameMode::Osu
 pub struct Osu;
 
+impl Osu {
+    pub(crate) fn difficulty_many(
+        difficulties: &[Difficulty],
+        map: &Beatmap,
+    ) -> Result<Vec<OsuDifficultyAttributes>, ConvertError> {
+        let values = difficulty::difficulty_many(difficulties, map)?;
+
+        Ok(values.into_iter().map(|values| values.attrs).collect())
+    }
+
+    pub(crate) fn difficulty_many_with_strains(
+        difficulties: &[Difficulty],
+        map: &Beatmap,
+    ) -> Result<Vec<(OsuDifficultyAttributes, OsuStrains)>, ConvertError> {
+        let values = difficulty::difficulty_many(difficulties, map)?;
+
+        let attrs_and_strains = values
+            .into_iter()
+            .map(|values| (values.attrs, OsuStrains::new(values.skills)))
+            .collect();
+
+        Ok(attrs_and_strains)
+    }
+}
+
 impl IGameMode for Osu {
     type DifficultyAttributes = OsuDifficultyAttributes;
     type Strains = OsuStrains;
@@ -1,4 +1,11 @@
-use crate::{model::mode::ConvertError, osu::OsuGradualDifficulty, Beatmap, Difficulty};
+use std::cmp;
+
+use crate::{
+    any::ResumeError,
+    model::mode::ConvertError,
+    osu::{Osu, OsuDifficultyAttributes, OsuGradualDifficulty, OsuScoreOrigin},
+    Beatmap, Difficulty,
+};
 
 use super::{OsuPerformanceAttributes, OsuScoreState};
 
@@ -77,6 +84,8 @@ use super::{OsuPerformanceAttributes, OsuScoreState};
 pub struct OsuGradualPerformance {
     lazer: bool,
     difficulty: OsuGradualDifficulty,
+    /// Difficulty attributes of the whole map, calculated on demand.
+    final_attrs: Option<OsuDifficultyAttributes>,
 }
 
 impl OsuGradualPerformance {
@@ -85,7 +94,11 @@ impl OsuGradualPerformance {
         let lazer = difficulty.get_lazer();
         let difficulty = OsuGradualDifficulty::new(difficulty, map)?;
 
-        Ok(Self { lazer, difficulty })
+        Ok(Self {
+            lazer,
+            difficulty,
+            final_attrs: None,
+        })
     }
 
     /// Process the next hit object and calculate the performance attributes
@@ -121,6 +134,158 @@ impl OsuGradualPerformance {
         Some(performance)
     }
 
+    /// Same as [`OsuGradualPerformance::next`] but also provides running
+    /// estimates, see [`OsuGradualStep`].
+    ///
+    /// `combo` is the current combo, as opposed to the maximum combo of the
+    /// score state.
+    pub fn next_step(&mut self, state: OsuScoreState, combo: u32) -> Option<OsuGradualStep> {
+        self.nth_step(state, combo, 0)
+    }
+
+    /// Same as [`OsuGradualPerformance::nth`] but also provides running
+    /// estimates, see [`OsuGradualStep`].
+    ///
+    /// `combo` is the current combo, as opposed to the maximum combo of the
+    /// score state.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn nth_step(
+        &mut self,
+        state: OsuScoreState,
+        combo: u32,
+        n: usize,
+    ) -> Option<OsuGradualStep> {
+        let performance = self.nth(state.clone(), n)?;
+        let projected_pp = self.projected_pp(&state, combo, &performance.difficulty);
+
+        Some(OsuGradualStep {
+            performance,
+            projected_pp,
+        })
+    }
+
+    /// The pp of the whole map if all remaining objects are hit at the
+    /// current accuracy without breaking combo.
+    fn projected_pp(
+        &mut self,
+        state: &OsuScoreState,
+        combo: u32,
+        curr: &OsuDifficultyAttributes,
+    ) -> f64 {
+        let difficulty = &self.difficulty.difficulty;
+        let map = &self.difficulty.map;
+
+        let final_attrs = self.final_attrs.get_or_insert_with(|| {
+            difficulty
+                .calculate_for_mode::<Osu>(map)
+                .expect("no conversion required")
+        });
+
+        let origin = match (
+            self.lazer,
+            difficulty.get_mods().no_slider_head_acc(self.lazer),
+        ) {
+            (false, _) => OsuScoreOrigin::Stable,
+            (true, false) => OsuScoreOrigin::WithSliderAcc {
+                max_large_ticks: curr.n_large_ticks,
+                max_slider_ends: curr.n_sliders,
+            },
+            (true, true) => OsuScoreOrigin::WithoutSliderAcc {
+                max_large_ticks: curr.n_sliders + curr.n_large_ticks,
+                max_small_ticks: curr.n_sliders,
+            },
+        };
+
+        let remaining_combo = final_attrs.max_combo.saturating_sub(curr.max_combo);
+        let max_combo = cmp::max(state.max_combo, combo + remaining_combo);
+
+        let mut performance = final_attrs
+            .clone()
+            .performance()
+            .lazer(self.lazer)
+            .difficulty(difficulty.clone())
+            .misses(state.misses)
+            .combo(cmp::min(max_combo, final_attrs.max_combo));
+
+        if state.total_hits() > 0 {
+            performance = performance.accuracy(state.accuracy(origin) * 100.0);
+        }
+
+        performance.calculate().expect("no conversion required").pp
+    }
+
+    /// Process all objects that are judged at or before `time`, i.e. the
+    /// current playback position in milliseconds, and calculate the
+    /// performance attributes for the resulting score state.
+    ///
+    /// See [`OsuGradualDifficulty::advance_to_time`].
+    #[allow(clippy::missing_panics_doc)]
+    pub fn advance_to_time(
+        &mut self,
+        state: OsuScoreState,
+        time: f64,
+    ) -> Option<OsuPerformanceAttributes> {
+        let performance = self
+            .difficulty
+            .advance_to_time(time)?
+            .performance()
+            .lazer(self.lazer)
+            .state(state)
+            .difficulty(self.difficulty.difficulty.clone())
+            .passed_objects(self.difficulty.idx as u32)
+            .calculate()
+            .expect("no conversion required");
+
+        Some(performance)
+    }
+
+    /// Take a snapshot of the calculator's state every `interval` processed
+    /// objects so that [`OsuGradualPerformance::seek`] can rewind without
+    /// starting over.
+    ///
+    /// See [`OsuGradualDifficulty::checkpoint_interval`].
+    #[must_use]
+    pub fn checkpoint_interval(mut self, interval: usize) -> Self {
+        self.difficulty = self.difficulty.checkpoint_interval(interval);
+
+        self
+    }
+
+    /// Move to the state after processing `idx` objects so that the next call
+    /// of [`OsuGradualPerformance::next`] processes the object at index `idx`.
+    ///
+    /// See [`OsuGradualDifficulty::seek`].
+    pub fn seek(&mut self, idx: usize) {
+        self.difficulty.seek(idx);
+    }
+
+    /// Serialize the current state so that it can be resumed through
+    /// [`OsuGradualPerformance::resume`], e.g. in another process.
+    ///
+    /// See [`OsuGradualDifficulty::save_state`].
+    pub fn save_state(&self) -> Vec<u8> {
+        self.difficulty.save_state()
+    }
+
+    /// Resume a state that was serialized through
+    /// [`OsuGradualPerformance::save_state`].
+    ///
+    /// See [`OsuGradualDifficulty::resume`].
+    pub fn resume(
+        difficulty: Difficulty,
+        map: &Beatmap,
+        state: &[u8],
+    ) -> Result<Self, ResumeError> {
+        let lazer = difficulty.get_lazer();
+        let difficulty = OsuGradualDifficulty::resume(difficulty, map, state)?;
+
+        Ok(Self {
+            lazer,
+            difficulty,
+            final_attrs: None,
+        })
+    }
+
     /// Returns the amount of remaining objects.
     #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
@@ -128,6 +293,29 @@ impl OsuGradualPerformance {
     }
 }
 
+/// The result of an [`OsuGradualPerformance`] step alongside running
+/// estimates of the score.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OsuGradualStep {
+    /// The performance attributes of the current score state.
+    pub performance: OsuPerformanceAttributes,
+    /// The pp of the whole map if all remaining objects are hit at the
+    /// current accuracy without breaking combo.
+    pub projected_pp: f64,
+}
+
+impl OsuGradualStep {
+    /// The current estimate of misses including slider breaks.
+    pub const fn effective_miss_count(&self) -> f64 {
+        self.performance.effective_miss_count
+    }
+
+    /// The current estimate of the deviation on circle hits.
+    pub const fn speed_deviation(&self) -> Option<f64> {
+        self.performance.speed_deviation
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{osu::OsuPerformance, Beatmap};
@@ -181,4 +369,72 @@ mod tests {
             assert_eq!(next_gradual, expected);
         }
     }
+
+    #[test]
+    fn steps() {
+        let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+        let difficulty = Difficulty::new().mods(8).lazer(false); // HD
+
+        let mut gradual = OsuGradualPerformance::new(difficulty.clone(), &map).unwrap();
+        let mut state = OsuScoreState::default();
+        let mut last = None;
+
+        loop {
+            state.n300 += 1;
+
+            let Some(step) = gradual.next_step(state.clone(), 0) else {
+                break;
+            };
+
+            assert_eq!(step.speed_deviation(), step.performance.speed_deviation);
+
+            last = Some(step);
+        }
+
+        let last = last.unwrap();
+        state.n300 -= 1;
+
+        let expected = OsuPerformance::new(&map)
+            .difficulty(difficulty)
+            .state(state)
+            .calculate()
+            .unwrap();
+
+        assert_eq!(last.performance.difficulty, expected.difficulty);
+        assert!((last.projected_pp - expected.pp).abs() < 1e-9);
+    }
+
+    #[test]
+    fn projected_pp_after_combo_break() {
+        let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+        let difficulty = Difficulty::new().lazer(false);
+
+        let mut gradual = OsuGradualPerformance::new(difficulty.clone(), &map).unwrap();
+
+        // Combo broke once and has been rebuilt to 60 since
+        let state = OsuScoreState {
+            max_combo: 150,
+            n300: 149,
+            misses: 1,
+            ..Default::default()
+        };
+
+        let combo = 60;
+        let step = gradual.nth_step(state.clone(), combo, 149).unwrap();
+
+        let final_attrs = difficulty.calculate_for_mode::<Osu>(&map).unwrap();
+        let remaining_combo = final_attrs.max_combo - step.performance.difficulty.max_combo;
+
+        let expected = final_attrs
+            .performance()
+            .lazer(false)
+            .difficulty(difficulty)
+            .misses(1)
+            .combo(combo + remaining_combo)
+            .accuracy(state.accuracy(OsuScoreOrigin::Stable) * 100.0)
+            .calculate()
+            .unwrap();
+
+        assert!((step.projected_pp - expected.pp).abs() < 1e-9);
+    }
 }
@@ -170,6 +170,16 @@ impl<'map> OsuPerformance<'map> {
         self
     }
 
+    /// Whether objects should be moved the way visual mods such as Wiggle or
+    /// Barrel Roll move them in osu!lazer before evaluating difficulty.
+    ///
+    /// Defaults to `false`.
+    pub fn simulate_mod_geometry(mut self, simulate_mod_geometry: bool) -> Self {
+        self.difficulty = self.difficulty.simulate_mod_geometry(simulate_mod_geometry);
+
+        self
+    }
+
     /// Specify the amount of "large tick" hits.
     ///
     /// The meaning depends on the kind of score:
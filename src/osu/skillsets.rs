@@ -0,0 +1,403 @@
+use std::{f64::consts::PI, iter};
+
+use rosu_map::section::general::GameMode;
+
+use crate::{model::mode::ConvertError, Beatmap, Difficulty};
+
+use super::difficulty::{object::OsuDifficultyObject, DifficultyValues};
+
+/// A skillset that sections of an osu!standard map can be classified as.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum OsuSkillset {
+    /// At least [`OsuSkillsets::MIN_STREAM_LEN`] consecutive notes in quick
+    /// succession.
+    Stream,
+    /// Fewer than [`OsuSkillsets::MIN_STREAM_LEN`] but at least three
+    /// consecutive notes in quick succession.
+    Burst,
+    /// Far spaced notes.
+    Jump,
+    /// Far spaced notes that move back and forth i.e. at an acute angle.
+    AlternatingJump,
+    /// Long and fast sliders.
+    SliderAim,
+    /// Notes with frequently changing angles and velocities.
+    Tech,
+}
+
+impl OsuSkillset {
+    /// All skillsets.
+    pub const ALL: [Self; 6] = [
+        Self::Stream,
+        Self::Burst,
+        Self::Jump,
+        Self::AlternatingJump,
+        Self::SliderAim,
+        Self::Tech,
+    ];
+
+    /// The name of the skillset in lowercase, e.g. to use it as tag.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Stream => "stream",
+            Self::Burst => "burst",
+            Self::Jump => "jump",
+            Self::AlternatingJump => "alternating_jump",
+            Self::SliderAim => "slider_aim",
+            Self::Tech => "tech",
+        }
+    }
+}
+
+/// A section of consecutive time windows with the same dominant skillset.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OsuSkillsetSection {
+    /// The start time of the section in milliseconds.
+    pub start_time: f64,
+    /// The end time of the section in milliseconds.
+    pub end_time: f64,
+    /// The dominant skillset of the section.
+    ///
+    /// `None` if no object within the section could be classified.
+    pub skillset: Option<OsuSkillset>,
+}
+
+/// Skillset classification of an osu!standard map.
+///
+/// Objects are classified based on the same difficulty objects that the aim
+/// and speed skills process, i.e. normalized jump distances, angles, slider
+/// travel distances, and strain times. All times are adjusted to the clock
+/// rate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OsuSkillsets {
+    /// The skillset of each hit object.
+    ///
+    /// `None` for objects that fit no skillset.
+    pub labels: Vec<Option<OsuSkillset>>,
+    /// Sections of the map with the same dominant skillset.
+    pub sections: Vec<OsuSkillsetSection>,
+}
+
+impl OsuSkillsets {
+    /// Length in ms of the time windows whose dominant skillsets are merged
+    /// into [`OsuSkillsetSection`]s.
+    pub const SECTION_LEN: f64 = 2000.0;
+
+    /// Minimum amount of notes for a [`OsuSkillset::Stream`].
+    pub const MIN_STREAM_LEN: usize = 9;
+
+    /// Maximum strain time between two stream notes, ~135 BPM 1/4th.
+    const STREAM_DELTA_TIME: f64 = 111.0;
+    /// Maximum strain time between two jump notes, 100 BPM 1/2th.
+    const JUMP_DELTA_TIME: f64 = 300.0;
+    /// 1.5 circles distance between centers.
+    const JUMP_DIST: f64 = OsuDifficultyObject::NORMALIZED_DIAMETER as f64 * 1.5;
+    const ALTERNATING_ANGLE: f64 = PI / 3.0;
+    const SLIDER_TRAVEL_DIST: f64 = OsuDifficultyObject::NORMALIZED_DIAMETER as f64 * 1.5;
+    const SLIDER_VELOCITY: f64 = 0.5;
+    /// Amount of previous objects to consider for tech.
+    const TECH_WINDOW: usize = 8;
+    /// Minimum coefficient of variation of the object velocities for tech.
+    const TECH_VELOCITY_VARIATION: f64 = 0.5;
+    /// Minimum standard deviation of angles in radians for tech.
+    const TECH_ANGLE_VARIATION: f64 = 0.6;
+
+    /// Classify the skillsets of a map.
+    ///
+    /// Only the mods, clock rate, and passed objects of the [`Difficulty`]
+    /// are relevant.
+    pub fn new(difficulty: &Difficulty, map: &Beatmap) -> Result<Self, ConvertError> {
+        let map = map.convert_ref(GameMode::Osu, difficulty.get_mods())?;
+
+        let n_objects = map.hit_objects.len().min(difficulty.get_passed_objects());
+
+        let this = DifficultyValues::with_difficulty_objects(difficulty, &map, |diff_objects| {
+            let mut labels = vec![None; n_objects];
+
+            for (label, skillset) in labels.iter_mut().zip(classify(diff_objects)) {
+                *label = skillset;
+            }
+
+            let sections = sections(diff_objects, &labels);
+
+            Self { labels, sections }
+        });
+
+        Ok(this)
+    }
+
+    /// The share of hit objects that are classified as the given skillset.
+    pub fn share(&self, skillset: OsuSkillset) -> f64 {
+        if self.labels.is_empty() {
+            return 0.0;
+        }
+
+        let count = self
+            .labels
+            .iter()
+            .filter(|label| **label == Some(skillset))
+            .count();
+
+        count as f64 / self.labels.len() as f64
+    }
+
+    /// All skillsets whose share is at least `min_share`, sorted by their
+    /// share in descending order.
+    pub fn tags(&self, min_share: f64) -> Vec<OsuSkillset> {
+        let mut tags: Vec<_> = OsuSkillset::ALL
+            .into_iter()
+            .map(|skillset| (skillset, self.share(skillset)))
+            .filter(|(_, share)| *share > 0.0 && *share >= min_share)
+            .collect();
+
+        tags.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+        tags.into_iter().map(|(skillset, _)| skillset).collect()
+    }
+}
+
+/// Classify each hit object based on the difficulty objects.
+fn classify(diff_objects: &[OsuDifficultyObject<'_>]) -> Vec<Option<OsuSkillset>> {
+    // The first hit object has no difficulty object
+    let mut labels = vec![None; diff_objects.len() + 1];
+
+    // Streams and bursts
+    let mut i = 0;
+
+    while i < diff_objects.len() {
+        let len = diff_objects[i..]
+            .iter()
+            .take_while(|curr| is_stream_note(curr))
+            .count();
+
+        // The note before the first difficulty object leads into the run so
+        // the run spans the hit objects `i..=i + len`
+        let skillset = match len + 1 {
+            n if n >= OsuSkillsets::MIN_STREAM_LEN => Some(OsuSkillset::Stream),
+            n if n >= 3 => Some(OsuSkillset::Burst),
+            _ => None,
+        };
+
+        if skillset.is_some() {
+            labels[i..=i + len].fill(skillset);
+        }
+
+        i += len.max(1);
+    }
+
+    for (i, (curr, label)) in diff_objects.iter().zip(&mut labels[1..]).enumerate() {
+        if label.is_some() || curr.base.is_spinner() {
+            continue;
+        }
+
+        *label = if is_slider_aim(curr) {
+            Some(OsuSkillset::SliderAim)
+        } else if is_tech(&diff_objects[i.saturating_sub(OsuSkillsets::TECH_WINDOW - 1)..=i]) {
+            Some(OsuSkillset::Tech)
+        } else if is_jump(curr) {
+            match curr.angle {
+                Some(angle) if angle < OsuSkillsets::ALTERNATING_ANGLE => {
+                    Some(OsuSkillset::AlternatingJump)
+                }
+                _ => Some(OsuSkillset::Jump),
+            }
+        } else {
+            None
+        };
+    }
+
+    labels
+}
+
+fn is_stream_note(curr: &OsuDifficultyObject<'_>) -> bool {
+    !curr.base.is_spinner() && curr.adjusted_delta_time <= OsuSkillsets::STREAM_DELTA_TIME
+}
+
+fn is_jump(curr: &OsuDifficultyObject<'_>) -> bool {
+    curr.lazy_jump_dist >= OsuSkillsets::JUMP_DIST
+        && curr.adjusted_delta_time <= OsuSkillsets::JUMP_DELTA_TIME
+}
+
+fn is_slider_aim(curr: &OsuDifficultyObject<'_>) -> bool {
+    curr.base.is_slider()
+        && curr.travel_dist >= OsuSkillsets::SLIDER_TRAVEL_DIST
+        && curr.travel_dist / curr.travel_time >= OsuSkillsets::SLIDER_VELOCITY
+}
+
+/// Whether the velocities and angles within the window vary enough.
+fn is_tech(window: &[OsuDifficultyObject<'_>]) -> bool {
+    if window.len() < OsuSkillsets::TECH_WINDOW {
+        return false;
+    }
+
+    let velocities: Vec<_> = window
+        .iter()
+        .map(|curr| curr.lazy_jump_dist / curr.adjusted_delta_time)
+        .collect();
+
+    let angles: Vec<_> = window.iter().filter_map(|curr| curr.angle).collect();
+
+    let (velocity_mean, velocity_deviation) = mean_and_deviation(&velocities);
+    let (_, angle_deviation) = mean_and_deviation(&angles);
+
+    velocity_mean > 0.0
+        && velocity_deviation / velocity_mean >= OsuSkillsets::TECH_VELOCITY_VARIATION
+        && angle_deviation >= OsuSkillsets::TECH_ANGLE_VARIATION
+}
+
+fn mean_and_deviation(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    let len = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / len;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / len;
+
+    (mean, variance.sqrt())
+}
+
+/// Merge the dominant skillsets of consecutive time windows.
+fn sections(
+    diff_objects: &[OsuDifficultyObject<'_>],
+    labels: &[Option<OsuSkillset>],
+) -> Vec<OsuSkillsetSection> {
+    let Some(first) = diff_objects.first() else {
+        return Vec::new();
+    };
+
+    let section_start = first.start_time - first.delta_time;
+    let mut sections: Vec<OsuSkillsetSection> = Vec::new();
+    let mut counts = [0_usize; OsuSkillset::ALL.len()];
+    let mut window = 0;
+
+    let mut push = |window: usize, counts: &mut [usize; OsuSkillset::ALL.len()]| {
+        // On ties, prefer the skillset that comes first
+        let mut dominant: Option<(usize, OsuSkillset)> = None;
+
+        for (count, skillset) in counts.iter().copied().zip(OsuSkillset::ALL) {
+            if count > 0 && dominant.is_none_or(|(max, _)| count > max) {
+                dominant = Some((count, skillset));
+            }
+        }
+
+        let skillset = dominant.map(|(_, skillset)| skillset);
+
+        let start_time = section_start + window as f64 * OsuSkillsets::SECTION_LEN;
+        let end_time = start_time + OsuSkillsets::SECTION_LEN;
+
+        match sections.last_mut() {
+            Some(last) if last.skillset == skillset => last.end_time = end_time,
+            _ => sections.push(OsuSkillsetSection {
+                start_time,
+                end_time,
+                skillset,
+            }),
+        }
+
+        *counts = [0; OsuSkillset::ALL.len()];
+    };
+
+    let start_times = diff_objects.iter().map(|curr| curr.start_time);
+
+    for (start_time, label) in iter::once(section_start).chain(start_times).zip(labels) {
+        let curr_window = ((start_time - section_start) / OsuSkillsets::SECTION_LEN) as usize;
+
+        while window < curr_window {
+            push(window, &mut counts);
+            window += 1;
+        }
+
+        if let Some(skillset) = label {
+            counts[*skillset as usize] += 1;
+        }
+    }
+
+    push(window, &mut counts);
+
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use rosu_map::util::Pos;
+
+    use crate::model::beatmap::BeatmapBuilder;
+
+    use super::*;
+
+    fn classify_map(map: &Beatmap) -> OsuSkillsets {
+        OsuSkillsets::new(&Difficulty::new(), map).unwrap()
+    }
+
+    #[test]
+    fn stream_and_burst() {
+        let mut builder = BeatmapBuilder::new().timing_point(0.0, 400.0);
+
+        // 12 notes at 150 BPM 1/4th
+        for i in 0..12 {
+            let pos = Pos::new(200.0 + 10.0 * i as f32, 200.0);
+            builder = builder.circle(pos, 1000.0 + 100.0 * f64::from(i));
+        }
+
+        // 4 notes at 150 BPM 1/4th after a pause
+        for i in 0..4 {
+            let pos = Pos::new(200.0, 200.0 + 10.0 * i as f32);
+            builder = builder.circle(pos, 5000.0 + 100.0 * f64::from(i));
+        }
+
+        let skillsets = classify_map(&builder.build().unwrap());
+
+        assert_eq!(skillsets.labels.len(), 16);
+        assert!(skillsets.labels[..12]
+            .iter()
+            .all(|label| *label == Some(OsuSkillset::Stream)));
+        assert!(skillsets.labels[12..]
+            .iter()
+            .all(|label| *label == Some(OsuSkillset::Burst)));
+        assert_eq!(
+            skillsets.tags(0.1),
+            [OsuSkillset::Stream, OsuSkillset::Burst]
+        );
+        assert_eq!(skillsets.sections[0].skillset, Some(OsuSkillset::Stream));
+    }
+
+    #[test]
+    fn alternating_jumps() {
+        let mut builder = BeatmapBuilder::new().timing_point(0.0, 400.0);
+
+        for i in 0..16 {
+            let x = if i % 2 == 0 { 50.0 } else { 450.0 };
+            builder = builder.circle(Pos::new(x, 200.0), 1000.0 + 200.0 * f64::from(i));
+        }
+
+        let skillsets = classify_map(&builder.build().unwrap());
+
+        assert!(skillsets.share(OsuSkillset::AlternatingJump) > 0.8);
+        assert_eq!(skillsets.tags(0.5), [OsuSkillset::AlternatingJump]);
+        assert_eq!(skillsets.sections.len(), 1);
+    }
+
+    #[test]
+    fn real_map() {
+        let map = Beatmap::from_path("./resources/2785319.osu").unwrap();
+        let skillsets = classify_map(&map);
+
+        assert_eq!(skillsets.labels.len(), map.hit_objects.len());
+
+        let total: f64 = OsuSkillset::ALL
+            .into_iter()
+            .map(|skillset| skillsets.share(skillset))
+            .sum();
+
+        assert!(total > 0.0 && total <= 1.0);
+        assert!(skillsets
+            .sections
+            .windows(2)
+            .all(|w| (w[0].end_time - w[1].start_time).abs() < f64::EPSILON
+                && w[0].skillset != w[1].skillset));
+
+        let dt = OsuSkillsets::new(&Difficulty::new().mods(64), &map).unwrap();
+        assert!(dt.share(OsuSkillset::Stream) >= skillsets.share(OsuSkillset::Stream));
+    }
+}
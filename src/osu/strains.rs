@@ -22,26 +22,28 @@ pub struct OsuStrains {
 impl OsuStrains {
     /// Time between two strains in ms.
     pub const SECTION_LEN: f64 = 400.0;
+
+    pub(crate) fn new(skills: OsuSkills) -> Self {
+        let OsuSkills {
+            aim,
+            aim_no_sliders,
+            speed,
+            flashlight,
+        } = skills;
+
+        Self {
+            aim: aim.into_current_strain_peaks().into_vec(),
+            aim_no_sliders: aim_no_sliders.into_current_strain_peaks().into_vec(),
+            speed: speed.into_current_strain_peaks().into_vec(),
+            flashlight: flashlight.into_current_strain_peaks().into_vec(),
+        }
+    }
 }
 
 pub fn strains(difficulty: &Difficulty, map: &Beatmap) -> Result<OsuStrains, ConvertError> {
     let map = map.convert_ref(GameMode::Osu, difficulty.get_mods())?;
 
-    let DifficultyValues {
-        skills:
-            OsuSkills {
-                aim,
-                aim_no_sliders,
-                speed,
-                flashlight,
-            },
-        attrs: _,
-    } = DifficultyValues::calculate(difficulty, &map);
-
-    Ok(OsuStrains {
-        aim: aim.into_current_strain_peaks().into_vec(),
-        aim_no_sliders: aim_no_sliders.into_current_strain_peaks().into_vec(),
-        speed: speed.into_current_strain_peaks().into_vec(),
-        flashlight: flashlight.into_current_strain_peaks().into_vec(),
-    })
+    let DifficultyValues { skills, attrs: _ } = DifficultyValues::calculate(difficulty, &map);
+
+    Ok(OsuStrains::new(skills))
 }
@@ -8,13 +8,14 @@ use rosu_map::{
 };
 
 use crate::{
+    any::difficulty::cancellation::Cancellation,
     model::{
         beatmap::Beatmap,
         control_point::{DifficultyPoint, EffectPoint, TimingPoint},
         hit_object::{HitObject, HitObjectKind, HoldNote, Slider, Spinner},
     },
     util::{
-        float_ext::FloatExt, get_precision_adjusted_beat_len,
+        float_ext::FloatExt, get_precision_adjusted_beat_len, hint::unlikely,
         random::csharp::Random as CsharpRandom, sort::TandemSorter,
     },
 };
@@ -22,7 +23,7 @@ use crate::{
 const VELOCITY_MULTIPLIER: f32 = 1.4;
 const OSU_BASE_SCORING_DIST: f32 = 100.0;
 
-pub fn convert(map: &mut Beatmap) {
+pub fn convert(map: &mut Beatmap, cancellation: &Cancellation) {
     let mut new_objects = Vec::new();
     let mut new_sounds = Vec::new();
 
@@ -31,6 +32,10 @@ pub fn convert(map: &mut Beatmap) {
     let mut last_scroll_speed = 1.0;
 
     while idx < map.hit_objects.len() {
+        if unlikely(cancellation.is_cancelled()) {
+            break;
+        }
+
         match map.hit_objects[idx].kind {
             HitObjectKind::Circle | HitObjectKind::Spinner(_) => {}
             HitObjectKind::Slider(ref slider) => {
@@ -68,6 +73,10 @@ pub fn convert(map: &mut Beatmap) {
                     while j
                         <= obj.start_time + f64::from(params.duration) + params.tick_spacing / 8.0
                     {
+                        if unlikely(cancellation.is_cancelled()) {
+                            break;
+                        }
+
                         let h = HitObject {
                             pos: Pos::default(),
                             start_time: j,
@@ -3,10 +3,13 @@ use std::{cmp, mem, slice::Iter};
 use rosu_map::section::general::GameMode;
 
 use crate::{
-    any::difficulty::skills::StrainSkill,
+    any::difficulty::{
+        checkpoints::{Checkpoints, ResumeError, StateHeader, StateReader, StateWriter},
+        skills::StrainSkill,
+    },
     model::{beatmap::HitWindows, hit_object::HitObject, mode::ConvertError},
     taiko::convert,
-    util::sync::RefCount,
+    util::{judgement_times::JudgementTimes, sync::RefCount},
     Beatmap, Difficulty,
 };
 
@@ -51,14 +54,24 @@ use super::{
 pub struct TaikoGradualDifficulty {
     pub(crate) idx: usize,
     pub(crate) difficulty: Difficulty,
+    /// See [`StateHeader::map_hash`].
+    map_hash: u64,
     attrs: TaikoDifficultyAttributes,
     diff_objects: TaikoDifficultyObjects,
     diff_objects_iter: Iter<'static, RefCount<TaikoDifficultyObject>>,
     skills: TaikoSkills,
     total_hits: usize,
+    judgement_times: JudgementTimes,
     first_combos: FirstTwoCombos,
+    checkpoints: Checkpoints<TaikoGradualState>,
 }
 
+type TaikoGradualState = (
+    TaikoDifficultyAttributes,
+    TaikoSkills,
+    Iter<'static, RefCount<TaikoDifficultyObject>>,
+);
+
 #[derive(Copy, Clone, Debug)]
 enum FirstTwoCombos {
     None,
@@ -117,34 +130,140 @@ impl TaikoGradualDifficulty {
             ..Default::default()
         };
 
-        let total_hits = map.hit_objects.iter().filter(|h| h.is_circle()).count();
+        let hit_times: Vec<f64> = map
+            .hit_objects
+            .iter()
+            .filter(|h| h.is_circle())
+            .map(|h| h.start_time)
+            .collect();
+
+        let total_hits = hit_times.len();
 
         let diff_objects_iter = extend_lifetime(diff_objects.iter());
+        let checkpoints =
+            Checkpoints::new((attrs.clone(), skills.clone(), diff_objects_iter.clone()));
 
         Ok(Self {
             idx: 0,
+            map_hash: StateHeader::map_hash(&map),
             difficulty,
             diff_objects,
             diff_objects_iter,
             skills,
             attrs,
             total_hits,
+            judgement_times: hit_times.into_iter().collect(),
             first_combos,
+            checkpoints,
         })
     }
-}
 
-fn extend_lifetime(
-    iter: Iter<'_, RefCount<TaikoDifficultyObject>>,
-) -> Iter<'static, RefCount<TaikoDifficultyObject>> {
-    // SAFETY: The underlying data will never be moved.
-    unsafe { mem::transmute(iter) }
-}
+    /// Take a snapshot of the calculator's state every `interval` processed
+    /// hit objects so that [`TaikoGradualDifficulty::seek`] can rewind
+    /// without starting over.
+    ///
+    /// Snapshots are disabled by default, i.e. an interval of `0`. Each
+    /// snapshot stores the skills' strain history so smaller intervals trade
+    /// memory for faster seeking.
+    #[must_use]
+    pub const fn checkpoint_interval(mut self, interval: usize) -> Self {
+        self.checkpoints.set_interval(interval);
+
+        self
+    }
 
-impl Iterator for TaikoGradualDifficulty {
-    type Item = TaikoDifficultyAttributes;
+    /// Move to the state after processing `idx` hit objects so that the next
+    /// call of [`Iterator::next`] processes the hit object at index `idx`.
+    ///
+    /// Seeking backwards resumes from the latest snapshot at or before `idx`,
+    /// see [`TaikoGradualDifficulty::checkpoint_interval`].
+    pub fn seek(&mut self, idx: usize) {
+        if let Some((n, (attrs, skills, iter))) = self.checkpoints.restore(self.idx, idx) {
+            self.idx = n;
+            self.attrs = attrs;
+            self.skills = skills;
+            self.diff_objects_iter = iter;
+        }
 
-    fn next(&mut self) -> Option<Self::Item> {
+        while self.idx < idx && self.process_next().is_some() {}
+    }
+
+    /// Process all hit objects that are judged at or before `time`, i.e. the
+    /// current playback position in milliseconds, and return the attributes
+    /// at that moment.
+    ///
+    /// Only circles are considered, drum rolls and spinners are skipped.
+    /// Processing includes every hit object up to the last one that is judged at
+    /// or before `time`. Already processed hit objects are never rewound, see
+    /// [`TaikoGradualDifficulty::seek`].
+    ///
+    /// Returns `None` if no hit object has been processed yet.
+    pub fn advance_to_time(&mut self, time: f64) -> Option<TaikoDifficultyAttributes> {
+        while self.judgement_times.is_judged(self.idx, time) {
+            self.process_next()?;
+        }
+
+        (self.idx > 0).then(|| self.eval())
+    }
+
+    /// Serialize the current state, i.e. the amount of processed hit objects,
+    /// the partial attributes, and the skills' strain history.
+    ///
+    /// The state can be resumed through [`TaikoGradualDifficulty::resume`],
+    /// e.g. in another process. Snapshots of
+    /// [`TaikoGradualDifficulty::checkpoint_interval`] are not included.
+    pub fn save_state(&self) -> Vec<u8> {
+        let processed = self.diff_objects.objects.len() - self.diff_objects_iter.len();
+        let mut writer = StateWriter::new(self.state_header(), self.idx);
+
+        writer
+            .write(&processed)
+            .write(&self.attrs.max_combo)
+            .write(&self.skills);
+
+        writer.finish()
+    }
+
+    /// Resume a state that was serialized through
+    /// [`TaikoGradualDifficulty::save_state`].
+    ///
+    /// The difficulty objects are rebuilt from the map so `difficulty` and
+    /// `map` must be the same as for the serialized calculator.
+    pub fn resume(
+        difficulty: Difficulty,
+        map: &Beatmap,
+        state: &[u8],
+    ) -> Result<Self, ResumeError> {
+        let mut this = Self::new(difficulty, map)?;
+        let mut reader = StateReader::new(state, this.state_header())?;
+
+        let processed: usize = reader.read()?;
+
+        this.diff_objects_iter = this
+            .diff_objects_iter
+            .as_slice()
+            .get(processed..)
+            .ok_or(ResumeError::Invalid)?
+            .iter();
+        this.attrs.max_combo = reader.read()?;
+        this.skills = reader.read()?;
+        this.idx = reader.idx();
+        reader.finish()?;
+
+        Ok(this)
+    }
+
+    fn state_header(&self) -> StateHeader {
+        StateHeader::new(
+            GameMode::Taiko,
+            &self.difficulty,
+            self.idx + self.len(),
+            self.map_hash,
+        )
+    }
+
+    /// Process the next hit object without evaluating the skills.
+    fn process_next(&mut self) -> Option<()> {
         // The first difficulty object belongs to the third note since each
         // difficulty object requires the current, the last, and the second to
         // last note. Hence, if we're still on the first or second object, we
@@ -182,12 +301,41 @@ impl Iterator for TaikoGradualDifficulty {
 
         self.idx += 1;
 
+        self.checkpoints.save(self.idx, || {
+            (
+                self.attrs.clone(),
+                self.skills.clone(),
+                self.diff_objects_iter.clone(),
+            )
+        });
+
+        Some(())
+    }
+
+    fn eval(&self) -> TaikoDifficultyAttributes {
         let mut attrs = self.attrs.clone();
         let is_relax = self.difficulty.get_mods().rx();
 
         DifficultyValues::eval(&mut attrs, self.skills.clone(), is_relax);
 
-        Some(attrs)
+        attrs
+    }
+}
+
+fn extend_lifetime(
+    iter: Iter<'_, RefCount<TaikoDifficultyObject>>,
+) -> Iter<'static, RefCount<TaikoDifficultyObject>> {
+    // SAFETY: The underlying data will never be moved.
+    unsafe { mem::transmute(iter) }
+}
+
+impl Iterator for TaikoGradualDifficulty {
+    type Item = TaikoDifficultyAttributes;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.process_next()?;
+
+        Some(self.eval())
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -197,65 +345,10 @@ impl Iterator for TaikoGradualDifficulty {
     }
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
-        let mut take = cmp::min(n, self.len().saturating_sub(1));
-
-        // The first two notes have no difficulty object but might add to combo
-        match (take, self.idx) {
-            (_, 2..) | (0, _) => {}
-            (1, 0) => {
-                take -= 1;
-                self.idx += 1;
-
-                match self.first_combos {
-                    FirstTwoCombos::None => {}
-                    FirstTwoCombos::OnlyFirst => self.attrs.max_combo = 1,
-                    FirstTwoCombos::OnlySecond => {}
-                    FirstTwoCombos::Both => self.attrs.max_combo = 1,
-                }
-            }
-            (_, 0) => {
-                take -= 2;
-                self.idx += 2;
-
-                match self.first_combos {
-                    FirstTwoCombos::None => {}
-                    FirstTwoCombos::OnlyFirst => self.attrs.max_combo = 1,
-                    FirstTwoCombos::OnlySecond => self.attrs.max_combo = 1,
-                    FirstTwoCombos::Both => self.attrs.max_combo = 2,
-                }
-            }
-            (_, 1) => {
-                take -= 1;
-                self.idx += 1;
-
-                match self.first_combos {
-                    FirstTwoCombos::None => {}
-                    FirstTwoCombos::OnlyFirst => self.attrs.max_combo = 1,
-                    FirstTwoCombos::OnlySecond => self.attrs.max_combo = 1,
-                    FirstTwoCombos::Both => self.attrs.max_combo = 2,
-                }
-            }
-        }
+        let take = cmp::min(n, self.len().saturating_sub(1));
 
         for _ in 0..take {
-            loop {
-                let curr = self.diff_objects_iter.next()?;
-                let borrowed = curr.get();
-                self.skills.rhythm.process(&borrowed, &self.diff_objects);
-                self.skills.reading.process(&borrowed, &self.diff_objects);
-                self.skills.color.process(&borrowed, &self.diff_objects);
-                self.skills.stamina.process(&borrowed, &self.diff_objects);
-                self.skills
-                    .single_color_stamina
-                    .process(&borrowed, &self.diff_objects);
-
-                if borrowed.base_hit_type.is_hit() {
-                    self.attrs.max_combo += 1;
-                    self.idx += 1;
-
-                    break;
-                }
-            }
+            self.process_next()?;
         }
 
         self.next()
@@ -322,4 +415,73 @@ mod tests {
             assert_eq!(next_gradual, expected);
         }
     }
+
+    #[test]
+    fn seek() {
+        let map = Beatmap::from_path("./resources/1028484.osu").unwrap();
+
+        let difficulty = Difficulty::new();
+
+        let expected: Vec<_> = TaikoGradualDifficulty::new(difficulty.clone(), &map)
+            .unwrap()
+            .collect();
+
+        for interval in [0, 1, 50] {
+            let mut gradual = TaikoGradualDifficulty::new(difficulty.clone(), &map)
+                .unwrap()
+                .checkpoint_interval(interval);
+
+            for idx in [100, 30, 0, 51, 50, expected.len() - 1, 2, 99] {
+                gradual.seek(idx);
+                assert_eq!(gradual.len(), expected.len() - idx);
+                assert_eq!(gradual.next().as_ref(), expected.get(idx), "{idx}");
+            }
+
+            gradual.seek(expected.len());
+            assert!(gradual.next().is_none());
+        }
+    }
+
+    #[test]
+    fn save_and_resume() {
+        let map = Beatmap::from_path("./resources/1028484.osu").unwrap();
+
+        let difficulty = Difficulty::new().mods(64); // DT
+
+        let mut gradual = TaikoGradualDifficulty::new(difficulty.clone(), &map).unwrap();
+        let _ = gradual.nth(100);
+        let state = gradual.save_state();
+
+        let resumed = TaikoGradualDifficulty::resume(difficulty, &map, &state).unwrap();
+        assert_eq!(resumed.len(), gradual.len());
+        assert!(resumed.eq(gradual));
+    }
+
+    #[test]
+    fn advance_to_time() {
+        let map = Beatmap::from_path("./resources/1028484.osu").unwrap();
+
+        let difficulty = Difficulty::new();
+
+        let expected: Vec<_> = TaikoGradualDifficulty::new(difficulty.clone(), &map)
+            .unwrap()
+            .collect();
+
+        let mut gradual = TaikoGradualDifficulty::new(difficulty, &map).unwrap();
+        assert!(gradual.advance_to_time(f64::MIN).is_none());
+
+        let times = gradual.judgement_times.clone();
+
+        for time in [times[10], times[10] + 1.0, times[100] - 1.0, times[200]] {
+            let attrs = gradual.advance_to_time(time);
+            let idx = gradual.idx;
+
+            assert!(times[idx - 1] <= time && times[idx] > time, "{time}");
+            assert_eq!(attrs.as_ref(), expected.get(idx - 1), "{time}");
+        }
+
+        let attrs = gradual.advance_to_time(f64::MAX);
+        assert_eq!(attrs.as_ref(), expected.last());
+        assert_eq!(gradual.len(), 0);
+    }
 }
@@ -1,4 +1,4 @@
-use std::cmp;
+use std::{borrow::Cow, cmp};
 
 use rhythm::preprocessor::RhythmDifficultyPreprocessor;
 use rosu_map::section::general::GameMode;
@@ -14,17 +14,18 @@ use crate::{
         },
         object::TaikoObject,
     },
-    util::difficulty::norm,
+    util::{difficulty::norm, hint::unlikely},
     Beatmap, Difficulty, GameMods,
 };
 
 pub(crate) use self::skills::TaikoSkills;
 
-use super::{attributes::TaikoDifficultyAttributes, convert};
+use super::{attributes::TaikoDifficultyAttributes, convert, TaikoStrains};
 
 mod color;
 pub mod gradual;
 mod object;
+pub mod patterns;
 mod rhythm;
 mod skills;
 
@@ -39,12 +40,34 @@ pub fn difficulty(
     difficulty: &Difficulty,
     map: &Beatmap,
 ) -> Result<TaikoDifficultyAttributes, ConvertError> {
-    let mut map = map.convert_ref(GameMode::Taiko, difficulty.get_mods())?;
+    let map = prepare_map(difficulty, map)?;
+    let (mut attrs, skills) = attributes_and_skills(difficulty, &map);
 
-    if let Some(seed) = difficulty.get_mods().random_seed() {
-        convert::apply_random_to_beatmap(map.to_mut(), seed);
-    }
+    DifficultyValues::eval(&mut attrs, skills, difficulty.get_mods().rx());
+
+    Ok(attrs)
+}
+
+/// Calculate the difficulty attributes alongside the strains.
+pub fn difficulty_with_strains(
+    difficulty: &Difficulty,
+    map: &Beatmap,
+) -> Result<(TaikoDifficultyAttributes, TaikoStrains), ConvertError> {
+    let map = prepare_map(difficulty, map)?;
+    let (mut attrs, skills) = attributes_and_skills(difficulty, &map);
+    let strains = TaikoStrains::new(skills.clone());
+
+    DifficultyValues::eval(&mut attrs, skills, difficulty.get_mods().rx());
+
+    Ok((attrs, strains))
+}
 
+/// Process all skills and return them alongside the attributes that are
+/// known before evaluation.
+fn attributes_and_skills(
+    difficulty: &Difficulty,
+    map: &Beatmap,
+) -> (TaikoDifficultyAttributes, TaikoSkills) {
     let HitWindows {
         od_great,
         od_ok,
@@ -53,9 +76,9 @@ pub fn difficulty(
     } = map.attributes().difficulty(difficulty).hit_windows();
 
     let DifficultyValues { skills, max_combo } =
-        DifficultyValues::calculate(difficulty, &map, od_great);
+        DifficultyValues::calculate(difficulty, map, od_great);
 
-    let mut attrs = TaikoDifficultyAttributes {
+    let attrs = TaikoDifficultyAttributes {
         great_hit_window: od_great,
         ok_hit_window: od_ok.unwrap_or(0.0),
         max_combo,
@@ -63,11 +86,25 @@ pub fn difficulty(
         ..Default::default()
     };
 
-    let is_relax = difficulty.get_mods().rx();
+    (attrs, skills)
+}
 
-    DifficultyValues::eval(&mut attrs, skills, is_relax);
+/// Convert the map to osu!taiko and apply all mods that modify hit objects.
+fn prepare_map<'a>(
+    difficulty: &Difficulty,
+    map: &'a Beatmap,
+) -> Result<Cow<'a, Beatmap>, ConvertError> {
+    let mut map = map.convert_ref_cancellable(
+        GameMode::Taiko,
+        difficulty.get_mods(),
+        difficulty.get_cancellation(),
+    )?;
 
-    Ok(attrs)
+    if let Some(seed) = difficulty.get_mods().random_seed() {
+        convert::apply_random_to_beatmap(map.to_mut(), seed);
+    }
+
+    Ok(map)
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -183,7 +220,13 @@ impl DifficultyValues {
 
         let mut skills = TaikoSkills::new(great_hit_window, map.is_convert);
 
+        let cancellation = difficulty.get_cancellation();
+
         for hit_object in diff_objects.iter().take(n_diff_objects) {
+            if unlikely(cancellation.is_cancelled()) {
+                break;
+            }
+
             skills.rhythm.process(&hit_object.get(), &diff_objects);
             skills.reading.process(&hit_object.get(), &diff_objects);
             skills.color.process(&hit_object.get(), &diff_objects);
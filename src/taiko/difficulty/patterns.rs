@@ -0,0 +1,289 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    model::mode::ConvertError,
+    taiko::difficulty::{
+        color::data::repeating_hit_patterns::MAX_REPETITION_INTERVAL, object::TaikoDifficultyObject,
+    },
+    util::sync::{RefCount, Weak},
+    Beatmap, Difficulty,
+};
+
+use super::{prepare_map, DifficultyValues};
+
+/// The amount of notes at an effective BPM.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TaikoEffectiveBpm {
+    /// The effective BPM rounded to the nearest integer.
+    pub bpm: f64,
+    /// The amount of notes at this effective BPM.
+    pub notes: usize,
+}
+
+/// Summary of the colour and rhythm structures of an osu!taiko map.
+///
+/// These are the structures that the colour, rhythm, and reading skills
+/// evaluate so they help to explain high ratings in these skills. Effective
+/// BPMs are adjusted to the clock rate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaikoPatterns {
+    /// The amount of mono streaks per length, i.e. the amount of streaks of
+    /// `i + 1` consecutive notes of the same colour is stored at index `i`.
+    pub mono_streak_lengths: Vec<usize>,
+    /// The amount of alternating mono patterns, i.e. groups of consecutive
+    /// mono streaks with the same length.
+    pub n_alternating_mono_patterns: usize,
+    /// The amount of repeating hit patterns per repetition interval.
+    ///
+    /// Index `i` holds the amount of hit patterns that repeat the pattern
+    /// `i + 1` patterns before them. The last index holds the amount of
+    /// hit patterns that do not repeat any of the previous
+    /// [`MAX_REPETITION_INTERVAL`](Self::MAX_REPETITION_INTERVAL) patterns.
+    pub repetition_intervals: Vec<usize>,
+    /// The amount of notes whose interval differs from the interval of the
+    /// previous note.
+    pub rhythm_changes: usize,
+    /// The amount of groups of consecutive notes with the same interval.
+    pub n_rhythm_groups: usize,
+    /// The amount of groups of consecutive rhythm groups with the same
+    /// interval.
+    pub n_rhythm_pattern_groups: usize,
+    /// The amount of notes per effective BPM, i.e. BPM multiplied by slider
+    /// velocity, sorted by BPM.
+    pub effective_bpm: Vec<TaikoEffectiveBpm>,
+}
+
+impl TaikoPatterns {
+    /// The maximum amount of previous hit patterns that are checked for a
+    /// repetition.
+    pub const MAX_REPETITION_INTERVAL: usize = MAX_REPETITION_INTERVAL;
+
+    /// Summarize the colour and rhythm structures of a map.
+    ///
+    /// Only the mods, clock rate, and passed objects of the [`Difficulty`]
+    /// are relevant.
+    pub fn new(difficulty: &Difficulty, map: &Beatmap) -> Result<Self, ConvertError> {
+        let map = prepare_map(difficulty, map)?;
+
+        let mut max_combo = 0;
+        let mut n_diff_objects = 0;
+
+        let diff_objects = DifficultyValues::create_difficulty_objects(
+            &map,
+            difficulty.get_passed_objects() as u32,
+            difficulty.get_clock_rate(),
+            &mut max_combo,
+            &mut n_diff_objects,
+            difficulty.get_mods(),
+        );
+
+        // The first hit object has no difficulty object
+        n_diff_objects = n_diff_objects.saturating_sub(1);
+
+        let mut this = Self {
+            mono_streak_lengths: Vec::new(),
+            n_alternating_mono_patterns: 0,
+            repetition_intervals: vec![0; MAX_REPETITION_INTERVAL + 1],
+            rhythm_changes: 0,
+            n_rhythm_groups: 0,
+            n_rhythm_pattern_groups: 0,
+            effective_bpm: Vec::new(),
+        };
+
+        let mut mono_streak = None;
+        let mut alternating_mono_pattern = None;
+        let mut repeating_hit_patterns = None;
+        let mut rhythm_group = None;
+        let mut rhythm_pattern_group = None;
+        let mut effective_bpm = BTreeMap::new();
+
+        for h in diff_objects.iter().take(n_diff_objects) {
+            let h = h.get();
+
+            if !h.base_hit_type.is_hit() {
+                continue;
+            }
+
+            let color = &h.color_data;
+
+            if let Some(mono) = color.mono_streak.as_ref().and_then(Weak::upgrade) {
+                let mono = mono.get();
+
+                if is_new(&mut mono_streak, mono.first_hit_object()) {
+                    let len = mono.run_len();
+
+                    if this.mono_streak_lengths.len() < len {
+                        this.mono_streak_lengths.resize(len, 0);
+                    }
+
+                    this.mono_streak_lengths[len - 1] += 1;
+                }
+            }
+
+            if let Some(pattern) = color
+                .alternating_mono_pattern
+                .as_ref()
+                .and_then(Weak::upgrade)
+            {
+                if is_new(
+                    &mut alternating_mono_pattern,
+                    pattern.get().first_hit_object(),
+                ) {
+                    this.n_alternating_mono_patterns += 1;
+                }
+            }
+
+            if let Some(ref patterns) = color.repeating_hit_patterns {
+                let patterns = patterns.get();
+
+                if is_new(&mut repeating_hit_patterns, patterns.first_hit_object()) {
+                    let interval = patterns
+                        .repetition_interval
+                        .clamp(1, MAX_REPETITION_INTERVAL + 1);
+                    this.repetition_intervals[interval - 1] += 1;
+                }
+            }
+
+            let rhythm = &h.rhythm_data;
+
+            if (rhythm.ratio - 1.0).abs() > f64::EPSILON {
+                this.rhythm_changes += 1;
+            }
+
+            if let Some(ref group) = rhythm.same_rhythm_grouped_hit_objects {
+                if is_new(&mut rhythm_group, group.get().first_hit_object()) {
+                    this.n_rhythm_groups += 1;
+                }
+            }
+
+            if let Some(ref group) = rhythm.same_patterns_grouped_hit_objects {
+                if is_new(&mut rhythm_pattern_group, group.get().first_hit_object()) {
+                    this.n_rhythm_pattern_groups += 1;
+                }
+            }
+
+            *effective_bpm
+                .entry(h.effective_bpm.round() as u64)
+                .or_insert(0) += 1;
+        }
+
+        this.effective_bpm = effective_bpm
+            .into_iter()
+            .map(|(bpm, notes)| TaikoEffectiveBpm {
+                bpm: bpm as f64,
+                notes,
+            })
+            .collect();
+
+        Ok(this)
+    }
+
+    /// The effective BPM with the most notes.
+    pub fn most_common_effective_bpm(&self) -> Option<f64> {
+        self.effective_bpm
+            .iter()
+            .max_by_key(|entry| entry.notes)
+            .map(|entry| entry.bpm)
+    }
+}
+
+/// Whether a structure starting at the given hit object was not yet seen.
+///
+/// Notes of the same structure are consecutive so it suffices to compare with
+/// the previous structure's first hit object.
+fn is_new(prev: &mut Option<usize>, first: Option<RefCount<TaikoDifficultyObject>>) -> bool {
+    let curr = first.map(|h| h.get().idx);
+
+    if curr.is_none() || *prev == curr {
+        return false;
+    }
+
+    *prev = curr;
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use rosu_map::{
+        section::{general::GameMode, hit_objects::hit_samples::HitSoundType},
+        util::Pos,
+    };
+
+    use crate::model::{
+        beatmap::BeatmapBuilder,
+        hit_object::{HitObject, HitObjectKind},
+    };
+
+    use super::*;
+
+    #[test]
+    fn color_and_rhythm() {
+        let mut builder = BeatmapBuilder::new()
+            .mode(GameMode::Taiko)
+            .timing_point(0.0, 400.0);
+
+        // Pairs of center and rim hits, first at 1/2 and then at 1/4
+        for i in 0..32 {
+            let start_time = if i < 16 {
+                1000.0 + 200.0 * f64::from(i)
+            } else {
+                4200.0 + 100.0 * f64::from(i - 16)
+            };
+
+            let sound = if i % 4 < 2 {
+                HitSoundType::default()
+            } else {
+                HitSoundType::from(HitSoundType::CLAP)
+            };
+
+            let h = HitObject {
+                pos: Pos::default(),
+                start_time,
+                kind: HitObjectKind::Circle,
+            };
+
+            builder = builder.hit_object(h, sound);
+        }
+
+        let map = builder.build().unwrap();
+        let patterns = TaikoPatterns::new(&Difficulty::new(), &map).unwrap();
+
+        // The first streak is shortened since the first two hit objects have
+        // no difficulty object
+        assert_eq!(patterns.mono_streak_lengths, [0, 15]);
+        assert_eq!(patterns.n_alternating_mono_patterns, 1);
+        assert_eq!(patterns.rhythm_changes, 1);
+        assert_eq!(patterns.n_rhythm_groups, 2);
+        assert_eq!(
+            patterns.repetition_intervals.len(),
+            TaikoPatterns::MAX_REPETITION_INTERVAL + 1
+        );
+        assert_eq!(patterns.most_common_effective_bpm(), Some(210.0));
+    }
+
+    #[test]
+    fn real_map() {
+        let map = Beatmap::from_path("./resources/1028484.osu").unwrap();
+
+        let nomod = TaikoPatterns::new(&Difficulty::new(), &map).unwrap();
+        let dt = TaikoPatterns::new(&Difficulty::new().mods(64), &map).unwrap();
+
+        let n_streak_notes: usize = nomod
+            .mono_streak_lengths
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (i + 1) * n)
+            .sum();
+
+        let n_bpm_notes: usize = nomod.effective_bpm.iter().map(|entry| entry.notes).sum();
+
+        assert!(n_streak_notes >= n_bpm_notes);
+        assert!(nomod.n_rhythm_groups >= nomod.n_rhythm_pattern_groups);
+        assert_eq!(nomod.mono_streak_lengths, dt.mono_streak_lengths);
+
+        let bpm = nomod.most_common_effective_bpm().unwrap();
+        let dt_bpm = dt.most_common_effective_bpm().unwrap();
+        assert!((dt_bpm - 1.5 * bpm).abs() <= 1.0);
+    }
+}
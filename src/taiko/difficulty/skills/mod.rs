@@ -1,5 +1,7 @@
 use reading::Reading;
 
+use crate::util::codec::{Decode, Encode};
+
 use self::{color::Color, rhythm::Rhythm, stamina::Stamina};
 
 pub mod color;
@@ -27,3 +29,25 @@ impl TaikoSkills {
         }
     }
 }
+
+impl Encode for TaikoSkills {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.rhythm.encode(buf);
+        self.reading.encode(buf);
+        self.color.encode(buf);
+        self.stamina.encode(buf);
+        self.single_color_stamina.encode(buf);
+    }
+}
+
+impl Decode for TaikoSkills {
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        Some(Self {
+            rhythm: Rhythm::decode(bytes)?,
+            reading: Reading::decode(bytes)?,
+            color: Color::decode(bytes)?,
+            stamina: Stamina::decode(bytes)?,
+            single_color_stamina: Stamina::decode(bytes)?,
+        })
+    }
+}
@@ -1,6 +1,7 @@
 use rosu_map::section::general::GameMode;
 
 use crate::{
+    any::difficulty::cancellation::Cancellation,
     model::{
         beatmap::Beatmap,
         mode::{ConvertError, IGameMode},
@@ -10,8 +11,14 @@ use crate::{
 
 pub use self::{
     attributes::{TaikoDifficultyAttributes, TaikoPerformanceAttributes},
-    difficulty::gradual::TaikoGradualDifficulty,
-    performance::{gradual::TaikoGradualPerformance, TaikoPerformance},
+    difficulty::{
+        gradual::TaikoGradualDifficulty,
+        patterns::{TaikoEffectiveBpm, TaikoPatterns},
+    },
+    performance::{
+        gradual::{TaikoGradualPerformance, TaikoGradualStep},
+        TaikoPerformance,
+    },
     score_state::TaikoScoreState,
     strains::TaikoStrains,
 };
@@ -31,8 +38,19 @@ pub struct Taiko;
 
 impl Taiko {
     pub fn convert(map: &mut Beatmap) {
+        Self::convert_cancellable(map, &Cancellation::NONE);
+    }
+
+    pub(crate) fn convert_cancellable(map: &mut Beatmap, cancellation: &Cancellation) {
         debug_assert!(!map.is_convert && map.mode == GameMode::Osu);
-        convert::convert(map);
+        convert::convert(map, cancellation);
+    }
+
+    pub(crate) fn difficulty_with_strains(
+        difficulty: &Difficulty,
+        map: &Beatmap,
+    ) -> Result<(TaikoDifficultyAttributes, TaikoStrains), ConvertError> {
+        difficulty::difficulty_with_strains(difficulty, map)
     }
 }
 
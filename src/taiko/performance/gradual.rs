@@ -1,6 +1,12 @@
+use std::cmp;
+
 use crate::{
+    any::ResumeError,
     model::mode::ConvertError,
-    taiko::{difficulty::gradual::TaikoGradualDifficulty, TaikoScoreState},
+    taiko::{
+        difficulty::gradual::TaikoGradualDifficulty, Taiko, TaikoDifficultyAttributes,
+        TaikoScoreState,
+    },
     Beatmap, Difficulty,
 };
 
@@ -79,6 +85,9 @@ use super::TaikoPerformanceAttributes;
 /// [`nth`]: TaikoGradualPerformance::nth
 pub struct TaikoGradualPerformance {
     difficulty: TaikoGradualDifficulty,
+    map: Beatmap,
+    /// Difficulty attributes of the whole map, calculated on demand.
+    final_attrs: Option<TaikoDifficultyAttributes>,
 }
 
 impl TaikoGradualPerformance {
@@ -86,7 +95,11 @@ impl TaikoGradualPerformance {
     pub fn new(difficulty: Difficulty, map: &Beatmap) -> Result<Self, ConvertError> {
         let difficulty = TaikoGradualDifficulty::new(difficulty, map)?;
 
-        Ok(Self { difficulty })
+        Ok(Self {
+            difficulty,
+            map: map.to_owned(),
+            final_attrs: None,
+        })
     }
 
     /// Process the next hit object and calculate the performance attributes
@@ -121,6 +134,139 @@ impl TaikoGradualPerformance {
         Some(performance)
     }
 
+    /// Same as [`TaikoGradualPerformance::next`] but also provides running
+    /// estimates, see [`TaikoGradualStep`].
+    ///
+    /// `combo` is the current combo, as opposed to the maximum combo of the
+    /// score state.
+    pub fn next_step(&mut self, state: TaikoScoreState, combo: u32) -> Option<TaikoGradualStep> {
+        self.nth_step(state, combo, 0)
+    }
+
+    /// Same as [`TaikoGradualPerformance::nth`] but also provides running
+    /// estimates, see [`TaikoGradualStep`].
+    ///
+    /// `combo` is the current combo, as opposed to the maximum combo of the
+    /// score state.
+    pub fn nth_step(
+        &mut self,
+        state: TaikoScoreState,
+        combo: u32,
+        n: usize,
+    ) -> Option<TaikoGradualStep> {
+        let performance = self.nth(state, n)?;
+        let projected_pp = self.projected_pp(&state, combo, &performance.difficulty);
+
+        Some(TaikoGradualStep {
+            performance,
+            projected_pp,
+        })
+    }
+
+    /// The pp of the whole map if all remaining objects are hit at the
+    /// current accuracy without breaking combo.
+    fn projected_pp(
+        &mut self,
+        state: &TaikoScoreState,
+        combo: u32,
+        curr: &TaikoDifficultyAttributes,
+    ) -> f64 {
+        let difficulty = &self.difficulty.difficulty;
+        let map = &self.map;
+
+        let final_attrs = self.final_attrs.get_or_insert_with(|| {
+            difficulty
+                .calculate_for_mode::<Taiko>(map)
+                .expect("no conversion required")
+        });
+
+        let remaining_combo = final_attrs.max_combo.saturating_sub(curr.max_combo);
+        let max_combo = cmp::max(state.max_combo, combo + remaining_combo);
+
+        let mut performance = final_attrs
+            .clone()
+            .performance()
+            .difficulty(difficulty.clone())
+            .misses(state.misses)
+            .combo(cmp::min(max_combo, final_attrs.max_combo));
+
+        if state.total_hits() > 0 {
+            performance = performance.accuracy(state.accuracy() * 100.0);
+        }
+
+        performance.calculate().expect("no conversion required").pp
+    }
+
+    /// Process all objects that are judged at or before `time`, i.e. the
+    /// current playback position in milliseconds, and calculate the
+    /// performance attributes for the resulting score state.
+    ///
+    /// See [`TaikoGradualDifficulty::advance_to_time`].
+    #[allow(clippy::missing_panics_doc, reason = "technically false positive")]
+    pub fn advance_to_time(
+        &mut self,
+        state: TaikoScoreState,
+        time: f64,
+    ) -> Option<TaikoPerformanceAttributes> {
+        let performance = self
+            .difficulty
+            .advance_to_time(time)?
+            .performance()
+            .state(state)
+            .difficulty(self.difficulty.difficulty.clone())
+            .passed_objects(self.difficulty.idx as u32)
+            .calculate()
+            .expect("no conversion required");
+
+        Some(performance)
+    }
+
+    /// Take a snapshot of the calculator's state every `interval` processed
+    /// objects so that [`TaikoGradualPerformance::seek`] can rewind without
+    /// starting over.
+    ///
+    /// See [`TaikoGradualDifficulty::checkpoint_interval`].
+    #[must_use]
+    pub fn checkpoint_interval(mut self, interval: usize) -> Self {
+        self.difficulty = self.difficulty.checkpoint_interval(interval);
+
+        self
+    }
+
+    /// Move to the state after processing `idx` objects so that the next call
+    /// of [`TaikoGradualPerformance::next`] processes the object at index `idx`.
+    ///
+    /// See [`TaikoGradualDifficulty::seek`].
+    pub fn seek(&mut self, idx: usize) {
+        self.difficulty.seek(idx);
+    }
+
+    /// Serialize the current state so that it can be resumed through
+    /// [`TaikoGradualPerformance::resume`], e.g. in another process.
+    ///
+    /// See [`TaikoGradualDifficulty::save_state`].
+    pub fn save_state(&self) -> Vec<u8> {
+        self.difficulty.save_state()
+    }
+
+    /// Resume a state that was serialized through
+    /// [`TaikoGradualPerformance::save_state`].
+    ///
+    /// See [`TaikoGradualDifficulty::resume`].
+    pub fn resume(
+        difficulty: Difficulty,
+        map: &Beatmap,
+        state: &[u8],
+    ) -> Result<Self, ResumeError> {
+        let difficulty = TaikoGradualDifficulty::resume(difficulty, map, state)?;
+
+        Ok(Self {
+            difficulty,
+            map: map.to_owned(),
+            final_attrs: None,
+        })
+    }
+
     /// Returns the amount of remaining objects.
     #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
@@ -128,6 +274,29 @@ impl TaikoGradualPerformance {
     }
 }
 
+/// The result of a [`TaikoGradualPerformance`] step alongside running
+/// estimates of the score.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaikoGradualStep {
+    /// The performance attributes of the current score state.
+    pub performance: TaikoPerformanceAttributes,
+    /// The pp of the whole map if all remaining objects are hit at the
+    /// current accuracy without breaking combo.
+    pub projected_pp: f64,
+}
+
+impl TaikoGradualStep {
+    /// The current estimate of misses.
+    pub const fn effective_miss_count(&self) -> f64 {
+        self.performance.effective_miss_count
+    }
+
+    /// The current estimate of the unstable rate.
+    pub const fn estimated_unstable_rate(&self) -> Option<f64> {
+        self.performance.estimated_unstable_rate
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{taiko::TaikoPerformance, Beatmap};
@@ -183,4 +352,77 @@ mod tests {
             assert_eq!(next_gradual, expected);
         }
     }
+
+    #[test]
+    fn steps() {
+        let map = Beatmap::from_path("./resources/1028484.osu").unwrap();
+        let difficulty = Difficulty::new().mods(64); // DT
+
+        let mut gradual = TaikoGradualPerformance::new(difficulty.clone(), &map).unwrap();
+        let mut state = TaikoScoreState::default();
+        let mut last = None;
+
+        loop {
+            state.n300 += 1;
+            state.max_combo += 1;
+
+            let Some(step) = gradual.next_step(state, state.max_combo) else {
+                break;
+            };
+
+            assert!(step.effective_miss_count().abs() < f64::EPSILON);
+            assert_eq!(
+                step.estimated_unstable_rate(),
+                step.performance.estimated_unstable_rate
+            );
+            assert!(step.projected_pp >= step.performance.pp);
+
+            last = Some(step);
+        }
+
+        let last = last.unwrap();
+        state.n300 -= 1;
+        state.max_combo -= 1;
+
+        let expected = TaikoPerformance::new(&map)
+            .difficulty(difficulty)
+            .state(state)
+            .calculate()
+            .unwrap();
+
+        assert_eq!(last.performance, expected);
+        assert!((last.projected_pp - expected.pp).abs() < 1e-9);
+    }
+
+    #[test]
+    fn projected_pp_after_combo_break() {
+        let map = Beatmap::from_path("./resources/1028484.osu").unwrap();
+        let difficulty = Difficulty::new();
+
+        let mut gradual = TaikoGradualPerformance::new(difficulty.clone(), &map).unwrap();
+
+        // Combo broke after 200 hits and the next 49 objects were hit
+        let state = TaikoScoreState {
+            max_combo: 200,
+            n300: 249,
+            n100: 0,
+            misses: 1,
+        };
+
+        let step = gradual.nth_step(state, 49, 249).unwrap();
+
+        let final_attrs = difficulty.calculate_for_mode::<Taiko>(&map).unwrap();
+        let remaining_combo = final_attrs.max_combo - step.performance.difficulty.max_combo;
+
+        let expected = final_attrs
+            .performance()
+            .difficulty(difficulty)
+            .misses(1)
+            .combo(49 + remaining_combo)
+            .accuracy(state.accuracy() * 100.0)
+            .calculate()
+            .unwrap();
+
+        assert!((step.projected_pp - expected.pp).abs() < 1e-9);
+    }
 }
@@ -27,10 +27,32 @@ pub struct TaikoStrains {
 impl TaikoStrains {
     /// Time between two strains in ms.
     pub const SECTION_LEN: f64 = 400.0;
+
+    pub(crate) fn new(skills: TaikoSkills) -> Self {
+        let TaikoSkills {
+            rhythm,
+            reading,
+            color,
+            stamina,
+            single_color_stamina,
+        } = skills;
+
+        Self {
+            color: color.into_current_strain_peaks().into_vec(),
+            reading: reading.into_current_strain_peaks().into_vec(),
+            rhythm: rhythm.into_current_strain_peaks().into_vec(),
+            stamina: stamina.into_current_strain_peaks().into_vec(),
+            single_color_stamina: single_color_stamina.into_current_strain_peaks().into_vec(),
+        }
+    }
 }
 
 pub fn strains(difficulty: &Difficulty, map: &Beatmap) -> Result<TaikoStrains, ConvertError> {
-    let map = map.convert_ref(GameMode::Taiko, difficulty.get_mods())?;
+    let map = map.convert_ref_cancellable(
+        GameMode::Taiko,
+        difficulty.get_mods(),
+        difficulty.get_cancellation(),
+    )?;
 
     let great_hit_window = map
         .attributes()
@@ -40,19 +62,5 @@ pub fn strains(difficulty: &Difficulty, map: &Beatmap) -> Result<TaikoStrains, C
 
     let values = DifficultyValues::calculate(difficulty, &map, great_hit_window);
 
-    let TaikoSkills {
-        rhythm,
-        reading,
-        color,
-        stamina,
-        single_color_stamina,
-    } = values.skills;
-
-    Ok(TaikoStrains {
-        color: color.into_current_strain_peaks().into_vec(),
-        reading: reading.into_current_strain_peaks().into_vec(),
-        rhythm: rhythm.into_current_strain_peaks().into_vec(),
-        stamina: stamina.into_current_strain_peaks().into_vec(),
-        single_color_stamina: single_color_stamina.into_current_strain_peaks().into_vec(),
-    })
+    Ok(TaikoStrains::new(values.skills))
 }
@@ -0,0 +1,196 @@
+use super::strains_vec::StrainsVec;
+
+/// Write a value into a little-endian byte buffer.
+pub trait Encode {
+    fn encode(&self, buf: &mut Vec<u8>);
+}
+
+/// Read a value that was written through [`Encode`].
+///
+/// Returns `None` if the bytes are too short or otherwise invalid.
+pub trait Decode: Sized {
+    fn decode(bytes: &mut &[u8]) -> Option<Self>;
+}
+
+fn take<const N: usize>(bytes: &mut &[u8]) -> Option<[u8; N]> {
+    let (head, tail) = bytes.split_first_chunk::<N>()?;
+    *bytes = tail;
+
+    Some(*head)
+}
+
+macro_rules! impl_primitive {
+    ( $( $ty:ty ),* ) => {
+        $(
+            impl Encode for $ty {
+                fn encode(&self, buf: &mut Vec<u8>) {
+                    buf.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+
+            impl Decode for $ty {
+                fn decode(bytes: &mut &[u8]) -> Option<Self> {
+                    take(bytes).map(<$ty>::from_le_bytes)
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive!(u8, u32, u64, f32, f64);
+
+impl Encode for usize {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        (*self as u64).encode(buf);
+    }
+}
+
+impl Decode for usize {
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        u64::decode(bytes).and_then(|n| usize::try_from(n).ok())
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        u8::from(*self).encode(buf);
+    }
+}
+
+impl Decode for bool {
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        match u8::decode(bytes)? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.is_some().encode(buf);
+
+        if let Some(value) = self {
+            value.encode(buf);
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        if bool::decode(bytes)? {
+            T::decode(bytes).map(Some)
+        } else {
+            Some(None)
+        }
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.len().encode(buf);
+
+        for value in self {
+            value.encode(buf);
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.as_slice().encode(buf);
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        let len = usize::decode(bytes)?;
+
+        // Every value takes at least one byte so the remaining length caps
+        // the capacity for invalid input
+        let mut vec = Vec::with_capacity(len.min(bytes.len()));
+
+        for _ in 0..len {
+            vec.push(T::decode(bytes)?);
+        }
+
+        Some(vec)
+    }
+}
+
+impl<T: Encode> Encode for Box<[T]> {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        (**self).encode(buf);
+    }
+}
+
+impl<T: Decode> Decode for Box<[T]> {
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        Vec::decode(bytes).map(Vec::into_boxed_slice)
+    }
+}
+
+impl Encode for StrainsVec {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.len().encode(buf);
+
+        for value in self.iter() {
+            value.encode(buf);
+        }
+    }
+}
+
+impl Decode for StrainsVec {
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        let len = usize::decode(bytes)?;
+        let mut vec = StrainsVec::with_capacity(len.min(bytes.len()));
+
+        for _ in 0..len {
+            vec.push(f64::decode(bytes)?);
+        }
+
+        Some(vec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip<T: Encode + Decode>(value: &T) -> T {
+        let mut buf = Vec::new();
+        value.encode(&mut buf);
+
+        let mut bytes = buf.as_slice();
+        let decoded = T::decode(&mut bytes).unwrap();
+        assert!(bytes.is_empty());
+
+        decoded
+    }
+
+    #[test]
+    fn roundtrips() {
+        assert_eq!(roundtrip(&Some(1.5_f32)), Some(1.5));
+        assert_eq!(roundtrip(&None::<u32>), None);
+        assert_eq!(roundtrip(&vec![true, false]), [true, false]);
+        assert_eq!(roundtrip(&usize::MAX), usize::MAX);
+
+        let mut strains = StrainsVec::with_capacity(4);
+
+        for value in [1.0, 0.0, 0.0, 2.5] {
+            strains.push(value);
+        }
+
+        let decoded = roundtrip(&strains);
+        assert_eq!(decoded.len(), 4);
+        assert!(decoded.iter().eq(strains.iter()));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(f64::decode(&mut [0; 7].as_slice()).is_none());
+        assert!(bool::decode(&mut [2].as_slice()).is_none());
+        assert!(Vec::<u8>::decode(&mut [255; 8].as_slice()).is_none());
+    }
+}
@@ -0,0 +1,43 @@
+use std::ops::Index;
+
+/// The times at which hit objects of a gradual calculator are judged,
+/// e.g. the end time of hold notes.
+///
+/// Objects are processed in order of their start time but may be judged out
+/// of order, e.g. a note is judged while a previous hold note is still being
+/// held. To not let such a hold note block all objects after it, each entry
+/// stores the earliest judgement time of its object and all objects after it.
+/// Hence, the entries are sorted and an object needs to be processed at a
+/// given time if any object at or after its index is judged by then.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JudgementTimes(Box<[f64]>);
+
+impl JudgementTimes {
+    /// Whether the object at index `idx` needs to be processed to include
+    /// all objects that are judged at or before `time`.
+    pub fn is_judged(&self, idx: usize, time: f64) -> bool {
+        self.0.get(idx).is_some_and(|&judged| judged <= time)
+    }
+}
+
+impl FromIterator<f64> for JudgementTimes {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        let mut times: Box<[f64]> = iter.into_iter().collect();
+        let mut min = f64::INFINITY;
+
+        for time in times.iter_mut().rev() {
+            min = min.min(*time);
+            *time = min;
+        }
+
+        Self(times)
+    }
+}
+
+impl Index<usize> for JudgementTimes {
+    type Output = f64;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.0[idx]
+    }
+}
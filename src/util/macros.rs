@@ -190,6 +190,20 @@ macro_rules! define_skill {
             }
         }
 
+        impl crate::util::codec::Encode for $name {
+            fn encode(&self, _buf: &mut Vec<u8>) {
+                $( crate::util::codec::Encode::encode(&self.$field_name, _buf); )*
+            }
+        }
+
+        impl crate::util::codec::Decode for $name {
+            fn decode(_bytes: &mut &[u8]) -> Option<Self> {
+                Some(Self {
+                    $( $field_name: crate::util::codec::Decode::decode(_bytes)?, )*
+                })
+            }
+        }
+
         const _: () = {
             #[allow(unused_imports)]
             use crate::{
@@ -1,7 +1,9 @@
+pub mod codec;
 pub mod difficulty;
 pub mod float_ext;
 pub mod hint;
 pub mod interval_grouping;
+pub mod judgement_times;
 pub mod limited_queue;
 pub mod map_or_attrs;
 pub mod random;
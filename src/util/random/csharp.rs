@@ -23,6 +23,11 @@ impl Random {
     pub fn next_max(&mut self, max: i32) -> i32 {
         (self.prng.sample() * f64::from(max)) as i32
     }
+
+    // <https://github.com/dotnet/runtime/blob/15872212c29cecc8d82da4548c3060f2614665f7/src/libraries/System.Private.CoreLib/src/System/Random.CompatImpl.cs#L46>
+    pub fn next_double(&mut self) -> f64 {
+        self.prng.sample()
+    }
 }
 
 // <https://github.com/dotnet/runtime/blob/15872212c29cecc8d82da4548c3060f2614665f7/src/libraries/System.Private.CoreLib/src/System/Random.CompatImpl.cs#L256>
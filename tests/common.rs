@@ -21,6 +21,7 @@ mod paths {
     #[allow(dead_code)]
     pub const DICK: &str = "./resources/5174690.osu";
 
+    #[allow(dead_code)]
     pub const TAIKO: &str = "./resources/1028484.osu";
     pub const CATCH: &str = "./resources/2118524.osu";
     pub const MANIA: &str = "./resources/1638954.osu";
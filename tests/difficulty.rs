@@ -3,11 +3,14 @@ use std::panic::{self, UnwindSafe};
 use refx_pp::{
     catch::{Catch, CatchDifficultyAttributes},
     mania::{Mania, ManiaDifficultyAttributes},
-    osu::{Osu, OsuDifficultyAttributes},
+    osu::OsuDifficultyAttributes,
     taiko::{Taiko, TaikoDifficultyAttributes},
     Beatmap, Difficulty,
 };
 
+#[allow(unused_imports, reason = "only used on windows")]
+use refx_pp::osu::Osu;
+
 use self::common::*;
 
 mod common;
@@ -677,3 +680,145 @@ impl AssertEq for ManiaDifficultyAttributes {
         assert_eq!(*is_convert, expected.is_convert);
     }
 }
+
+/// Difficulty attributes of `map` at `rate` and of its rate-changed copy.
+fn with_rate_stars(map: &Beatmap, rate: f64) -> (f64, f64) {
+    let expected = Difficulty::new().clock_rate(rate).calculate(map).stars();
+
+    let rate_map = map.with_rate(rate);
+
+    // Specifying AR and OD manually so they're not clamped
+    let actual = Difficulty::new()
+        .ar(rate_map.ar, false)
+        .od(rate_map.od, false)
+        .calculate(&rate_map)
+        .stars();
+
+    (actual, expected)
+}
+
+#[test]
+fn with_rate() {
+    for path in [TAIKO, MANIA] {
+        let map = Beatmap::from_path(path).unwrap();
+
+        for rate in [0.75, 1.5] {
+            let (actual, expected) = with_rate_stars(&map, rate);
+
+            assert!(
+                (actual - expected).abs() < 1e-6,
+                "{path} at {rate}x: {actual} != {expected}"
+            );
+        }
+    }
+}
+
+#[test]
+fn with_rate_osu() {
+    // Slider tail leniency and similar constants don't scale with the clock
+    // rate. See the docs of `Beatmap::with_rate`.
+    let map = Beatmap::from_path(OSU).unwrap();
+
+    for rate in [0.75, 1.5] {
+        let (actual, expected) = with_rate_stars(&map, rate);
+
+        assert!(
+            (actual - expected).abs() / expected < 0.005,
+            "{rate}x: {actual} != {expected}"
+        );
+    }
+}
+
+#[test]
+fn with_rate_catch() {
+    // Hyperdashes, tiny droplets, and the movement skill of osu!catch depend
+    // on the clock rate itself so only the rate-independent parts match. See
+    // the docs of `Beatmap::with_rate`.
+    let map = Beatmap::from_path(CATCH).unwrap();
+    let nomod = Difficulty::new().calculate_for_mode::<Catch>(&map).unwrap();
+
+    for rate in [0.75, 1.5] {
+        let expected = Difficulty::new()
+            .clock_rate(rate)
+            .calculate_for_mode::<Catch>(&map)
+            .unwrap();
+
+        let rate_map = map.with_rate(rate);
+
+        let actual = Difficulty::new()
+            .ar(rate_map.ar, false)
+            .calculate_for_mode::<Catch>(&rate_map)
+            .unwrap();
+
+        assert!((actual.ar - expected.ar).abs() < 1e-6, "{rate}x");
+        assert_eq!(actual.n_fruits, expected.n_fruits, "{rate}x");
+        assert_eq!(actual.n_droplets, expected.n_droplets, "{rate}x");
+        assert_eq!(actual.stars > nomod.stars, rate > 1.0, "{rate}x");
+    }
+}
+
+#[test]
+fn calculate_many() {
+    // NM, HD, HR, DT, HDDT, HDHR, FL, EZ
+    let mods = [0, 8, 16, 64, 72, 24, 1024, 2];
+
+    for path in [OSU, TAIKO] {
+        let map = Beatmap::from_path(path).unwrap();
+        let difficulty = Difficulty::new().simulate_mod_geometry(true);
+
+        let actual = difficulty.calculate_many(&map, mods);
+        assert_eq!(actual.len(), mods.len());
+
+        for (attrs, mods) in actual.iter().zip(mods) {
+            let expected = difficulty.clone().mods(mods).calculate(&map);
+            assert_eq!(attrs, &expected, "{path} with mods {mods}");
+        }
+    }
+}
+
+#[test]
+fn calculate_many_with_strains() {
+    // NM, HD, HR, DT, HDHR
+    let mods = [0, 8, 16, 64, 24];
+
+    for path in [OSU, TAIKO, CATCH, MANIA] {
+        let map = Beatmap::from_path(path).unwrap();
+        let difficulty = Difficulty::new();
+
+        let actual = difficulty.calculate_many_with_strains(&map, mods);
+        assert_eq!(actual.len(), mods.len());
+
+        for ((attrs, strains), mods) in actual.iter().zip(mods) {
+            let difficulty = difficulty.clone().mods(mods);
+
+            assert_eq!(
+                attrs,
+                &difficulty.calculate(&map),
+                "{path} with mods {mods}"
+            );
+            assert_eq!(
+                strains,
+                &difficulty.strains(&map),
+                "{path} with mods {mods}"
+            );
+        }
+    }
+}
+
+#[test]
+fn calculate_rates() {
+    let rates = [0.5, 0.75, 1.0, 1.05, 1.5, 2.0];
+
+    for path in [OSU, TAIKO] {
+        let map = Beatmap::from_path(path).unwrap();
+        let difficulty = Difficulty::new().mods(8); // HD
+
+        let actual = difficulty.calculate_rates(&map, rates);
+        assert_eq!(actual.len(), rates.len());
+
+        for (attrs, rate) in actual.iter().zip(rates) {
+            let expected = difficulty.clone().clock_rate(rate).calculate(&map);
+            assert_eq!(attrs, &expected, "{path} at {rate}x");
+        }
+    }
+}
@@ -3,11 +3,14 @@ use std::panic::{self, UnwindSafe};
 use refx_pp::{
     catch::{CatchPerformance, CatchPerformanceAttributes},
     mania::{ManiaPerformance, ManiaPerformanceAttributes},
-    osu::{OsuPerformance, OsuPerformanceAttributes},
+    osu::OsuPerformanceAttributes,
     taiko::{TaikoPerformance, TaikoPerformanceAttributes},
     Beatmap,
 };
 
+#[allow(unused_imports, reason = "only used on windows")]
+use refx_pp::osu::OsuPerformance;
+
 use self::common::*;
 
 mod common;